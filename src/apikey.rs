@@ -0,0 +1,250 @@
+use rand::Rng;
+
+/// Alphabet of the checksum, and of the body by default.
+pub const BASE62: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+/// Characters of the checksum, which hold a CRC32 in base 62.
+pub const CHECKSUM_LENGTH: usize = 6;
+pub const MIN_LENGTH: usize = 16;
+pub const MAX_LENGTH: usize = 255;
+pub const DEFAULT_LENGTH: usize = 30;
+
+/// Format of API keys, as `acme_live_` and 30 characters of base 62 followed by the checksum.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiKey {
+    prefix: String,
+    length: usize,
+    alphabet: Vec<char>,
+}
+
+impl ApiKey {
+    pub fn new(prefix: &str, length: usize, alphabet: &str) -> Result<Self, String> {
+        Self::validate_prefix(prefix)?;
+        Self::validate_length(length)?;
+        let alphabet = Self::validate_alphabet(alphabet)?;
+        Ok(ApiKey {
+            prefix: String::from(prefix),
+            length,
+            alphabet,
+        })
+    }
+
+    /// Prefixes are ASCII letters, numbers, `_` and `-`, starting with a letter or a number.
+    pub fn validate_prefix(prefix: &str) -> Result<(), String> {
+        if !prefix.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+            return Err(format!(
+                "prefix must start with ASCII letter or number: {}",
+                prefix
+            ));
+        }
+        let invalid: String = prefix
+            .chars()
+            .filter(|c| !c.is_ascii_alphanumeric() && *c != '_' && *c != '-')
+            .collect();
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("not ASCII letter, number, _ nor -: {}", invalid))
+        }
+    }
+
+    pub fn validate_length(length: usize) -> Result<(), String> {
+        if (MIN_LENGTH..=MAX_LENGTH).contains(&length) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Needs {} to {} characters: {}",
+                MIN_LENGTH, MAX_LENGTH, length
+            ))
+        }
+    }
+
+    /// Characters of the body, which are 2 or more distinct ASCII letters and numbers.
+    pub fn validate_alphabet(alphabet: &str) -> Result<Vec<char>, String> {
+        let invalid: String = alphabet
+            .chars()
+            .filter(|c| !c.is_ascii_alphanumeric())
+            .collect();
+        if !invalid.is_empty() {
+            return Err(format!("not ASCII letter nor number: {}", invalid));
+        }
+        let mut characters: Vec<char> = alphabet.chars().collect();
+        characters.sort_unstable();
+        characters.dedup();
+        if characters.len() < 2 {
+            return Err(format!(
+                "Needs 2 or more characters in the alphabet: {}",
+                characters.len()
+            ));
+        }
+        Ok(characters)
+    }
+
+    /// Entropy of the body in bits.
+    pub fn entropy(&self) -> f64 {
+        self.length as f64 * (self.alphabet.len() as f64).log2()
+    }
+
+    pub fn generate(&self) -> String {
+        self.sample(&mut rand::thread_rng())
+    }
+
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        let mut key = self.prefix.clone();
+        for _ in 0..self.length {
+            key.push(self.alphabet[rng.gen_range(0, self.alphabet.len())]);
+        }
+        key.push_str(&checksum(&key));
+        key
+    }
+
+    /// Regex of the keys for secret scanners, bounded by word boundaries.
+    pub fn regex(&self) -> String {
+        format!(
+            r"\b{}{}{{{}}}[0-9A-Za-z]{{{}}}\b",
+            self.prefix,
+            character_class(&self.alphabet),
+            self.length,
+            CHECKSUM_LENGTH
+        )
+    }
+}
+
+/// CRC32 of `text` in `CHECKSUM_LENGTH` characters of base 62.
+pub fn checksum(text: &str) -> String {
+    let digits: Vec<char> = BASE62.chars().collect();
+    let mut crc = crc32fast::hash(text.as_bytes());
+    let mut checksum = vec!['0'; CHECKSUM_LENGTH];
+    for c in checksum.iter_mut().rev() {
+        *c = digits[(crc % 62) as usize];
+        crc /= 62;
+    }
+    checksum.into_iter().collect()
+}
+
+/// Verifies the checksum at the end of `key`, which covers the prefix and the body.
+pub fn verify(key: &str) -> Result<(), String> {
+    if !key.is_ascii() || key.len() <= CHECKSUM_LENGTH {
+        return Err(String::from("not an API key with a checksum"));
+    }
+    let (text, expected) = key.split_at(key.len() - CHECKSUM_LENGTH);
+    if checksum(text) == expected {
+        Ok(())
+    } else {
+        Err(format!("checksum mismatch: {}", expected))
+    }
+}
+
+/// Character class of sorted `characters`, which joins runs of 3 or more characters into ranges.
+fn character_class(characters: &[char]) -> String {
+    let mut class = String::from("[");
+    let mut i = 0;
+    while i < characters.len() {
+        let mut j = i;
+        while j + 1 < characters.len() && characters[j + 1] as u32 == characters[j] as u32 + 1 {
+            j += 1;
+        }
+        if j - i >= 2 {
+            class.push_str(&format!("{}-{}", characters[i], characters[j]));
+        } else {
+            class.extend(&characters[i..=j]);
+        }
+        i = j + 1;
+    }
+    class.push(']');
+    class
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn test_checksum() {
+        // Check value of CRC-32/ISO-HDLC.
+        assert_eq!(crc32fast::hash(b"123456789"), 0xCBF4_3926);
+        assert_eq!(checksum("123456789"), "3jZRME");
+        assert_eq!(checksum(""), "000000");
+    }
+
+    #[test]
+    fn test_verify() {
+        let api_key = ApiKey::new("acme_live_", DEFAULT_LENGTH, BASE62).unwrap();
+        let key = api_key.generate();
+        assert!(key.starts_with("acme_live_"));
+        assert_eq!(key.len(), 10 + DEFAULT_LENGTH + CHECKSUM_LENGTH);
+        assert_eq!(verify(&key), Ok(()));
+        // A typo in any character is caught.
+        for i in 0..key.len() {
+            let mut typo: Vec<char> = key.chars().collect();
+            typo[i] = if typo[i] == 'x' { 'y' } else { 'x' };
+            let typo: String = typo.into_iter().collect();
+            assert!(verify(&typo).is_err(), "{}", typo);
+        }
+        assert_eq!(
+            verify("abc"),
+            Err(String::from("not an API key with a checksum"))
+        );
+        assert_eq!(
+            verify("acme_live_123456789000000"),
+            Err(String::from("checksum mismatch: 000000"))
+        );
+        assert_eq!(verify("1234567893jZRME"), Ok(()));
+    }
+
+    #[test]
+    fn test_sample() {
+        let api_key = ApiKey::new("k_", 16, "ab").unwrap();
+        let key = api_key.sample(&mut StepRng::new(0, 0));
+        assert_eq!(&key[..18], "k_aaaaaaaaaaaaaaaa");
+        assert_eq!(&key[18..], checksum("k_aaaaaaaaaaaaaaaa"));
+        assert_eq!(api_key.entropy(), 16.0);
+    }
+
+    #[test]
+    fn test_regex() {
+        let api_key = ApiKey::new("acme_live_", 30, BASE62).unwrap();
+        assert_eq!(
+            api_key.regex(),
+            r"\bacme_live_[0-9A-Za-z]{30}[0-9A-Za-z]{6}\b"
+        );
+        let api_key = ApiKey::new("k-", 20, "0123456789abcdefxz").unwrap();
+        assert_eq!(api_key.regex(), r"\bk-[0-9a-fxz]{20}[0-9A-Za-z]{6}\b");
+        let api_key = ApiKey::new("k", 20, "abdf").unwrap();
+        assert_eq!(api_key.regex(), r"\bk[abdf]{20}[0-9A-Za-z]{6}\b");
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            ApiKey::new("acme live", 30, BASE62),
+            Err(String::from("not ASCII letter, number, _ nor -:  "))
+        );
+        assert_eq!(
+            ApiKey::new("", 30, BASE62),
+            Err(String::from(
+                "prefix must start with ASCII letter or number: "
+            ))
+        );
+        assert_eq!(
+            ApiKey::new("_live_", 30, BASE62),
+            Err(String::from(
+                "prefix must start with ASCII letter or number: _live_"
+            ))
+        );
+        assert_eq!(
+            ApiKey::new("k_", 8, BASE62),
+            Err(String::from("Needs 16 to 255 characters: 8"))
+        );
+        assert_eq!(
+            ApiKey::new("k_", 30, "ab+"),
+            Err(String::from("not ASCII letter nor number: +"))
+        );
+        assert_eq!(
+            ApiKey::new("k_", 30, "aaa"),
+            Err(String::from(
+                "Needs 2 or more characters in the alphabet: 1"
+            ))
+        );
+    }
+}
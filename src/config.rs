@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::generator::character::{
+    confusing, with_presets, CustomCategory, ALL_CHARACTERS, ALL_PROFILES,
+};
+use crate::generator::policy::Policy;
+
+/// Environment variable of the config file path.
+pub const ENV_CONFIG: &str = "PSWDGN_CONFIG";
+
+/// Settings of the command line, read from a JSON file such as
+/// `{"categories": [{"name": "greek", "flag": "g", "characters": "αβγ"}],
+/// "easy_profiles": {"kiosk": ["5 S", "rn m"]}}`.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Custom categories added to every policy.
+    pub categories: Vec<CustomCategory>,
+    /// User-defined easy profiles of confused character groups, whose members are separated
+    /// by spaces.
+    pub easy_profiles: BTreeMap<String, Vec<String>>,
+}
+
+impl Config {
+    /// Reads `path`, or else the file of `PSWDGN_CONFIG`, or else `pswdgn/config.json` in
+    /// `XDG_CONFIG_HOME` or `~/.config` if it exists.
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let path = match path {
+            Some(p) => PathBuf::from(p),
+            None => match std::env::var_os(ENV_CONFIG) {
+                Some(p) => PathBuf::from(p),
+                None => match Self::default_path() {
+                    Some(p) if p.exists() => p,
+                    _ => return Ok(Config::default()),
+                },
+            },
+        };
+        let json =
+            std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(&json).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn parse(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    fn default_path() -> Option<PathBuf> {
+        let dir = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(d) if !d.is_empty() => PathBuf::from(d),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(dir.join("pswdgn").join("config.json"))
+    }
+
+    /// Adds the custom categories to `policy`, and replaces the user-defined profiles in
+    /// `policy.easy_profile` with their groups in `policy.confusables`, leaving the built-in
+    /// profiles.
+    pub fn resolve(&self, policy: &mut Policy) {
+        policy.categories.extend(self.categories.iter().cloned());
+        let mut builtin = Vec::new();
+        for name in policy.easy_profile.split(',').filter(|n| !n.is_empty()) {
+            match self.easy_profiles.get(name) {
+                Some(groups) if ALL_PROFILES.iter().all(|p| p.name != name) => {
+                    policy.confusables.extend(groups.iter().cloned())
+                }
+                _ => builtin.push(name),
+            }
+        }
+        policy.easy_profile = builtin.join(",");
+    }
+
+    /// Names of the built-in and user-defined easy profiles, with the characters which each
+    /// removes from all the characters of the built-in, custom and kana categories.
+    pub fn removed(&self) -> Vec<(String, String)> {
+        let mut all: String = ALL_CHARACTERS.iter().map(|c| c.chars_all).collect();
+        for category in &with_presets(&self.categories) {
+            let mut characters: Vec<char> = category
+                .char_set()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|c| !all.contains(*c))
+                .collect();
+            characters.sort_unstable();
+            all.extend(characters);
+        }
+        let characters = all.chars().collect();
+        let removed = |groups: &[&str]| -> String {
+            let confusing = confusing(groups, &characters);
+            all.chars().filter(|c| confusing.contains(c)).collect()
+        };
+        let builtin = ALL_PROFILES
+            .iter()
+            .map(|p| (String::from(p.name), removed(p.groups)));
+        let user = self
+            .easy_profiles
+            .iter()
+            .filter(|(name, _)| ALL_PROFILES.iter().all(|p| p.name != *name))
+            .map(|(name, groups)| {
+                let groups: Vec<&str> = groups.iter().map(String::as_str).collect();
+                (name.clone(), removed(&groups))
+            });
+        builtin.chain(user).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config() -> Config {
+        Config::parse(
+            r#"{
+                "categories": [{"name": "greek", "flag": "g", "characters": "αβγορ"}],
+                "easy_profiles": {"kiosk": ["5 S", "rn m"], "ocr": ["a e"]}
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Config::parse("{}"), Ok(Config::default()));
+        assert_eq!(config().easy_profiles["kiosk"], vec!["5 S", "rn m"]);
+        assert!(Config::parse(r#"{"profiles": {}}"#).is_err());
+    }
+
+    #[test]
+    fn test_load() {
+        let path = std::env::temp_dir().join(format!("pswdgn-config-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"easy_profiles": {"kiosk": ["5 S"]}}"#).unwrap();
+        let config = Config::load(Some(&path)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.easy_profiles["kiosk"], vec!["5 S"]);
+
+        assert!(Config::load(Some(&path)).is_err());
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut policy = Policy {
+            easy_profile: String::from("kiosk,ocr,fax"),
+            ..Policy::default()
+        };
+        config().resolve(&mut policy);
+        assert_eq!(policy.easy_profile, "ocr,fax");
+        assert_eq!(policy.confusables, vec!["5 S", "rn m"]);
+        assert_eq!(policy.categories[0].name, "greek");
+    }
+
+    #[test]
+    fn test_removed() {
+        let removed = config().removed();
+        let names: Vec<&str> = removed.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "monospace",
+                "handwriting",
+                "phone",
+                "ocr",
+                "homoglyph",
+                "kiosk"
+            ]
+        );
+        assert_eq!(removed[0].1, "lIO01:`|'\";.,");
+        assert_eq!(
+            removed[4],
+            (
+                String::from("homoglyph"),
+                String::from("opορへべぺりヘベペリ")
+            )
+        );
+        assert_eq!(removed[5], (String::from("kiosk"), String::from("mS5")));
+    }
+}
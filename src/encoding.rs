@@ -0,0 +1,249 @@
+/// Alphabet of hex in lowercase.
+const HEX: &[u8; 16] = b"0123456789abcdef";
+/// Alphabet of Base32 in RFC 4648.
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+/// Alphabet of Crockford's Base32, which leaves out I, L, O and U.
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+/// Alphabet of Base58 of Bitcoin, which leaves out 0, O, I and l.
+const BASE58: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+/// Alphabet of Base64 in RFC 4648.
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+/// Alphabet of Base64 for URLs and file names in RFC 4648.
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+pub const ENCODINGS: [&str; 6] = [
+    "hex",
+    "base32",
+    "crockford",
+    "base58",
+    "base64",
+    "base64url",
+];
+
+/// Encoding of bytes in text.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    Hex,
+    Base32,
+    Crockford,
+    Base58,
+    Base64,
+    Base64Url,
+}
+
+impl Encoding {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "hex" => Ok(Encoding::Hex),
+            "base32" => Ok(Encoding::Base32),
+            "crockford" => Ok(Encoding::Crockford),
+            "base58" => Ok(Encoding::Base58),
+            "base64" => Ok(Encoding::Base64),
+            "base64url" => Ok(Encoding::Base64Url),
+            _ => Err(format!("unknown encoding: {}", name)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Hex => "hex",
+            Encoding::Base32 => "base32",
+            Encoding::Crockford => "crockford",
+            Encoding::Base58 => "base58",
+            Encoding::Base64 => "base64",
+            Encoding::Base64Url => "base64url",
+        }
+    }
+
+    /// Whether the encoding defines padding with `=`.
+    pub fn has_padding(self) -> bool {
+        match self {
+            Encoding::Base32 | Encoding::Base64 | Encoding::Base64Url => true,
+            Encoding::Hex | Encoding::Crockford | Encoding::Base58 => false,
+        }
+    }
+
+    /// Whether the encoding is padded unless specified.
+    pub fn pads_by_default(self) -> bool {
+        matches!(self, Encoding::Base32 | Encoding::Base64)
+    }
+
+    /// Text of `bytes`, padded if `padding` and the encoding has padding.
+    pub fn encode(self, bytes: &[u8], padding: bool) -> String {
+        let padding = padding && self.has_padding();
+        match self {
+            Encoding::Hex => encode_bits(bytes, HEX, 4, false),
+            Encoding::Base32 => encode_bits(bytes, BASE32, 5, padding),
+            Encoding::Crockford => encode_bits(bytes, CROCKFORD, 5, false),
+            Encoding::Base58 => base58(bytes),
+            Encoding::Base64 => encode_bits(bytes, BASE64, 6, padding),
+            Encoding::Base64Url => encode_bits(bytes, BASE64URL, 6, padding),
+        }
+    }
+}
+
+/// Text of `bytes` in `bits` bits a character, padded with `=` if `padding`.
+fn encode_bits(bytes: &[u8], alphabet: &[u8], bits: u32, padding: bool) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 8).div_ceil(bits as usize));
+    let mask = (1 << bits) - 1;
+    let mut buffer = 0u32;
+    let mut buffered = 0;
+    for byte in bytes {
+        buffer = (buffer << 8) | u32::from(*byte);
+        buffered += 8;
+        while buffered >= bits {
+            buffered -= bits;
+            encoded.push(alphabet[(buffer >> buffered) as usize & mask] as char);
+        }
+    }
+    if buffered > 0 {
+        encoded.push(alphabet[(buffer << (bits - buffered)) as usize & mask] as char);
+    }
+    if padding {
+        // Blocks are of the least common multiple of 8 bits and the bits of a character.
+        let block = match bits {
+            5 => 8,
+            6 => 4,
+            _ => 1,
+        };
+        while encoded.len() % block != 0 {
+            encoded.push('=');
+        }
+    }
+    encoded
+}
+
+/// Base58 of `bytes`, whose leading zero bytes are `1` each.
+fn base58(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    // Digits of base 58 in little endian.
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for byte in &bytes[zeros..] {
+        let mut carry = u32::from(*byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    std::iter::repeat('1')
+        .take(zeros)
+        .chain(digits.iter().rev().map(|d| BASE58[*d as usize] as char))
+        .collect()
+}
+
+/// Base32 of `bytes` without padding, as in otpauth URIs.
+pub fn base32(bytes: &[u8]) -> String {
+    Encoding::Base32.encode(bytes, false)
+}
+
+/// Bytes of Base32 `text`, in either case and with or without spaces and padding.
+pub fn decode_base32(text: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(text.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text.chars().filter(|c| *c != ' ' && *c != '=') {
+        let value = BASE32
+            .iter()
+            .position(|b| *b as char == c.to_ascii_uppercase())
+            .ok_or_else(|| format!("not Base32 character: {}", c))?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_base32() {
+        // Test vectors of RFC 4648 without padding.
+        let vectors = [
+            ("", ""),
+            ("f", "MY"),
+            ("fo", "MZXQ"),
+            ("foo", "MZXW6"),
+            ("foob", "MZXW6YQ"),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI"),
+        ];
+        for (bytes, encoded) in vectors.iter() {
+            assert_eq!(base32(bytes.as_bytes()), *encoded);
+            assert_eq!(decode_base32(encoded), Ok(bytes.as_bytes().to_vec()));
+        }
+        assert_eq!(decode_base32("mzxw 6ytb oi======"), Ok(b"foobar".to_vec()));
+        assert_eq!(
+            decode_base32("MZXW1"),
+            Err(String::from("not Base32 character: 1"))
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        // Test vectors of RFC 4648.
+        let vectors = [
+            ("", "", "", ""),
+            ("f", "66", "MY======", "Zg=="),
+            ("fo", "666f", "MZXQ====", "Zm8="),
+            ("foo", "666f6f", "MZXW6===", "Zm9v"),
+            ("foob", "666f6f62", "MZXW6YQ=", "Zm9vYg=="),
+            ("fooba", "666f6f6261", "MZXW6YTB", "Zm9vYmE="),
+            ("foobar", "666f6f626172", "MZXW6YTBOI======", "Zm9vYmFy"),
+        ];
+        for (bytes, hex, base32, base64) in vectors.iter() {
+            let bytes = bytes.as_bytes();
+            assert_eq!(Encoding::Hex.encode(bytes, true), *hex);
+            assert_eq!(Encoding::Base32.encode(bytes, true), *base32);
+            assert_eq!(Encoding::Base64.encode(bytes, true), *base64);
+            assert_eq!(
+                Encoding::Base64.encode(bytes, false),
+                base64.trim_end_matches('=')
+            );
+        }
+        assert_eq!(Encoding::Base64.encode(&[0xfb, 0xff], true), "+/8=");
+        assert_eq!(Encoding::Base64Url.encode(&[0xfb, 0xff], true), "-_8=");
+        assert_eq!(Encoding::Base64Url.encode(&[0xfb, 0xff], false), "-_8");
+        assert_eq!(Encoding::Crockford.encode(b"Hello", true), "91JPRV3F");
+        assert_eq!(Encoding::Crockford.encode(b"f", false), "CR");
+    }
+
+    #[test]
+    fn test_base58() {
+        // Test vectors of draft-msporny-base58.
+        assert_eq!(
+            Encoding::Base58.encode(b"Hello World!", false),
+            "2NEpo7TZRRrLZSi2U"
+        );
+        assert_eq!(
+            Encoding::Base58.encode(b"The quick brown fox jumps over the lazy dog.", false),
+            "USm3fpXnKG5EUBx2ndxBDMPVciP5hGey2Jh4NDv6gmeo1LkMeiKrLJUUBk6Z"
+        );
+        assert_eq!(
+            Encoding::Base58.encode(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd], false),
+            "11233QC4"
+        );
+        assert_eq!(Encoding::Base58.encode(&[0, 0], false), "11");
+        assert_eq!(Encoding::Base58.encode(&[], false), "");
+    }
+
+    #[test]
+    fn test_from_name() {
+        for name in ENCODINGS.iter() {
+            assert_eq!(Encoding::from_name(name).unwrap().name(), *name);
+        }
+        assert_eq!(
+            Encoding::from_name("base36"),
+            Err(String::from("unknown encoding: base36"))
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::io::{Read, Write};
+
+use serde_json::{json, Map, Value};
+
+use crate::config::Config;
+use crate::generator::policy::Policy;
+
+/// Terraform `external` data source protocol.
+///
+/// Reads a flat JSON object of options from `input`, and writes `{"password": ...}` to `output`.
+/// Values may be strings as Terraform sends them, or JSON numbers and booleans.
+/// The options take the custom categories and easy profiles of `config`.
+/// Errors are returned to be written to standard error with a non-zero exit status.
+pub fn run<R: Read, W: Write>(mut input: R, mut output: W, config: &Config) -> Result<(), String> {
+    let mut query = String::new();
+    input
+        .read_to_string(&mut query)
+        .map_err(|e| e.to_string())?;
+    let query = match serde_json::from_str(&query).map_err(|e| e.to_string())? {
+        Value::Object(q) => q,
+        _ => return Err(String::from("Query is not JSON object")),
+    };
+
+    let mut policy = policy(&query)?;
+    config.resolve(&mut policy);
+    let generator = policy.generator()?;
+    let result = json!({ "password": generator.generate() });
+    writeln!(output, "{}", result).map_err(|e| e.to_string())
+}
+
+fn policy(query: &Map<String, Value>) -> Result<Policy, String> {
+    let mut policy = Policy::default();
+    for (key, value) in query {
+        let value = match value {
+            Value::String(s) if s.is_empty() => continue,
+            Value::String(s) => s.clone(),
+            Value::Number(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::Null => continue,
+            _ => return Err(format!("Not flat value: {}", key)),
+        };
+        policy.set_option(key, &value)?;
+    }
+    Ok(policy)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn query(s: &str) -> Map<String, Value> {
+        serde_json::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_run() {
+        let mut output = Vec::new();
+        run(
+            r#"{"length": "24", "usable": "lun", "easy": "true"}"#.as_bytes(),
+            &mut output,
+            &Config::default(),
+        )
+        .unwrap();
+        let result: Map<String, Value> = serde_json::from_slice(&output).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result["password"].as_str().unwrap().len(), 24);
+
+        assert_eq!(
+            run(
+                r#"{"length": "7"}"#.as_bytes(),
+                Vec::new(),
+                &Config::default()
+            ),
+            Err(String::from("Needs 8 or more: 7"))
+        );
+        assert_eq!(
+            run("[]".as_bytes(), Vec::new(), &Config::default()),
+            Err(String::from("Query is not JSON object"))
+        );
+
+        let config = Config::parse(
+            r#"{"categories": [{"name": "greek", "flag": "g", "characters": "αβγ"}]}"#,
+        )
+        .unwrap();
+        let mut output = Vec::new();
+        run(r#"{"usable": "g"}"#.as_bytes(), &mut output, &config).unwrap();
+        let result: Map<String, Value> = serde_json::from_slice(&output).unwrap();
+        let password = result["password"].as_str().unwrap();
+        assert!(password.chars().all(|c| "αβγ".contains(c)));
+    }
+
+    #[test]
+    fn test_policy() {
+        assert_eq!(policy(&query("{}")), Ok(Policy::default()));
+        assert_eq!(
+            policy(&query(
+                r#"{"length": "12", "usable": "ln", "easy": "true", "symbols": ""}"#
+            )),
+            Ok(Policy {
+                length: 12,
+                usable: String::from("ln"),
+                is_easy: true,
+                ..Policy::default()
+            })
+        );
+        assert_eq!(
+            policy(&query(
+                r#"{"length": 12, "is_easy": false, "symbols": "!@"}"#
+            )),
+            Ok(Policy {
+                length: 12,
+                symbols: String::from("!@"),
+                ..Policy::default()
+            })
+        );
+        assert_eq!(
+            policy(&query(r#"{"length": "twelve"}"#)),
+            Err(String::from("Not number value: twelve"))
+        );
+        assert_eq!(
+            policy(&query(r#"{"easy": "yes"}"#)),
+            Err(String::from("Not boolean value: yes"))
+        );
+        assert_eq!(
+            policy(&query(r#"{"lenght": "12"}"#)),
+            Err(String::from("unknown option: lenght"))
+        );
+        assert_eq!(
+            policy(&query(r#"{"usable": ["l"]}"#)),
+            Err(String::from("Not flat value: usable"))
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::generator::character::CharSets;
+
+pub const DEFAULT_SEPARATOR: &str = "-";
+
+/// ANSI colours of the flags of the built-in categories.
+const COLORS: [(char, &str); 6] = [
+    ('l', "32"),
+    ('u', "34"),
+    ('n', "33"),
+    ('s', "31"),
+    ('h', "35"),
+    ('k', "36"),
+];
+/// ANSI colours of the custom categories in turn.
+const CUSTOM_COLORS: [&str; 6] = ["92", "94", "93", "91", "95", "96"];
+/// ANSI colour of the characters of no category.
+const OTHER_COLOR: &str = "37";
+const RESET: &str = "\x1b[0m";
+
+/// Groups of `size` grapheme clusters joined by `separator`, as `abcd-EfGh-2k9x`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grouping {
+    size: usize,
+    separator: String,
+}
+
+impl Grouping {
+    pub fn new(size: usize, separator: &str) -> Result<Self, String> {
+        if size == 0 {
+            return Err(format!("Needs 1 or more characters in a group: {}", size));
+        }
+        if separator.is_empty() {
+            return Err(String::from("separator must not be empty"));
+        }
+        Ok(Grouping {
+            size,
+            separator: String::from(separator),
+        })
+    }
+
+    pub fn format(&self, secret: &str) -> String {
+        self.groups(secret).join(&self.separator)
+    }
+
+    /// Groups of `secret` without the separators.
+    pub fn groups(&self, secret: &str) -> Vec<String> {
+        secret
+            .graphemes(true)
+            .collect::<Vec<&str>>()
+            .chunks(self.size)
+            .map(|group| group.concat())
+            .collect()
+    }
+
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+}
+
+/// `secret` with each character in the ANSI colour of its category in `usable`.
+pub(crate) fn colorize(secret: &str, usable: &CharSets) -> String {
+    let custom: Vec<char> = usable
+        .flags()
+        .chars()
+        .filter(|f| COLORS.iter().all(|(c, _)| c != f))
+        .collect();
+    let color_of = |flag: char| match COLORS.iter().find(|(f, _)| *f == flag) {
+        Some((_, color)) => color,
+        None => {
+            let i = custom.iter().position(|f| *f == flag).unwrap_or(0);
+            CUSTOM_COLORS[i % CUSTOM_COLORS.len()]
+        }
+    };
+    let mut colored = String::with_capacity(secret.len() * 6);
+    let mut current = None;
+    for grapheme in secret.graphemes(true) {
+        // Combining marks take the colour of their base character unless they compose.
+        let nfc: Vec<char> = grapheme.nfc().collect();
+        let flag = match nfc.as_slice() {
+            [c] => usable.flag_of(*c),
+            _ => None,
+        }
+        .or_else(|| usable.flag_of(grapheme.chars().next().unwrap()));
+        let color = flag.map_or(OTHER_COLOR, color_of);
+        if current != Some(color) {
+            if current.is_some() {
+                colored.push_str(RESET);
+            }
+            colored.push_str(&format!("\x1b[{}m", color));
+            current = Some(color);
+        }
+        colored.push_str(grapheme);
+    }
+    if current.is_some() {
+        colored.push_str(RESET);
+    }
+    colored
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generator::character::{with_presets, CustomCategory};
+
+    #[test]
+    fn test_format() {
+        let grouping = Grouping::new(4, DEFAULT_SEPARATOR).unwrap();
+        assert_eq!(grouping.format("abcdEfGh2k9x"), "abcd-EfGh-2k9x");
+        assert_eq!(grouping.format("abcdEfGh2k"), "abcd-EfGh-2k");
+        assert_eq!(grouping.format("abc"), "abc");
+        assert_eq!(grouping.format(""), "");
+        // A grapheme cluster is not split.
+        let grouping = Grouping::new(2, " ").unwrap();
+        assert_eq!(grouping.format("ka\u{308}ガ1"), "ka\u{308} ガ1");
+
+        assert_eq!(
+            Grouping::new(0, "-"),
+            Err(String::from("Needs 1 or more characters in a group: 0"))
+        );
+        assert_eq!(
+            Grouping::new(4, ""),
+            Err(String::from("separator must not be empty"))
+        );
+    }
+
+    #[test]
+    fn test_colorize() {
+        let usable =
+            CharSets::from_custom("lunsh", false, String::from("!"), &with_presets(&[])).unwrap();
+        assert_eq!(
+            colorize("abC1!!あ", &usable),
+            "\x1b[32mab\x1b[0m\x1b[34mC\x1b[0m\x1b[33m1\x1b[0m\x1b[31m!!\x1b[0m\x1b[35mあ\x1b[0m"
+        );
+        assert_eq!(colorize("", &usable), "");
+        // Only the colours are added.
+        let secret = "Ab3$ガe\u{301}";
+        let colored = colorize(secret, &usable);
+        let plain: String = colored
+            .split('\x1b')
+            .map(|s| s.find('m').map_or(s, |i| &s[i + 1..]))
+            .collect();
+        assert_eq!(plain, secret);
+        assert!(colored.contains("\x1b[32me\u{301}"));
+        assert!(colored.contains("\x1b[37m$ガ"));
+
+        // Katakana and each custom category have their own colours, also when composed.
+        let categories = [
+            CustomCategory {
+                name: String::from("accented"),
+                flag: 'a',
+                characters: String::from("éñ"),
+            },
+            CustomCategory {
+                name: String::from("greek"),
+                flag: 'g',
+                characters: String::from("αβ"),
+            },
+        ];
+        let categories = with_presets(&categories);
+        let usable = CharSets::from_custom("lkag", false, String::default(), &categories).unwrap();
+        assert_eq!(
+            colorize("aガe\u{301}α", &usable),
+            "\x1b[32ma\x1b[0m\x1b[36mガ\x1b[0m\x1b[92me\u{301}\x1b[0m\x1b[94mα\x1b[0m"
+        );
+    }
+}
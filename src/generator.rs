@@ -1,14 +1,24 @@
 pub(crate) mod character;
 pub mod cli;
 pub mod error;
+pub mod policy;
+mod sampler;
 
-use self::character::CharSets;
+use self::character::{
+    confusing, pages, CharSets, Context, CustomCategory, Layout, Profile, ANY, SWAPPED, SYMBOL, US,
+};
 use self::error::Error;
+use self::sampler::{Limits, Sampling, MAX_SETS};
 
-use rand::seq::SliceRandom;
+use std::collections::{HashMap, HashSet};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
 
 pub(crate) const MIN_LENGTH: i128 = 8;
-pub(crate) const MAX_LENGTH: i128 = std::u8::MAX as i128;
+pub(crate) const MAX_LENGTH: i128 = u8::MAX as i128;
+pub(crate) const MAX_REQUIRED: usize = 8;
+/// Weight of the characters typed with Shift or AltGr when shift presses are minimized.
+pub(crate) const SHIFT_WEIGHT: f64 = 0.25;
 
 #[macro_export]
 macro_rules! symbols_all {
@@ -20,6 +30,25 @@ macro_rules! symbols_all {
 pub struct Generator {
     length: usize,
     usable: CharSets,
+    constraints: Constraints,
+    sampler: Sampling,
+}
+
+/// Rules on generated passwords besides their characters.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Constraints {
+    required: Vec<char>,
+    /// Flags of the categories of the first character, `None` for any category.
+    first: Option<String>,
+    /// Flags of the categories of the last character, `None` for any category.
+    last: Option<String>,
+    limits: Limits,
+    /// Keyboard layouts on which the password is typed, empty for unknown.
+    layouts: Vec<&'static Layout>,
+    /// Whether the characters typed with Shift or AltGr are drawn less often.
+    minimize_shift: bool,
+    /// Whether the characters of each keyboard page are typed in one run.
+    mobile: bool,
 }
 
 impl Generator {
@@ -41,40 +70,414 @@ impl Generator {
         Self::new(length, CharSets::from_code(code, is_easy, symbols)?)
     }
 
+    /// Takes the custom `categories` as well as the built-in categories of `flag_str`.
+    pub fn from_custom(
+        length: usize,
+        flag_str: String,
+        is_easy: bool,
+        symbols: String,
+        categories: &[CustomCategory],
+    ) -> Result<Self, Error> {
+        Self::new(
+            length,
+            CharSets::from_custom(&flag_str, is_easy, symbols, categories)?,
+        )
+    }
+
     fn new(length: usize, usable: CharSets) -> Result<Self, Error> {
         Self::validate_length(length as i128)?;
+        Self::build(length, usable, Constraints::default())
+    }
+
+    fn build(length: usize, usable: CharSets, constraints: Constraints) -> Result<Self, Error> {
+        let required = &constraints.required;
+        let characters = usable.characters();
+        let unusable: String = required
+            .iter()
+            .filter(|c| !characters.contains(c))
+            .collect();
+        if !unusable.is_empty() {
+            return Err(Error::NotUsableErr(unusable));
+        }
+        if required.len() > MAX_REQUIRED {
+            return Err(Error::RequireExcessErr(required.len()));
+        }
+
+        let mut alphabets = vec![Self::alphabet(characters); length];
+        if let Some(flags) = &constraints.first {
+            alphabets[0] = Self::alphabet(usable.characters_of(flags));
+        }
+        if let Some(flags) = &constraints.last {
+            alphabets[length - 1] = Self::alphabet(usable.characters_of(flags));
+        }
+        // A character set is intersected by the required characters in it.
+        let mut sets: Vec<HashSet<char>> = usable
+            .char_sets()
+            .iter()
+            .filter(|s| !required.iter().any(|c| s.contains(c)))
+            .cloned()
+            .collect();
+        sets.extend(required.iter().map(|c| std::iter::once(*c).collect()));
+        if sets.len() > MAX_SETS {
+            return Err(Error::ComplexityErr(()));
+        }
+        let mut weights = HashMap::new();
+        if constraints.minimize_shift {
+            let us = [&US];
+            let layouts = match constraints.layouts.as_slice() {
+                [] => &us[..],
+                l => l,
+            };
+            for c in usable.characters() {
+                if layouts.iter().any(|l| l.shifted.contains(c)) {
+                    weights.insert(c, SHIFT_WEIGHT);
+                }
+            }
+        }
+        let pages = if constraints.mobile {
+            Some(pages(&usable.characters()))
+        } else {
+            None
+        };
+        let sampler = Sampling::new(
+            &alphabets,
+            &sets,
+            usable.char_sets(),
+            constraints.limits,
+            &weights,
+            pages.as_deref(),
+        )?;
+        if sampler.is_empty() {
+            return Err(Error::InfeasibleErr(()));
+        }
+
         Ok(Generator {
-            length: length,
-            usable: usable,
+            length,
+            usable,
+            constraints,
+            sampler,
         })
     }
 
-    pub fn generate(self: &Self) -> String {
-        let mut generated: String;
-        loop {
-            generated = self.generate_str();
-            if self.usable.exists_intersection(&generated) {
-                break;
+    fn alphabet(characters: HashSet<char>) -> Vec<char> {
+        let mut alphabet: Vec<char> = characters.into_iter().collect();
+        alphabet.sort_unstable();
+        alphabet
+    }
+
+    /// Removes `characters` from every category.
+    pub fn exclude(self, characters: &str) -> Result<Self, Error> {
+        let characters: String = characters.nfc().collect();
+        Self::build(
+            self.length,
+            self.usable.exclude(&characters)?,
+            self.constraints,
+        )
+    }
+
+    /// Excludes the characters which need escaping in any of the comma separated `contexts`.
+    pub fn safe_for(self, contexts: &str) -> Result<Self, Error> {
+        let mut characters = String::new();
+        for name in contexts.split(',').filter(|n| !n.is_empty()) {
+            characters.push_str(Context::from_name(name)?.unsafe_chars);
+        }
+        self.exclude(&characters)
+    }
+
+    /// Excludes the symbols which are not typed without dead keys on every one of the comma
+    /// separated keyboard `layouts`.
+    /// `any` is all the layouts, and also excludes the letters on different keys among them.
+    pub fn layout(self, layouts: &str) -> Result<Self, Error> {
+        let selected = Layout::from_names(layouts)?;
+        let mut characters: String = crate::symbols_all!()
+            .chars()
+            .filter(|c| !selected.iter().all(|l| l.types(*c)))
+            .collect();
+        if layouts.split(',').any(|n| n == ANY) {
+            characters.push_str(SWAPPED);
+        }
+        let mut constraints = self.constraints;
+        constraints.layouts = selected;
+        Self::build(self.length, self.usable.exclude(&characters)?, constraints)
+    }
+
+    /// Draws the characters typed with Shift or AltGr on any of the layouts, or on US without
+    /// layouts, a quarter as often as the others.
+    /// Passwords are no longer uniform, and the entropy is their Shannon entropy.
+    pub fn minimize_shift(self) -> Result<Self, Error> {
+        let mut constraints = self.constraints;
+        constraints.minimize_shift = true;
+        Self::build(self.length, self.usable, constraints)
+    }
+
+    /// Types the characters of each page of phone keyboards, such as letters, numbers and
+    /// symbols, in one run, so that the keyboard switches to each page only once.
+    /// The order and the lengths of the runs are random, and every such password is equally
+    /// likely.
+    pub fn mobile(self) -> Result<Self, Error> {
+        let mut constraints = self.constraints;
+        constraints.mobile = true;
+        Self::build(self.length, self.usable, constraints)
+    }
+
+    /// Excludes the characters which are confused in any of the comma separated easy `profiles`.
+    pub fn easy_profile(self, profiles: &str) -> Result<Self, Error> {
+        let mut groups = Vec::new();
+        for name in profiles.split(',').filter(|n| !n.is_empty()) {
+            groups.extend_from_slice(Profile::from_name(name)?.groups);
+        }
+        self.confusables(&groups)
+    }
+
+    /// Excludes the characters which are confused in `groups` of members separated by spaces,
+    /// such as `5 S` or `rn m`.
+    /// A group is ignored unless two or more of its members are usable.
+    pub fn confusables<S: AsRef<str>>(self, groups: &[S]) -> Result<Self, Error> {
+        let characters: String = confusing(groups, &self.usable.characters())
+            .into_iter()
+            .collect();
+        self.exclude(&characters)
+    }
+
+    /// Makes every character of `characters` appear in generated passwords.
+    pub fn require(self, characters: &str) -> Result<Self, Error> {
+        let mut constraints = self.constraints;
+        for c in characters.nfc() {
+            if !constraints.required.contains(&c) {
+                constraints.required.push(c);
             }
         }
-        generated
+        Self::build(self.length, self.usable, constraints)
+    }
+
+    /// Takes the first character from the categories of `flags`.
+    /// Repeated calls narrow the categories.
+    pub fn first(self, flags: &str) -> Result<Self, Error> {
+        let mut constraints = self.constraints;
+        constraints.first = Some(Self::narrow(constraints.first, flags));
+        Self::build(self.length, self.usable, constraints)
     }
 
-    fn generate_str(self: &Self) -> String {
-        let characters = self
+    /// Takes the last character from the categories of `flags`.
+    /// Repeated calls narrow the categories.
+    pub fn last(self, flags: &str) -> Result<Self, Error> {
+        let mut constraints = self.constraints;
+        constraints.last = Some(Self::narrow(constraints.last, flags));
+        Self::build(self.length, self.usable, constraints)
+    }
+
+    /// Never starts generated passwords with a symbol.
+    pub fn no_leading_symbol(self) -> Result<Self, Error> {
+        let flags: String = self
             .usable
-            .characters()
+            .flags()
+            .chars()
+            .filter(|f| *f != SYMBOL.flag)
+            .collect();
+        self.first(&flags)
+    }
+
+    /// Allows `n` or less same consecutive characters. 0 is no limit.
+    pub fn max_repeat(self, n: usize) -> Result<Self, Error> {
+        let mut constraints = self.constraints;
+        constraints.limits.max_repeat = n;
+        Self::build(self.length, self.usable, constraints)
+    }
+
+    /// Allows ascending or descending sequences such as `abc` up to `n` characters.
+    /// 0 is no limit.
+    pub fn max_sequence(self, n: usize) -> Result<Self, Error> {
+        let mut constraints = self.constraints;
+        constraints.limits.max_sequence = n;
+        Self::build(self.length, self.usable, constraints)
+    }
+
+    /// Allows `n` or less consecutive characters of the same category. 0 is no limit.
+    pub fn max_class_repeat(self, n: usize) -> Result<Self, Error> {
+        let mut constraints = self.constraints;
+        constraints.limits.max_class_repeat = n;
+        Self::build(self.length, self.usable, constraints)
+    }
+
+    /// Allows `n` or less bytes in UTF-8. 0 is no limit.
+    pub fn max_bytes(self, n: usize) -> Result<Self, Error> {
+        let mut constraints = self.constraints;
+        constraints.limits.max_bytes = n;
+        Self::build(self.length, self.usable, constraints)
+    }
+
+    /// Never uses a character twice.
+    pub fn no_duplicate_chars(self) -> Result<Self, Error> {
+        let mut constraints = self.constraints;
+        constraints.limits.unique = true;
+        Self::build(self.length, self.usable, constraints)
+    }
+
+    fn narrow(flags: Option<String>, other: &str) -> String {
+        match flags {
+            Some(f) => f.chars().filter(|c| other.contains(*c)).collect(),
+            None => String::from(other),
+        }
+    }
+
+    pub fn generate(&self) -> String {
+        self.sampler.sample(&mut rand::thread_rng())
+    }
+
+    /// `password` with each character in the ANSI colour of its category.
+    pub fn colorize(&self, password: &str) -> String {
+        crate::format::colorize(password, &self.usable)
+    }
+
+    /// Bits of entropy of a generated password.
+    pub fn entropy(&self) -> f64 {
+        self.sampler.entropy()
+    }
+
+    /// Bits of entropy of a password drawn uniformly from the same characters and constraints
+    /// without the mobile mode nor shift weighting, which is the entropy of this generator
+    /// unless they are set.
+    pub fn uniform_entropy(&self) -> Result<f64, Error> {
+        if !self.constraints.mobile && !self.constraints.minimize_shift {
+            return Ok(self.entropy());
+        }
+        let mut constraints = self.constraints.clone();
+        constraints.mobile = false;
+        constraints.minimize_shift = false;
+        Ok(Self::build(self.length, self.usable.clone(), constraints)?.entropy())
+    }
+
+    /// Checks that `password` satisfies the rules of generated passwords.
+    /// The length of the generator is the minimum length of `password`, counted in grapheme
+    /// clusters of `password` in NFC.
+    pub fn check(&self, password: &str) -> Result<(), Error> {
+        let nfc: String = password.nfc().collect();
+        let checked = self.check_normalized(&nfc);
+        // Halfwidth katakana and fullwidth Latin typed by IME are compatible with the
+        // usable characters.
+        let nfkc: String = password.nfkc().collect();
+        if checked.is_err() && nfkc != nfc && self.check_normalized(&nfkc).is_ok() {
+            return Ok(());
+        }
+        checked
+    }
+
+    fn check_normalized(&self, password: &str) -> Result<(), Error> {
+        let length = password.graphemes(true).count() as i128;
+        if length < self.length as i128 {
+            return Err(Error::LengthInsufficientErr(length));
+        }
+        Self::validate_length(length)?;
+        let max_bytes = self.constraints.limits.max_bytes;
+        if max_bytes > 0 && password.len() > max_bytes {
+            return Err(Error::ByteExcessErr(password.len()));
+        }
+
+        let characters = self.usable.characters();
+        let unusable: String = password
+            .chars()
+            .filter(|c| !characters.contains(c))
+            .collect();
+        let missing: String = self
+            .constraints
+            .required
             .iter()
-            .map(|c| *c)
-            .collect::<Vec<char>>();
-        let mut rng = rand::thread_rng();
-        let mut s = String::with_capacity(self.length);
+            .filter(|c| !password.contains(**c))
+            .collect();
+        let first = password.chars().next().unwrap();
+        let last = password.chars().last().unwrap();
+        if !unusable.is_empty() {
+            Err(Error::UnusableCharacterErr(unusable))
+        } else if !self.allows(&self.constraints.first, first) {
+            Err(Error::FirstCharacterErr(first.to_string()))
+        } else if !self.allows(&self.constraints.last, last) {
+            Err(Error::LastCharacterErr(last.to_string()))
+        } else if !missing.is_empty() {
+            Err(Error::RequireMissingErr(missing))
+        } else if !self.usable.exists_intersection(password) {
+            Err(Error::CategoryMissingErr(()))
+        } else {
+            self.check_limits(password)
+        }
+    }
 
-        for _ in 0..self.length {
-            let c = characters.choose(&mut rng).unwrap();
-            s.push(*c);
+    fn check_limits(&self, password: &str) -> Result<(), Error> {
+        let limits = &self.constraints.limits;
+        let chars: Vec<char> = password.chars().collect();
+        let class = |c: char| self.usable.char_sets().iter().position(|s| s.contains(&c));
+        if let Some(run) = Self::run_over(&chars, limits.max_repeat, |a, b| a == b) {
+            return Err(Error::RepeatExcessErr(run));
+        }
+        let up = Self::run_over(&chars, limits.max_sequence, |a, b| b as u32 == a as u32 + 1);
+        let down = Self::run_over(&chars, limits.max_sequence, |a, b| a as u32 == b as u32 + 1);
+        if let Some(run) = up.or(down) {
+            return Err(Error::SequenceExcessErr(run));
+        }
+        if let Some(run) =
+            Self::run_over(&chars, limits.max_class_repeat, |a, b| class(a) == class(b))
+        {
+            return Err(Error::ClassRepeatExcessErr(run));
+        }
+        let mut duplicates: Vec<char> = chars
+            .iter()
+            .enumerate()
+            .filter(|(i, c)| chars[..*i].contains(c))
+            .map(|(_, c)| *c)
+            .collect();
+        duplicates.sort_unstable();
+        duplicates.dedup();
+        if limits.unique && !duplicates.is_empty() {
+            return Err(Error::DuplicateErr(duplicates.into_iter().collect()));
+        }
+        if self.constraints.mobile {
+            return Self::check_pages(&chars, &pages(&self.usable.characters()));
+        }
+        Ok(())
+    }
+
+    /// Checks that `chars` switch to each of `pages` once, or returns the run which switches
+    /// back to a page left before.
+    fn check_pages(chars: &[char], pages: &[HashSet<char>]) -> Result<(), Error> {
+        let page = |c: &char| pages.iter().position(|p| p.contains(c));
+        let mut left = Vec::new();
+        for (i, c) in chars.iter().enumerate() {
+            if i > 0 && page(&chars[i - 1]) != page(c) {
+                left.push(page(&chars[i - 1]));
+                if left.contains(&page(c)) {
+                    let run = chars[i..].iter().take_while(|d| page(d) == page(c));
+                    return Err(Error::PageSwitchErr(run.collect()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The first run longer than `max` of characters each of which `follows` the previous.
+    fn run_over(
+        chars: &[char],
+        max: usize,
+        follows: impl Fn(char, char) -> bool,
+    ) -> Option<String> {
+        if max == 0 {
+            return None;
+        }
+        let mut start = 0;
+        for i in 1..=chars.len() {
+            if i == chars.len() || !follows(chars[i - 1], chars[i]) {
+                if i - start > max {
+                    return Some(chars[start..i].iter().collect());
+                }
+                start = i;
+            }
+        }
+        None
+    }
+
+    fn allows(&self, flags: &Option<String>, c: char) -> bool {
+        match flags {
+            Some(f) => self.usable.characters_of(f).contains(&c),
+            None => true,
         }
-        s
     }
 
     pub(crate) fn validate_length(length: i128) -> Result<(), error::Error> {
@@ -156,6 +559,655 @@ mod test {
         assert_ne!(result, gen.generate());
     }
 
+    #[test]
+    fn test_entropy() {
+        let gen = Generator::from_cli(10, String::from("n"), false, String::default())
+            .ok()
+            .unwrap();
+        assert!((gen.entropy() - 10f64 * 10f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_check() {
+        let gen = Generator::from_cli(10, String::from("ln"), true, String::default())
+            .ok()
+            .unwrap();
+        assert_eq!(gen.check("abcde23456"), Ok(()));
+        assert_eq!(gen.check("abcde234567"), Ok(()));
+        assert_eq!(
+            gen.check("abcde2345"),
+            Err(error::Error::LengthInsufficientErr(9))
+        );
+        assert_eq!(
+            gen.check(&"a2".repeat(128)),
+            Err(error::Error::LengthExcessErr(256))
+        );
+        assert_eq!(
+            gen.check("abcde2345l"),
+            Err(error::Error::UnusableCharacterErr(String::from("l")))
+        );
+        assert_eq!(
+            gen.check("abcdefghij"),
+            Err(error::Error::CategoryMissingErr(()))
+        );
+    }
+
+    #[test]
+    fn test_exclude() {
+        let gen = Generator::from_cli(20, String::from("ns"), false, String::from("!@#"))
+            .ok()
+            .unwrap()
+            .exclude("0123!")
+            .ok()
+            .unwrap();
+        for _ in 0..100 {
+            let result = gen.generate();
+            assert!(result.chars().all(|c| "456789@#".contains(c)));
+            assert!(gen.usable.exists_intersection(&result));
+        }
+
+        assert_eq!(
+            Generator::from_cli(8, String::from("ns"), false, String::from("!@#"))
+                .ok()
+                .unwrap()
+                .exclude("!@#")
+                .err()
+                .unwrap(),
+            error::Error::CategoryExcludedErr(String::from("s"))
+        );
+        assert_eq!(
+            Generator::from_cli(8, String::from("n"), false, String::default())
+                .ok()
+                .unwrap()
+                .require("5")
+                .ok()
+                .unwrap()
+                .exclude("5")
+                .err()
+                .unwrap(),
+            error::Error::NotUsableErr(String::from("5"))
+        );
+    }
+
+    #[test]
+    fn test_safe_for() {
+        let gen = Generator::from_cli(8, String::from("ns"), false, String::default())
+            .ok()
+            .unwrap()
+            .safe_for("url,shell")
+            .ok()
+            .unwrap();
+        for _ in 0..100 {
+            let result = gen.generate();
+            assert!(result.chars().all(|c| "0123456789_-.".contains(c)));
+        }
+
+        assert_eq!(
+            Generator::from_cli(8, String::from("ns"), false, String::from("\"\\"))
+                .ok()
+                .unwrap()
+                .safe_for("json")
+                .err()
+                .unwrap(),
+            error::Error::CategoryExcludedErr(String::from("s"))
+        );
+        assert_eq!(
+            Generator::from_cli(8, String::from("ns"), false, String::default())
+                .ok()
+                .unwrap()
+                .safe_for("url,toml")
+                .err()
+                .unwrap(),
+            error::Error::ContextErr(String::from("toml"))
+        );
+    }
+
+    #[test]
+    fn test_easy_profile() {
+        let gen = Generator::from_cli(8, String::from("un"), true, String::default())
+            .ok()
+            .unwrap()
+            .easy_profile("ocr")
+            .ok()
+            .unwrap();
+        for _ in 0..100 {
+            let result = gen.generate();
+            assert!(result.chars().all(|c| !"5S2Z8B6GD".contains(c)));
+        }
+
+        // Digits alone are confused only with 1 and 7 in handwriting.
+        let gen = Generator::from_cli(8, String::from("n"), false, String::default())
+            .ok()
+            .unwrap()
+            .easy_profile("handwriting")
+            .ok()
+            .unwrap();
+        assert!((gen.entropy() - 8.0 * 8f64.log2()).abs() < 1e-9);
+
+        let gen = Generator::from_cli(8, String::from("ln"), false, String::default())
+            .ok()
+            .unwrap()
+            .confusables(&["rn m", "a e"])
+            .ok()
+            .unwrap();
+        for _ in 0..100 {
+            let result = gen.generate();
+            assert!(result.chars().all(|c| !"mae".contains(c)));
+        }
+
+        assert_eq!(
+            Generator::from_cli(8, String::from("ln"), false, String::default())
+                .ok()
+                .unwrap()
+                .easy_profile("ocr,braille")
+                .err()
+                .unwrap(),
+            error::Error::ProfileErr(String::from("braille"))
+        );
+    }
+
+    #[test]
+    fn test_require() {
+        let gen = Generator::from_cli(8, String::from("ln"), false, String::default())
+            .ok()
+            .unwrap()
+            .require("xyz9")
+            .ok()
+            .unwrap();
+        for _ in 0..100 {
+            let result = gen.generate();
+            assert!("xyz9".chars().all(|c| result.contains(c)));
+            assert_eq!(gen.check(&result), Ok(()));
+        }
+        assert_eq!(
+            gen.check("abcd1234"),
+            Err(error::Error::RequireMissingErr(String::from("xyz9")))
+        );
+
+        // Strings of 8 digits containing 0 to 7.
+        let gen = Generator::from_cli(8, String::from("n"), false, String::default())
+            .ok()
+            .unwrap()
+            .require("01234567")
+            .ok()
+            .unwrap();
+        assert!((gen.entropy() - 40320f64.log2()).abs() < 1e-6);
+
+        assert_eq!(
+            Generator::from_cli(8, String::from("n"), true, String::default())
+                .ok()
+                .unwrap()
+                .require("1a")
+                .err()
+                .unwrap(),
+            error::Error::NotUsableErr(String::from("1a"))
+        );
+        assert_eq!(
+            Generator::from_cli(8, String::from("ln"), false, String::default())
+                .ok()
+                .unwrap()
+                .require("abcdefgh")
+                .err()
+                .unwrap(),
+            error::Error::InfeasibleErr(())
+        );
+        assert_eq!(
+            Generator::from_cli(10, String::from("l"), false, String::default())
+                .ok()
+                .unwrap()
+                .require("abcdefghi")
+                .err()
+                .unwrap(),
+            error::Error::RequireExcessErr(9)
+        );
+    }
+
+    #[test]
+    fn test_first_last() {
+        let gen = Generator::from_cli(8, String::from("lns"), false, String::from("-\\"))
+            .ok()
+            .unwrap()
+            .first("l")
+            .ok()
+            .unwrap()
+            .last("ln")
+            .ok()
+            .unwrap();
+        for _ in 0..100 {
+            let result = gen.generate();
+            assert!(result.starts_with(|c: char| c.is_ascii_lowercase()));
+            assert!(result.ends_with(|c: char| c.is_ascii_alphanumeric()));
+            assert_eq!(gen.check(&result), Ok(()));
+        }
+        assert_eq!(
+            gen.check("-abc123-"),
+            Err(error::Error::FirstCharacterErr(String::from("-")))
+        );
+        assert_eq!(
+            gen.check("abc123-\\"),
+            Err(error::Error::LastCharacterErr(String::from("\\")))
+        );
+
+        // The first letter satisfies the lower case category.
+        let gen = Generator::from_cli(8, String::from("ln"), false, String::default())
+            .ok()
+            .unwrap()
+            .first("l")
+            .ok()
+            .unwrap();
+        let count = 26.0 * (36f64.powi(7) - 26f64.powi(7));
+        assert!((gen.entropy() - count.log2()).abs() < 1e-6);
+
+        assert_eq!(
+            Generator::from_cli(8, String::from("ln"), false, String::default())
+                .ok()
+                .unwrap()
+                .first("u")
+                .err()
+                .unwrap(),
+            error::Error::InfeasibleErr(())
+        );
+    }
+
+    #[test]
+    fn test_no_leading_symbol() {
+        let gen = Generator::from_cli(8, String::from("ns"), false, String::default())
+            .ok()
+            .unwrap()
+            .no_leading_symbol()
+            .ok()
+            .unwrap();
+        for _ in 0..100 {
+            assert!(gen.generate().starts_with(|c: char| c.is_ascii_digit()));
+        }
+        assert_eq!(
+            Generator::from_cli(8, String::from("s"), false, String::default())
+                .ok()
+                .unwrap()
+                .no_leading_symbol()
+                .err()
+                .unwrap(),
+            error::Error::InfeasibleErr(())
+        );
+    }
+
+    #[test]
+    fn test_positional_sampling() {
+        // Positional constraints stay on the uniform samplers with limits.
+        let gen = || {
+            Generator::from_cli(16, String::from("luns"), false, String::default())
+                .ok()
+                .unwrap()
+                .first("lu")
+                .ok()
+                .unwrap()
+                .last("ln")
+                .ok()
+                .unwrap()
+                .no_leading_symbol()
+                .ok()
+                .unwrap()
+        };
+        let limited = gen()
+            .max_repeat(2)
+            .ok()
+            .unwrap()
+            .max_sequence(3)
+            .ok()
+            .unwrap()
+            .max_class_repeat(4)
+            .ok()
+            .unwrap();
+        assert!(matches!(limited.sampler, Sampling::Table(_)));
+        let unique = gen().no_duplicate_chars().ok().unwrap();
+        assert!(matches!(unique.sampler, Sampling::Unique(_)));
+        assert_eq!(
+            unique.max_sequence(3).err(),
+            Some(error::Error::UniqueConflictErr(()))
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let gen = Generator::from_cli(16, String::from("lun"), false, String::default())
+            .ok()
+            .unwrap()
+            .max_repeat(1)
+            .ok()
+            .unwrap()
+            .max_sequence(2)
+            .ok()
+            .unwrap()
+            .max_class_repeat(2)
+            .ok()
+            .unwrap();
+        for _ in 0..100 {
+            assert_eq!(gen.check(&gen.generate()), Ok(()));
+        }
+        assert_eq!(
+            gen.check("aaB1cD2eF3gH4iJ5"),
+            Err(error::Error::RepeatExcessErr(String::from("aa")))
+        );
+        assert_eq!(
+            gen.check("aB3210cD1eF2gH3i"),
+            Err(error::Error::SequenceExcessErr(String::from("3210")))
+        );
+        assert_eq!(
+            gen.check("abcA1bC2dE3fG4hI"),
+            Err(error::Error::SequenceExcessErr(String::from("abc")))
+        );
+        assert_eq!(
+            gen.check("aB1cD2eFHJ3hI4jK"),
+            Err(error::Error::ClassRepeatExcessErr(String::from("FHJ")))
+        );
+    }
+
+    #[test]
+    fn test_no_duplicate_chars() {
+        let gen = Generator::from_cli(20, String::from("ln"), true, String::default())
+            .ok()
+            .unwrap()
+            .no_duplicate_chars()
+            .ok()
+            .unwrap()
+            .first("l")
+            .ok()
+            .unwrap();
+        for _ in 0..100 {
+            let result = gen.generate();
+            assert_eq!(gen.check(&result), Ok(()));
+        }
+        assert_eq!(
+            gen.check("abcdefghij234567a89x"),
+            Err(error::Error::DuplicateErr(String::from("a")))
+        );
+
+        assert_eq!(
+            Generator::from_cli(40, String::from("l"), true, String::default())
+                .ok()
+                .unwrap()
+                .no_duplicate_chars()
+                .err()
+                .unwrap(),
+            error::Error::InfeasibleErr(())
+        );
+        // Characters of distinct passwords are not interchangeable with these limits.
+        let gen = || {
+            Generator::from_cli(12, String::from("lun"), false, String::default())
+                .ok()
+                .unwrap()
+                .no_duplicate_chars()
+                .ok()
+                .unwrap()
+        };
+        for limited in [
+            gen().max_sequence(2),
+            gen().max_class_repeat(2),
+            gen().minimize_shift(),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                limited.as_ref().err(),
+                Some(&error::Error::UniqueConflictErr(()))
+            );
+        }
+        // Limits which no password of the length exceeds are left out.
+        let gen = gen()
+            .max_sequence(12)
+            .ok()
+            .unwrap()
+            .max_bytes(12)
+            .ok()
+            .unwrap();
+        for _ in 0..100 {
+            let result = gen.generate();
+            assert_eq!(gen.check(&result), Ok(()));
+        }
+
+        // Permutations of 8 of 10 digits.
+        let gen = Generator::from_cli(8, String::from("n"), false, String::default())
+            .ok()
+            .unwrap()
+            .no_duplicate_chars()
+            .ok()
+            .unwrap();
+        assert!((gen.entropy() - 1814400f64.log2()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_custom() {
+        let categories = [CustomCategory {
+            name: String::from("accented"),
+            flag: 'a',
+            characters: String::from("áéíóúñ"),
+        }];
+        let gen = Generator::from_custom(
+            12,
+            String::from("la"),
+            false,
+            String::default(),
+            &categories,
+        )
+        .ok()
+        .unwrap()
+        .require("e\u{301}")
+        .ok()
+        .unwrap();
+        for _ in 0..100 {
+            let result = gen.generate();
+            assert_eq!(result.graphemes(true).count(), 12);
+            assert!(result.contains('é'));
+            assert_eq!(gen.check(&result), Ok(()));
+            // The same password typed in NFD.
+            assert_eq!(gen.check(&result.nfd().collect::<String>()), Ok(()));
+        }
+        assert_eq!(
+            gen.check("abcdefghijkl"),
+            Err(error::Error::RequireMissingErr(String::from("é")))
+        );
+        assert_eq!(
+            gen.check("ábcdéfgh"),
+            Err(error::Error::LengthInsufficientErr(8))
+        );
+    }
+
+    #[test]
+    fn test_layout() {
+        let gen = Generator::from_cli(16, String::from("luns"), false, String::default())
+            .ok()
+            .unwrap()
+            .layout("azerty,jis")
+            .ok()
+            .unwrap();
+        for _ in 0..100 {
+            let result = gen.generate();
+            assert!(!result.contains(|c| "^`~".contains(c)));
+            assert_eq!(gen.check(&result), Ok(()));
+        }
+        assert_eq!(
+            gen.check("abcdABCD1234!@#^"),
+            Err(error::Error::UnusableCharacterErr(String::from("^")))
+        );
+
+        let gen = Generator::from_cli(16, String::from("lun"), false, String::default())
+            .ok()
+            .unwrap()
+            .layout("any")
+            .ok()
+            .unwrap();
+        // 20 lower case, 20 upper case letters and 10 digits, each of which appears.
+        let count = 50f64.powi(16) - 2.0 * 30f64.powi(16) - 40f64.powi(16)
+            + 10f64.powi(16)
+            + 2.0 * 20f64.powi(16);
+        assert!((gen.entropy() - count.log2()).abs() < 1e-9);
+
+        assert_eq!(
+            Generator::from_cli(8, String::from("s"), false, String::from("^`"))
+                .ok()
+                .unwrap()
+                .layout("qwertz")
+                .err()
+                .unwrap(),
+            error::Error::CategoryExcludedErr(String::from("s"))
+        );
+        assert_eq!(
+            Generator::from_cli(8, String::from("l"), false, String::default())
+                .ok()
+                .unwrap()
+                .layout("colemak")
+                .err()
+                .unwrap(),
+            error::Error::LayoutErr(String::from("colemak"))
+        );
+    }
+
+    #[test]
+    fn test_minimize_shift() {
+        let gen = Generator::from_cli(8, String::from("lu"), false, String::default())
+            .ok()
+            .unwrap()
+            .minimize_shift()
+            .ok()
+            .unwrap();
+        // Upper case letters weigh a quarter, and every password has both cases.
+        let (lower, upper) = (26.0, 26.0 * SHIFT_WEIGHT);
+        let total: f64 = (lower + upper).powi(8) - lower.powi(8) - upper.powi(8);
+        let shifted = 8.0 * upper * (lower + upper).powi(7) - 8.0 * upper.powi(8);
+        let entropy = total.log2() - shifted / total * SHIFT_WEIGHT.log2();
+        assert!((gen.entropy() - entropy).abs() < 1e-9);
+        assert!(gen.entropy() < (52f64.powi(8) - 2.0 * 26f64.powi(8)).log2());
+
+        let mut count = 0;
+        for _ in 0..1000 {
+            let result = gen.generate();
+            assert_eq!(gen.check(&result), Ok(()));
+            count += result.chars().filter(char::is_ascii_uppercase).count();
+        }
+        let expected = shifted / total * 1000.0;
+        assert!((count as f64 - expected).abs() < expected * 0.1);
+
+        // Every digit needs Shift on AZERTY, so that the passwords are still uniform.
+        let gen = Generator::from_cli(8, String::from("n"), false, String::default())
+            .ok()
+            .unwrap()
+            .layout("azerty")
+            .ok()
+            .unwrap()
+            .minimize_shift()
+            .ok()
+            .unwrap();
+        assert!((gen.entropy() - 8.0 * 10f64.log2()).abs() < 1e-9);
+
+        assert_eq!(
+            Generator::from_cli(8, String::from("lu"), false, String::default())
+                .ok()
+                .unwrap()
+                .minimize_shift()
+                .ok()
+                .unwrap()
+                .no_duplicate_chars()
+                .err(),
+            Some(error::Error::UniqueConflictErr(()))
+        );
+    }
+
+    #[test]
+    fn test_mobile() {
+        let gen = Generator::from_cli(8, String::from("ns"), false, String::from("-#"))
+            .ok()
+            .unwrap()
+            .mobile()
+            .ok()
+            .unwrap();
+        // Digits and - are on the number page, and # on the symbol page.
+        // Without #, there are a digit and -. With #, a run of # starts or ends the password,
+        // and the rest has a digit.
+        let count = 11f64.powi(8) - 10f64.powi(8) - 1.0
+            + (1..8).map(|k| 2.0 * (11f64.powi(8 - k) - 1.0)).sum::<f64>();
+        assert!((gen.entropy() - count.log2()).abs() < 1e-9);
+        let uniform = (12f64.powi(8) - 10f64.powi(8) - 2f64.powi(8)).log2();
+        assert!((gen.uniform_entropy().unwrap() - uniform).abs() < 1e-9);
+
+        let mut starts = HashSet::new();
+        for _ in 0..1000 {
+            let result = gen.generate();
+            assert_eq!(gen.check(&result), Ok(()));
+            starts.insert(result.starts_with('#'));
+        }
+        // Runs come in either order.
+        assert_eq!(starts.len(), 2);
+        assert_eq!(gen.check("12-345##"), Ok(()));
+        assert_eq!(
+            gen.check("12#-3456"),
+            Err(error::Error::PageSwitchErr(String::from("-3456")))
+        );
+
+        let gen = Generator::from_cli(8, String::from("lns"), false, String::default())
+            .ok()
+            .unwrap();
+        let uniform = gen.entropy();
+        assert_eq!(gen.uniform_entropy(), Ok(uniform));
+        let gen = gen.mobile().ok().unwrap();
+        assert!(gen.entropy() < uniform);
+        assert_eq!(gen.uniform_entropy(), Ok(uniform));
+        assert_eq!(
+            Generator::from_cli(8, String::from("lu"), false, String::default())
+                .ok()
+                .unwrap()
+                .minimize_shift()
+                .ok()
+                .unwrap()
+                .mobile()
+                .err()
+                .unwrap(),
+            error::Error::PagesConflictErr(())
+        );
+    }
+
+    #[test]
+    fn test_max_bytes() {
+        let categories = [CustomCategory {
+            name: String::from("greek"),
+            flag: 'g',
+            characters: String::from("αβγδεζηθ"),
+        }];
+        let gen = Generator::from_custom(
+            10,
+            String::from("ng"),
+            false,
+            String::default(),
+            &categories,
+        )
+        .ok()
+        .unwrap()
+        .max_bytes(12)
+        .ok()
+        .unwrap();
+        for _ in 0..100 {
+            let result = gen.generate();
+            assert!(result.len() <= 12);
+            assert_eq!(gen.check(&result), Ok(()));
+        }
+        // 10 digits and 8 Greek letters: a Greek letter and 9 digits, or 2 and 8 digits.
+        let count = 10.0 * 8.0 * 10f64.powi(9) + 45.0 * 64.0 * 10f64.powi(8);
+        assert!((gen.entropy() - count.log2()).abs() < 1e-6);
+        assert_eq!(
+            gen.check("αβγ1234567"),
+            Err(error::Error::ByteExcessErr(13))
+        );
+
+        assert_eq!(
+            Generator::from_custom(10, String::from("g"), false, String::default(), &categories)
+                .ok()
+                .unwrap()
+                .max_bytes(19)
+                .err()
+                .unwrap(),
+            error::Error::InfeasibleErr(())
+        );
+    }
+
     #[test]
     fn test_validate_length() {
         assert_eq!(
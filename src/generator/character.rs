@@ -1,12 +1,25 @@
 mod category;
+mod confusable;
+mod context;
+mod custom;
+mod kana;
+mod layout;
+mod page;
 
-pub(crate) use self::category::Category;
+pub(crate) use self::category::{Category, ALL_CHARACTERS, SYMBOL};
+pub(crate) use self::confusable::{confusing, Profile, ALL_PROFILES, HOMOGLYPH};
+pub(crate) use self::context::Context;
+pub use self::custom::CustomCategory;
+pub(crate) use self::kana::with_presets;
+pub(crate) use self::layout::{Layout, ANY, SWAPPED, US};
+pub(crate) use self::page::pages;
 use crate::generator::error::Error;
 use std::collections::HashSet;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub(crate) struct CharSets {
     char_sets: Vec<HashSet<char>>,
+    flags: Vec<char>,
 }
 
 impl CharSets {
@@ -22,25 +35,125 @@ impl CharSets {
         Ok(Self::new(Category::from_code(code)?, is_easy, symbols))
     }
 
+    /// Takes the built-in and custom categories of `flag_str`.
+    /// With `is_easy`, the custom categories leave out the letters which look the same as
+    /// other usable letters, such as Cyrillic а and Latin a.
+    pub(crate) fn from_custom(
+        flag_str: &str,
+        is_easy: bool,
+        symbols: String,
+        categories: &[CustomCategory],
+    ) -> Result<Self, Error> {
+        CustomCategory::validate(categories)?;
+        let errors: String = flag_str
+            .chars()
+            .filter(|f| {
+                ALL_CHARACTERS.iter().all(|c| c.flag != *f)
+                    && categories.iter().all(|c| c.flag != *f)
+            })
+            .collect();
+        if !errors.is_empty() {
+            return Err(Error::CategoryFlagErr(errors));
+        }
+        let builtin = ALL_CHARACTERS
+            .iter()
+            .filter(|c| flag_str.contains(c.flag))
+            .collect();
+        let mut char_sets = Self::new(builtin, is_easy, symbols);
+        let mut custom = Vec::new();
+        for category in categories.iter().filter(|c| flag_str.contains(c.flag)) {
+            custom.push((category.flag, category.char_set()?));
+        }
+        if is_easy {
+            let mut characters = char_sets.characters();
+            characters.extend(custom.iter().flat_map(|(_, c)| c.iter()));
+            let confusing = confusing(HOMOGLYPH.groups, &characters);
+            for (flag, char_set) in custom.iter_mut() {
+                char_set.retain(|c| !confusing.contains(c));
+                if char_set.is_empty() {
+                    return Err(Error::CategoryExcludedErr(flag.to_string()));
+                }
+            }
+        }
+        for (flag, char_set) in custom {
+            char_sets.flags.push(flag);
+            char_sets.char_sets.push(char_set);
+        }
+        if char_sets.char_sets.is_empty() {
+            Err(Error::CharactersErr(()))
+        } else {
+            Ok(char_sets)
+        }
+    }
+
     fn new(char_sets: Vec<&'static Category>, is_easy: bool, symbols: String) -> Self {
         CharSets {
             char_sets: char_sets
                 .iter()
                 .map(|cs| cs.char_set(is_easy, &symbols))
                 .collect(),
+            flags: char_sets.iter().map(|cs| cs.flag).collect(),
+        }
+    }
+
+    /// Removes `characters` from every character set.
+    pub(crate) fn exclude(mut self, characters: &str) -> Result<Self, Error> {
+        for c in characters.chars() {
+            for char_set in self.char_sets.iter_mut() {
+                char_set.remove(&c);
+            }
+        }
+        let emptied: String = self
+            .char_sets
+            .iter()
+            .zip(&self.flags)
+            .filter(|(c, _)| c.is_empty())
+            .map(|(_, f)| *f)
+            .collect();
+        if emptied.is_empty() {
+            Ok(self)
+        } else {
+            Err(Error::CategoryExcludedErr(emptied))
         }
     }
 
-    pub(crate) fn characters(self: &Self) -> HashSet<char> {
+    pub(crate) fn flags(&self) -> String {
+        self.flags.iter().collect()
+    }
+
+    pub(crate) fn char_sets(&self) -> &[HashSet<char>] {
+        &self.char_sets
+    }
+
+    pub(crate) fn characters(&self) -> HashSet<char> {
         self.char_sets
             .iter()
             .flat_map(|c| c.iter())
-            .map(|c| *c)
+            .copied()
             .collect()
     }
 
-    pub(crate) fn exists_intersection(self: &Self, str: &String) -> bool {
-        let c_set = str.chars().into_iter().collect();
+    /// Characters of the categories of `flags`.
+    pub(crate) fn characters_of(&self, flags: &str) -> HashSet<char> {
+        self.char_sets
+            .iter()
+            .zip(&self.flags)
+            .filter(|(_, f)| flags.contains(**f))
+            .flat_map(|(c, _)| c.iter())
+            .copied()
+            .collect()
+    }
+
+    /// Flag of the first category of `c`.
+    pub(crate) fn flag_of(&self, c: char) -> Option<char> {
+        self.char_sets
+            .iter()
+            .position(|s| s.contains(&c))
+            .map(|i| self.flags[i])
+    }
+
+    pub(crate) fn exists_intersection(&self, str: &str) -> bool {
+        let c_set = str.chars().collect();
         self.char_sets.iter().all(|c| !c.is_disjoint(&c_set))
     }
 }
@@ -48,6 +161,7 @@ impl CharSets {
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::iter::FromIterator;
 
     #[test]
     fn test_from_cli() {
@@ -81,6 +195,53 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_from_custom() {
+        let categories = [
+            CustomCategory {
+                name: String::from("greek"),
+                flag: 'g',
+                characters: String::from("αβγδεοπρ"),
+            },
+            CustomCategory {
+                name: String::from("cyrillic"),
+                flag: 'c',
+                characters: String::from("абвгде"),
+            },
+        ];
+        let cs = CharSets::from_custom("ng", false, String::default(), &categories).unwrap();
+        assert_eq!(cs.flags, vec!['n', 'g']);
+        assert_eq!(
+            cs.char_sets,
+            vec![
+                category::NUMBER.char_set(false, &String::default()),
+                HashSet::from_iter("αβγδεοπρ".chars()),
+            ]
+        );
+
+        // Greek ρ and Cyrillic а and е look the same as Latin letters,
+        // but Greek ο does not since easy Latin letters leave out o.
+        let cs = CharSets::from_custom("lgc", true, String::default(), &categories).unwrap();
+        assert_eq!(
+            cs.char_sets[1..],
+            [
+                HashSet::from_iter("αβγδεοπ".chars()),
+                HashSet::from_iter("бвгд".chars()),
+            ]
+        );
+        let cs = CharSets::from_custom("c", true, String::default(), &categories).unwrap();
+        assert_eq!(cs.char_sets, vec![HashSet::from_iter("абвгде".chars())]);
+
+        assert_eq!(
+            CharSets::from_custom("lx", false, String::default(), &categories).err(),
+            Some(Error::CategoryFlagErr(String::from("x")))
+        );
+        assert_eq!(
+            CharSets::from_custom("", false, String::default(), &categories).err(),
+            Some(Error::CharactersErr(()))
+        );
+    }
+
     #[test]
     fn test_new() {
         let cs = CharSets::new(vec![&category::LOWER], false, String::default());
@@ -90,6 +251,37 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_exclude() {
+        let cs = CharSets::new(
+            vec![&category::NUMBER, &category::SYMBOL],
+            false,
+            String::from("!@#"),
+        )
+        .exclude("1!a")
+        .ok()
+        .unwrap();
+        assert_eq!(
+            cs.char_sets,
+            vec![
+                HashSet::from_iter("023456789".chars()),
+                HashSet::from_iter("@#".chars()),
+            ]
+        );
+
+        assert_eq!(
+            CharSets::new(
+                vec![&category::NUMBER, &category::SYMBOL],
+                false,
+                String::from("!@")
+            )
+            .exclude("!@")
+            .err()
+            .unwrap(),
+            Error::CategoryExcludedErr(String::from("s"))
+        );
+    }
+
     #[test]
     fn test_characters() {
         let cs = CharSets::new(
@@ -104,6 +296,20 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_characters_of() {
+        let cs = CharSets::new(
+            vec![&category::LOWER, &category::NUMBER],
+            false,
+            String::default(),
+        );
+        assert_eq!(
+            cs.characters_of("nu"),
+            category::NUMBER.char_set(false, &String::default())
+        );
+        assert!(cs.characters_of("s").is_empty());
+    }
+
     #[test]
     fn test_exists_intersection() {
         let cs = CharSets::new(
@@ -5,8 +5,9 @@ use std::collections::HashSet;
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub(crate) struct Category {
     pub(crate) chars_all: &'static str,
-    chars_easy: &'static str,
-    flag: char,
+    pub(crate) chars_easy: &'static str,
+    pub(crate) flag: char,
+    pub(crate) description: &'static str,
     code_point: usize,
 }
 
@@ -15,6 +16,7 @@ pub(crate) const LOWER: Category = Category {
     chars_all: "abcdefghijklmnopqrstuvwxyz",
     chars_easy: "abcdefghijkmnpqrstuvwxyz",
     flag: 'l',
+    description: "Lower case alphabets.",
     code_point: 0,
 };
 
@@ -23,6 +25,7 @@ pub(crate) const UPPER: Category = Category {
     chars_all: "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
     chars_easy: "ABCDEFGHJKLMNPQRSTUVWXYZ",
     flag: 'u',
+    description: "Upper case alphabets.",
     code_point: 1,
 };
 
@@ -31,6 +34,7 @@ pub(crate) const NUMBER: Category = Category {
     chars_all: "0123456789",
     chars_easy: "23456789",
     flag: 'n',
+    description: "Numbers.",
     code_point: 2,
 };
 
@@ -39,10 +43,11 @@ pub(crate) const SYMBOL: Category = Category {
     chars_all: crate::symbols_all!(),
     chars_easy: r##"!@#$%^&*=+~"<>?"##,
     flag: 's',
+    description: "Symbols.",
     code_point: 3,
 };
 
-const ALL_CHARACTERS: [Category; 4] = [LOWER, UPPER, NUMBER, SYMBOL];
+pub(crate) const ALL_CHARACTERS: [Category; 4] = [LOWER, UPPER, NUMBER, SYMBOL];
 
 impl Category {
     pub(crate) fn from_cli(flag_str: String) -> Result<Vec<&'static Self>, Error> {
@@ -68,11 +73,11 @@ impl Category {
         ALL_CHARACTERS.iter().map(|c| c.flag).collect()
     }
 
-    fn code(self: &Self) -> usize {
+    fn code(&self) -> usize {
         1 << self.code_point
     }
 
-    pub(crate) fn char_set(self: &Self, is_easy: bool, symbols: &String) -> HashSet<char> {
+    pub(crate) fn char_set(&self, is_easy: bool, symbols: &str) -> HashSet<char> {
         let characters = if SYMBOL == *self && !symbols.is_empty() {
             symbols
         } else if is_easy {
@@ -81,28 +86,48 @@ impl Category {
             self.chars_all
         };
 
-        characters.chars().into_iter().collect()
+        characters.chars().collect()
     }
 
-    pub(crate) fn validate_flag(frag_str: &String) -> Result<(), Error> {
+    /// Sorted characters of the categories of `flag_str`, without symbols other than the
+    /// built-in ones.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn alphabet(flag_str: &str, is_easy: bool) -> Result<Vec<char>, Error> {
+        Self::validate_flag(flag_str)?;
+        let mut alphabet: Vec<char> = Self::from_cli(String::from(flag_str))?
+            .iter()
+            .flat_map(|c| c.char_set(is_easy, ""))
+            .collect();
+        alphabet.sort_unstable();
+        Ok(alphabet)
+    }
+
+    pub(crate) fn validate_flag(frag_str: &str) -> Result<(), Error> {
         let flags = Self::flags();
-        let errors: String = frag_str
+        let errors: String = frag_str.chars().filter(|f| !flags.contains(*f)).collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::CategoryFlagErr(errors))
+        }
+    }
+
+    pub(crate) fn validate_characters(characters: &str) -> Result<(), Error> {
+        let errors: String = characters
             .chars()
-            .into_iter()
-            .filter(|f| !flags.contains(*f))
+            .filter(|c| !ALL_CHARACTERS.iter().any(|cs| cs.chars_all.contains(*c)))
             .collect();
         if errors.is_empty() {
             Ok(())
         } else {
-            Err(Error::CategoryFlagErr(errors))
+            Err(Error::NotUsableErr(errors))
         }
     }
 
-    pub(crate) fn validate_symbols(symbols: &String) -> Result<(), Error> {
+    pub(crate) fn validate_symbols(symbols: &str) -> Result<(), Error> {
         let symbol_all = SYMBOL.chars_all;
         let errors: String = symbols
             .chars()
-            .into_iter()
             .filter(|s| !symbol_all.contains(*s))
             .collect();
         if errors.is_empty() {
@@ -131,6 +156,20 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_alphabet() {
+        assert_eq!(
+            Category::alphabet("n", true).unwrap(),
+            "23456789".chars().collect::<Vec<char>>()
+        );
+        assert_eq!(Category::alphabet("lun", false).unwrap().len(), 62);
+        assert_eq!(
+            Category::alphabet("lx", false),
+            Err(Error::CategoryFlagErr(String::from("x")))
+        );
+        assert_eq!(Category::alphabet("", false), Err(Error::CharactersErr(())));
+    }
+
     #[test]
     fn test_from_code() {
         assert_eq!(
@@ -199,6 +238,15 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_validate_characters() {
+        assert_eq!(Category::validate_characters("aZ9!"), Ok(()));
+        assert_eq!(
+            Category::validate_characters("a b\u{e9}"),
+            Err(Error::NotUsableErr(String::from(" \u{e9}")))
+        );
+    }
+
     #[test]
     fn test_validate_symbols() {
         assert_eq!(
@@ -0,0 +1,198 @@
+use crate::generator::error::Error;
+use std::collections::HashSet;
+
+/// Named groups of look-alike or sound-alike characters.
+///
+/// A group lists its members separated by spaces, such as `rn m`.
+/// A member of several characters is confused with the others only as a whole.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Profile {
+    pub(crate) name: &'static str,
+    pub(crate) description: &'static str,
+    pub(crate) groups: &'static [&'static str],
+}
+
+/// Programming fonts and terminals, which keep the shapes of letters but shrink punctuation.
+pub(crate) const MONOSPACE: Profile = Profile {
+    name: "monospace",
+    description: "Programming fonts and terminals.",
+    groups: &["0 O", "1 l I |", "` '", "'' \"", ", .", "; :"],
+};
+
+/// Handwritten notes, where the cases of many letters look the same.
+pub(crate) const HANDWRITING: Profile = Profile {
+    name: "handwriting",
+    description: "Handwritten notes.",
+    groups: &[
+        "0 O o D Q",
+        "1 l I | 7",
+        "2 Z z",
+        "5 S s",
+        "8 B",
+        "6 b",
+        "9 g q",
+        "u v U V",
+        "rn m",
+        "C c",
+        "K k",
+        "P p",
+        "W w",
+        "X x",
+        "Y y",
+        "- _ ~",
+        "` '",
+        "シ ツ",
+        "ソ ン",
+        "ク ケ",
+        "ぬ め",
+        "わ ね れ",
+        "る ろ",
+        "は ほ",
+    ],
+};
+
+/// Passwords read aloud over the phone, where letters rhyme.
+pub(crate) const PHONE: Profile = Profile {
+    name: "phone",
+    description: "Passwords read aloud over the phone.",
+    groups: &[
+        "b d e g p t v z",
+        "B D E G P T V Z",
+        "m n",
+        "M N",
+        "f s",
+        "F S",
+        "a j k",
+        "A J K",
+        "i y",
+        "I Y",
+        "q u",
+        "Q U",
+        "- _",
+        "` ' \"",
+        "( ) [ ] { } < >",
+    ],
+};
+
+/// Printed codes read by OCR, which merges thin strokes and adjacent letters.
+pub(crate) const OCR: Profile = Profile {
+    name: "ocr",
+    description: "Printed codes read by OCR.",
+    groups: &[
+        "0 O o D Q",
+        "1 l I | i !",
+        "5 S",
+        "2 Z",
+        "8 B",
+        "6 G",
+        "rn m",
+        "cl d",
+        "vv w",
+        "` ' \"",
+        ", .",
+        "; :",
+        "- ~",
+    ],
+};
+
+/// Latin, Greek and Cyrillic letters and digits, and hiragana, katakana and kanji of the same
+/// shapes.
+pub(crate) const HOMOGLYPH: Profile = Profile {
+    name: "homoglyph",
+    description: "Latin, Greek, Cyrillic and Japanese letters of the same shapes.",
+    groups: &[
+        "a а", "c с ϲ", "e е", "h һ", "i і", "j ј", "o о ο", "p р ρ", "s ѕ", "v ν", "x х χ", "y у",
+        "A А Α", "B В Β", "C С Ϲ", "E Е Ε", "H Н Η", "I І Ι", "J Ј", "K К Κ", "M М Μ", "N Ν",
+        "O О Ο", "P Р Ρ", "S Ѕ", "T Т Τ", "X Х Χ", "Y Ү Υ", "Z Ζ", "3 З", "へ ヘ", "べ ベ",
+        "ぺ ペ", "り リ", "カ 力", "ロ 口", "ニ 二", "エ 工", "タ 夕", "ト 卜", "ハ 八", "ノ 丿",
+    ],
+};
+
+pub(crate) const ALL_PROFILES: [Profile; 5] = [MONOSPACE, HANDWRITING, PHONE, OCR, HOMOGLYPH];
+
+impl Profile {
+    pub(crate) fn from_name(name: &str) -> Result<&'static Self, Error> {
+        ALL_PROFILES
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| Error::ProfileErr(String::from(name)))
+    }
+}
+
+/// Characters of `groups` which may be confused among `characters`.
+///
+/// A group is confusing when two or more of its members can be written with `characters`,
+/// and then its single character members are removed.
+/// The remaining members of several characters are no longer ambiguous.
+pub(crate) fn confusing<S: AsRef<str>>(groups: &[S], characters: &HashSet<char>) -> HashSet<char> {
+    let mut confusing = HashSet::new();
+    for group in groups {
+        let members: Vec<&str> = group
+            .as_ref()
+            .split_whitespace()
+            .filter(|m| m.chars().all(|c| characters.contains(&c)))
+            .collect();
+        if members.len() < 2 {
+            continue;
+        }
+        for member in members {
+            let mut chars = member.chars();
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                confusing.insert(c);
+            }
+        }
+    }
+    confusing
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::iter::FromIterator;
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Profile::from_name("ocr"), Ok(&OCR));
+        assert_eq!(
+            Profile::from_name("braille"),
+            Err(Error::ProfileErr(String::from("braille")))
+        );
+    }
+
+    #[test]
+    fn test_groups() {
+        let symbols = crate::symbols_all!();
+        for profile in ALL_PROFILES.iter() {
+            for group in profile.groups {
+                assert!(group.split_whitespace().count() >= 2);
+                assert!(group
+                    .chars()
+                    .all(|c| c == ' ' || c.is_alphanumeric() || symbols.contains(c)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_confusing() {
+        let characters = HashSet::from_iter("0123456789SZmnr".chars());
+        assert_eq!(
+            confusing(&["5 S", "2 Z", "8 B", "rn m"], &characters),
+            HashSet::from_iter("5S2Zm".chars())
+        );
+
+        // Only digits are left, so that 5 is no longer confused with S.
+        let digits = HashSet::from_iter("0123456789".chars());
+        assert!(confusing(&OCR.groups[2..5], &digits).is_empty());
+        assert_eq!(
+            confusing(HANDWRITING.groups, &digits),
+            HashSet::from_iter("17".chars())
+        );
+
+        // A group of user-defined characters.
+        let groups = vec![String::from("a  e"), String::from("x")];
+        assert_eq!(
+            confusing(&groups, &HashSet::from_iter("aex".chars())),
+            HashSet::from_iter("ae".chars())
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use crate::generator::character::ALL_CHARACTERS;
+use crate::generator::error::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// User-defined category of any Unicode characters, such as Greek or Cyrillic letters.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomCategory {
+    pub name: String,
+    /// Flag in usable flags, which must differ from the flags of the other categories.
+    pub flag: char,
+    /// Characters of the category, normalized to NFC.
+    pub characters: String,
+}
+
+impl CustomCategory {
+    /// Characters of the category in NFC, each of which is a grapheme cluster of its own
+    /// next to any other.
+    pub(crate) fn char_set(&self) -> Result<HashSet<char>, Error> {
+        let characters: String = self.characters.nfc().collect();
+        let errors: String = characters.chars().filter(|c| !standalone(*c)).collect();
+        if !errors.is_empty() {
+            return Err(Error::CustomCharacterErr(errors));
+        }
+        let char_set: HashSet<char> = characters.chars().collect();
+        if char_set.is_empty() {
+            Err(Error::CustomCharacterErr(String::default()))
+        } else {
+            Ok(char_set)
+        }
+    }
+
+    /// Checks that names and flags of `categories` differ from each other and from the
+    /// built-in categories.
+    pub(crate) fn validate(categories: &[Self]) -> Result<(), Error> {
+        for (i, category) in categories.iter().enumerate() {
+            if category.name.is_empty() || categories[..i].iter().any(|c| c.name == category.name) {
+                return Err(Error::CustomNameErr(category.name.clone()));
+            }
+            let flag = category.flag;
+            if flag.is_whitespace()
+                || flag == ','
+                || ALL_CHARACTERS.iter().any(|c| c.flag == flag)
+                || categories[..i].iter().any(|c| c.flag == flag)
+            {
+                return Err(Error::CustomFlagErr(flag.to_string()));
+            }
+            category.char_set()?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `c` is a grapheme cluster of its own in NFC, before and after any other such
+/// character.
+///
+/// Combining marks, joiners and prepended characters attach to their neighbors, and
+/// regional indicators and Hangul jamo attach to their own kind.
+fn standalone(c: char) -> bool {
+    if c.is_control() || c.is_whitespace() {
+        return false;
+    }
+    [format!("a{}", c), format!("{}a", c), format!("{0}{0}", c)]
+        .iter()
+        .all(|s| is_nfc(s) && s.graphemes(true).count() == 2)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::iter::FromIterator;
+
+    fn category(name: &str, flag: char, characters: &str) -> CustomCategory {
+        CustomCategory {
+            name: String::from(name),
+            flag,
+            characters: String::from(characters),
+        }
+    }
+
+    #[test]
+    fn test_char_set() {
+        assert_eq!(
+            category("greek", 'g', "αβγαβ").char_set(),
+            Ok(HashSet::from_iter("αβγ".chars()))
+        );
+        // e and a combining acute accent are composed to é.
+        assert_eq!(
+            category("latin", 'a', "e\u{301}ñ").char_set(),
+            Ok(HashSet::from_iter("éñ".chars()))
+        );
+        assert_eq!(
+            category("marks", 'm', "a\u{20dd}b").char_set(),
+            Err(Error::CustomCharacterErr(String::from("\u{20dd}")))
+        );
+        assert_eq!(
+            category("jamo", 'j', "\u{1100}한").char_set(),
+            Err(Error::CustomCharacterErr(String::from("\u{1100}")))
+        );
+        assert_eq!(
+            category("flags", 'f', "🇯🇵 x\u{200d}").char_set(),
+            Err(Error::CustomCharacterErr(String::from("🇯🇵 \u{200d}")))
+        );
+        assert_eq!(
+            category("empty", 'e', "").char_set(),
+            Err(Error::CustomCharacterErr(String::default()))
+        );
+    }
+
+    #[test]
+    fn test_char_set_composed() {
+        // A leading and a vowel jamo are composed to a syllable.
+        assert_eq!(
+            category("hangul", 'h', "\u{1100}\u{1161}한").char_set(),
+            Ok(HashSet::from_iter("가한".chars()))
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let greek = category("greek", 'g', "αβγ");
+        let cyrillic = category("cyrillic", 'c', "абв");
+        assert_eq!(
+            CustomCategory::validate(&[greek.clone(), cyrillic.clone()]),
+            Ok(())
+        );
+        assert_eq!(
+            CustomCategory::validate(&[greek.clone(), category("greek", 'h', "δ")]),
+            Err(Error::CustomNameErr(String::from("greek")))
+        );
+        assert_eq!(
+            CustomCategory::validate(&[greek.clone(), category("gamma", 'g', "γ")]),
+            Err(Error::CustomFlagErr(String::from("g")))
+        );
+        assert_eq!(
+            CustomCategory::validate(&[category("lower", 'l', "ł")]),
+            Err(Error::CustomFlagErr(String::from("l")))
+        );
+    }
+
+    #[test]
+    fn test_standalone() {
+        for c in "aé한αж¡€あ漢😀".chars() {
+            assert!(standalone(c), "{}", c);
+        }
+        for c in "\u{301}\u{94d}\u{200d}\u{1100}\u{11a8}\u{1f1ef}\n ".chars() {
+            assert!(!standalone(c), "{:?}", c);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::generator::character::CustomCategory;
+
+/// Hiragana of the gojūon with dakuten and handakuten, without small kana.
+pub(crate) const HIRAGANA: &str = "\
+あいうえおかきくけこさしすせそたちつてとなにぬねのはひふへほまみむめもやゆよらりるれろわをん\
+がぎぐげござじずぜぞだぢづでどばびぶべぼぱぴぷぺぽ";
+
+/// Katakana of the gojūon with dakuten and handakuten, without small kana.
+pub(crate) const KATAKANA: &str = "\
+アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワヲン\
+ガギグゲゴザジズゼゾダヂヅデドバビブベボパピプペポ";
+
+/// `categories` followed by the kana presets whose names and flags they do not take.
+pub(crate) fn with_presets(categories: &[CustomCategory]) -> Vec<CustomCategory> {
+    let mut all = categories.to_vec();
+    for preset in presets() {
+        if categories
+            .iter()
+            .all(|c| c.name != preset.name && c.flag != preset.flag)
+        {
+            all.push(preset);
+        }
+    }
+    all
+}
+
+/// Categories of kana which are available without the config file.
+fn presets() -> Vec<CustomCategory> {
+    vec![
+        CustomCategory {
+            name: String::from("hiragana"),
+            flag: 'h',
+            characters: String::from(HIRAGANA),
+        },
+        CustomCategory {
+            name: String::from("katakana"),
+            flag: 'k',
+            characters: String::from(KATAKANA),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use unicode_normalization::UnicodeNormalization;
+
+    #[test]
+    fn test_presets() {
+        for category in presets() {
+            let characters = &category.characters;
+            assert_eq!(characters.chars().count(), 71);
+            assert_eq!(category.char_set().unwrap().len(), 71);
+            // IME input and its halfwidth or decomposed forms normalize to the same kana.
+            assert_eq!(&characters.nfc().collect::<String>(), characters);
+            assert_eq!(&characters.nfkc().collect::<String>(), characters);
+            assert_eq!(&characters.nfd().nfc().collect::<String>(), characters);
+        }
+        assert_eq!(CustomCategory::validate(&presets()), Ok(()));
+        assert_eq!("ｶﾞｷﾞﾊﾟ".nfkc().collect::<String>(), "ガギパ");
+    }
+
+    #[test]
+    fn test_with_presets() {
+        let names = |categories: Vec<CustomCategory>| -> Vec<String> {
+            categories.into_iter().map(|c| c.name).collect()
+        };
+        assert_eq!(names(with_presets(&[])), vec!["hiragana", "katakana"]);
+
+        // A custom category of the same flag takes the place of the preset.
+        let hangul = CustomCategory {
+            name: String::from("hangul"),
+            flag: 'h',
+            characters: String::from("가나다"),
+        };
+        assert_eq!(names(with_presets(&[hangul])), vec!["hangul", "katakana"]);
+    }
+}
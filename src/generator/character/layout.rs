@@ -0,0 +1,123 @@
+use crate::generator::error::Error;
+
+/// Keyboard layout, with the printable ASCII characters typed without dead keys.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Layout {
+    pub(crate) name: &'static str,
+    /// Characters typed without modifiers.
+    pub(crate) unshifted: &'static str,
+    /// Characters typed with Shift or AltGr.
+    pub(crate) shifted: &'static str,
+}
+
+/// US QWERTY.
+pub(crate) const US: Layout = Layout {
+    name: "us",
+    unshifted: r##"`1234567890-=qwertyuiop[]\asdfghjkl;'zxcvbnm,./"##,
+    shifted: r##"~!@#$%^&*()_+QWERTYUIOP{}|ASDFGHJKL:"ZXCVBNM<>?"##,
+};
+
+/// Japanese JIS, whose symbols are on other keys than US.
+pub(crate) const JIS: Layout = Layout {
+    name: "jis",
+    unshifted: r##"1234567890-^\qwertyuiop@[asdfghjkl;:]zxcvbnm,./"##,
+    shifted: r##"!"#$%&'()=~|`{+*}<>?_QWERTYUIOPASDFGHJKLZXCVBNM"##,
+};
+
+/// French AZERTY, whose digits need Shift, and whose `~`, `` ` `` and `^` are dead keys.
+pub(crate) const AZERTY: Layout = Layout {
+    name: "azerty",
+    unshifted: r##"&"'(-_)=azertyuiop$qsdfghjklm*<wxcvbn,;:!"##,
+    shifted: r##"1234567890+%>?./#{[|\@]}AZERTYUIOPQSDFGHJKLMWXCVBN"##,
+};
+
+/// German QWERTZ, whose `^` and `` ` `` are dead keys.
+pub(crate) const QWERTZ: Layout = Layout {
+    name: "qwertz",
+    unshifted: r##"1234567890qwertzuiop+asdfghjkl#<yxcvbnm,.-"##,
+    shifted: r##"!"$%&/()=?*'>;:_@{[]}\|~QWERTZUIOPASDFGHJKLYXCVBNM"##,
+};
+
+/// US Dvorak.
+pub(crate) const DVORAK: Layout = Layout {
+    name: "dvorak",
+    unshifted: r##"`1234567890[]',.pyfgcrl/=\aoeuidhtns-;qjkxbmwvz"##,
+    shifted: r##"~!@#$%^&*(){}"<>PYFGCRL?+|AOEUIDHTNS_:QJKXBMWVZ"##,
+};
+
+pub(crate) const ALL_LAYOUTS: [Layout; 5] = [US, JIS, AZERTY, QWERTZ, DVORAK];
+
+/// Name of all the layouts, which also leaves out `SWAPPED`.
+pub(crate) const ANY: &str = "any";
+
+/// Letters on different keys among QWERTY, QWERTZ and AZERTY, which are typed wrong when the
+/// layout of the keyboard is mistaken.
+pub(crate) const SWAPPED: &str = "aAmMqQwWyYzZ";
+
+impl Layout {
+    /// Layouts of comma separated `names`, where `any` is all the layouts.
+    pub(crate) fn from_names(names: &str) -> Result<Vec<&'static Self>, Error> {
+        let mut layouts: Vec<&'static Self> = Vec::new();
+        for name in names.split(',').filter(|n| !n.is_empty()) {
+            let named: Vec<&'static Self> = if name == ANY {
+                ALL_LAYOUTS.iter().collect()
+            } else {
+                vec![ALL_LAYOUTS
+                    .iter()
+                    .find(|l| l.name == name)
+                    .ok_or_else(|| Error::LayoutErr(String::from(name)))?]
+            };
+            for layout in named {
+                if !layouts.contains(&layout) {
+                    layouts.push(layout);
+                }
+            }
+        }
+        Ok(layouts)
+    }
+
+    /// Whether `c` is typed without dead keys.
+    pub(crate) fn types(&self, c: char) -> bool {
+        self.unshifted.contains(c) || self.shifted.contains(c)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_names() {
+        assert_eq!(Layout::from_names("jis,us"), Ok(vec![&JIS, &US]));
+        assert_eq!(Layout::from_names("us,any").unwrap().len(), 5);
+        assert_eq!(
+            Layout::from_names("us,colemak"),
+            Err(Error::LayoutErr(String::from("colemak")))
+        );
+    }
+
+    #[test]
+    fn test_layouts() {
+        let symbols = crate::symbols_all!();
+        for layout in ALL_LAYOUTS.iter() {
+            for c in layout.unshifted.chars() {
+                assert!(!layout.shifted.contains(c), "{} {}", layout.name, c);
+            }
+            for c in ('a'..='z').chain('A'..='Z').chain('0'..='9') {
+                assert!(layout.types(c), "{} {}", layout.name, c);
+            }
+            assert!(layout
+                .unshifted
+                .chars()
+                .chain(layout.shifted.chars())
+                .all(|c| c.is_ascii_alphanumeric() || symbols.contains(c)));
+        }
+        let dead =
+            |layout: &Layout| -> String { symbols.chars().filter(|c| !layout.types(*c)).collect() };
+        assert_eq!(dead(&US), "");
+        assert_eq!(dead(&JIS), "");
+        assert_eq!(dead(&AZERTY), "^`~");
+        assert_eq!(dead(&QWERTZ), "^`");
+        assert_eq!(dead(&DVORAK), "");
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashSet;
+
+/// ASCII characters on the number page of phone keyboards.
+pub(crate) const NUMBER_PAGE: &str = r##"0123456789-/:;()$&@".,?!'"##;
+
+/// ASCII characters on the symbol page of phone keyboards.
+pub(crate) const SYMBOL_PAGE: &str = r##"[]{}#%^*+=_\|~<>`"##;
+
+/// Keyboard pages of `characters` other than the letter page, in the order of number, symbol
+/// and other scripts, leaving out the pages none of `characters` are on.
+/// The characters in none of the pages are on the letter page.
+pub(crate) fn pages(characters: &HashSet<char>) -> Vec<HashSet<char>> {
+    let on = |page: &str| -> HashSet<char> {
+        characters
+            .iter()
+            .filter(|c| page.contains(**c))
+            .copied()
+            .collect()
+    };
+    let other: HashSet<char> = characters
+        .iter()
+        .filter(|c| !c.is_ascii())
+        .copied()
+        .collect();
+    vec![on(NUMBER_PAGE), on(SYMBOL_PAGE), other]
+        .into_iter()
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pages() {
+        let mut symbols: Vec<char> = NUMBER_PAGE.chars().chain(SYMBOL_PAGE.chars()).collect();
+        symbols.retain(|c| !c.is_ascii_digit());
+        symbols.sort_unstable();
+        let mut all: Vec<char> = crate::symbols_all!().chars().collect();
+        all.sort_unstable();
+        assert_eq!(symbols, all);
+
+        let characters: HashSet<char> = "ab1-#あア".chars().collect();
+        let expected: Vec<HashSet<char>> = vec![
+            "1-".chars().collect(),
+            "#".chars().collect(),
+            "あア".chars().collect(),
+        ];
+        assert_eq!(pages(&characters), expected);
+        assert_eq!(pages(&"abc".chars().collect()), Vec::<HashSet<char>>::new());
+    }
+}
@@ -1,11 +1,69 @@
-use clap::{App, Arg};
+pub mod apikey;
+pub mod easy_profiles;
+pub mod external;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod id;
+pub mod kana_passphrase;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod mnemonic;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod otp;
+pub mod pin;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod recovery_codes;
+pub mod render;
+pub mod rpc;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod serve;
+pub mod token;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod wifi;
 
-use crate::generator::{character::Category, error::Error, Generator, MAX_LENGTH, MIN_LENGTH};
+use clap::{App, Arg, ArgMatches};
+use std::path::{Path, PathBuf};
 
+use crate::format::{Grouping, DEFAULT_SEPARATOR};
+use crate::generator::{
+    character::{Category, Context, Layout, Profile},
+    error::Error,
+    Generator, MAX_LENGTH, MIN_LENGTH,
+};
+use crate::spell::Language;
+
+pub const OPTION_CONFIG: &str = "config";
 const OPTION_LENGTH: &str = "length";
 const OPTION_USABLE: &str = "usable";
+const OPTION_CATEGORY: &str = "category";
 const OPTION_IS_EASY: &str = "is_easy";
 const OPTION_SYMBOLS: &str = "symbols";
+const OPTION_EXCLUDE: &str = "exclude";
+const OPTION_REQUIRE: &str = "require";
+const OPTION_SAFE_FOR: &str = "safe-for";
+const OPTION_EASY_PROFILE: &str = "easy-profile";
+const OPTION_FIRST: &str = "first";
+const OPTION_LAST: &str = "last";
+const OPTION_NO_LEADING_SYMBOL: &str = "no-leading-symbol";
+const OPTION_MAX_REPEAT: &str = "max-repeat";
+const OPTION_MAX_SEQUENCE: &str = "max-sequence";
+const OPTION_MAX_CLASS_REPEAT: &str = "max-class-repeat";
+const OPTION_NO_DUPLICATE_CHARS: &str = "no-duplicate-chars";
+const OPTION_MAX_BYTES: &str = "max-bytes";
+const OPTION_ROMAJI: &str = "romaji";
+const OPTION_LAYOUT: &str = "layout";
+const OPTION_MINIMIZE_SHIFT: &str = "minimize-shift";
+const OPTION_MOBILE: &str = "mobile";
+const OPTION_GROUP: &str = "group";
+const OPTION_SEPARATOR: &str = "separator";
+const OPTION_SPELL: &str = "spell";
+const OPTION_LANGUAGE: &str = "language";
+const OPTION_COLOR: &str = "color";
+const OPTION_QR: &str = "qr";
+const OPTION_QR_WIFI: &str = "qr-wifi";
+const OPTION_QR_OUTPUT: &str = "qr-output";
+
+const HELP_CONFIG: &str = "\
+JSON config file of custom categories and user-defined easy profiles.
+Default is $PSWDGN_CONFIG, or pswdgn/config.json in $XDG_CONFIG_HOME or ~/.config.";
 
 const HELP_LENGTH: &str = "\
 Length of generated password string.
@@ -18,7 +76,14 @@ The category of characters to be used for the generated password.
     l : Lower case alphabets.
     u : Upper case alphabets.
     n : Numbers.
-    s : Symbols.";
+    s : Symbols.
+    h : Hiragana.
+    k : Katakana.
+Flags of custom categories in the config file are also available.";
+
+const HELP_CATEGORY: &str = "\
+Names of custom categories in the config file, hiragana or katakana to be used besides usable.
+Separate several categories with commas.";
 
 const HELP_IS_EASY: &str = "
 Use easy to identify characters.
@@ -26,11 +91,124 @@ Use easy to identify characters.
 
 const HELP_SYMBOLS: &str = concat!("Use symbols.\n", crate::symbols_all!());
 
+const HELP_EXCLUDE: &str = "\
+Characters never to be used in any category.";
+
+const HELP_REQUIRE: &str = "\
+Characters to appear in the generated password.
+Up to 8 characters.";
+
+const HELP_SAFE_FOR: &str = "\
+Never use symbols which need escaping in the contexts.
+    url, shell, json, xml, yaml, dotenv, jdbc, odbc, ldap-dn, csv
+Separate several contexts with commas.";
+
+const HELP_EASY_PROFILE: &str = "\
+Never use characters which are confused with others in the profiles.
+    monospace, handwriting, phone, ocr
+Separate several profiles with commas.
+Profiles defined in the config file are also available.";
+
+const HELP_FIRST: &str = "\
+The categories of the first character, in the same flags as usable.";
+
+const HELP_LAST: &str = "\
+The categories of the last character, in the same flags as usable.";
+
+const HELP_NO_LEADING_SYMBOL: &str = "\
+Never start with a symbol.";
+
+const HELP_MAX_REPEAT: &str = "\
+Maximum number of the same consecutive characters, as aa.
+0 is no limit.";
+
+const HELP_MAX_SEQUENCE: &str = "\
+Maximum length of ascending or descending sequences, as abc or 321.
+0 is no limit.";
+
+const HELP_MAX_CLASS_REPEAT: &str = "\
+Maximum number of consecutive characters of the same category.
+0 is no limit.";
+
+const HELP_NO_DUPLICATE_CHARS: &str = "\
+Never use a character twice.
+Cannot be combined with max-sequence, max-class-repeat nor max-bytes
+shorter than some passwords, nor with minimize-shift.";
+
+const HELP_MAX_BYTES: &str = "\
+Maximum length of generated password in UTF-8 bytes.
+Length is counted in characters, each of which is a grapheme cluster.
+0 is no limit.";
+
+const HELP_LAYOUT: &str = "\
+Never use symbols which need dead keys or are missing on any of the keyboard layouts.
+    us, jis, azerty, qwertz, dvorak
+any is all the layouts, and also never uses aAmMqQwWyYzZ, which move among them.
+Separate several layouts with commas.";
+
+const HELP_MINIMIZE_SHIFT: &str = "\
+Use characters typed with Shift or AltGr on the layouts a quarter as often.
+Passwords are no longer uniform, and their entropy is lower.";
+
+const HELP_MOBILE: &str = "\
+Type the letters, numbers, symbols and other scripts each in one run,
+so that a phone keyboard switches to each page only once.
+The order and the lengths of the runs are random.
+Shows the entropy and its cost against uniform passwords on stderr.
+Cannot be combined with max-repeat, max-sequence, max-class-repeat,
+no-duplicate-chars, max-bytes nor minimize-shift.";
+
+const HELP_GROUP: &str = "\
+Show the password in groups of N characters, as abcd-EfGh-2k9x.
+The separators are not counted in the length nor the entropy.";
+
+const HELP_SEPARATOR: &str = "\
+Characters between the groups.
+Default separator is -.";
+
+const HELP_SPELL: &str = "\
+Also show the phonetic name of each character, as Bravo, lowercase kilo, Seven.";
+
+const HELP_LANGUAGE: &str = "\
+Language of the phonetic names.
+    en : English.
+    ja : Japanese.
+Default language is Japanese in a Japanese locale of $LANG, and English otherwise.";
+
+const HELP_COLOR: &str = "\
+Show the characters of each category, such as lower case letters, upper case letters,
+numbers, symbols, kana and custom categories, in different terminal colours.";
+
+const HELP_QR: &str = "\
+Also show the password as a QR code in the terminal, to scan it with a phone.";
+
+const HELP_QR_WIFI: &str = "\
+Encode a WPA Wi-Fi network of the SSID and the password in the QR code,
+which phones join when they scan it.
+Shows the QR code in the terminal unless qr-output is given.";
+
+const HELP_QR_OUTPUT: &str = "\
+Write the QR code to a PNG or SVG image, chosen by the extension of FILE.
+The file is readable only by the owner.";
+
+const HELP_ROMAJI: &str = "\
+Also show the password in romaji to type kana on an ASCII keyboard.
+Katakana are written in upper case.";
+
 pub fn build() -> App<'static, 'static> {
-    App::new(clap::crate_name!())
+    let app = App::new(clap::crate_name!())
         .version(clap::crate_version!())
         .author(clap::crate_authors!())
         .about(clap::crate_description!())
+        .arg(
+            Arg::with_name(OPTION_CONFIG)
+                .long(OPTION_CONFIG)
+                .takes_value(true)
+                .value_name("FILE")
+                .multiple(false)
+                .global(true)
+                .help(HELP_CONFIG),
+        )
         .arg(
             Arg::with_name(OPTION_LENGTH)
                 .short("l")
@@ -48,9 +226,16 @@ pub fn build() -> App<'static, 'static> {
                 .takes_value(true)
                 .value_name("USABLE CHARACTER")
                 .multiple(false)
-                .validator(validate_usable)
                 .help(HELP_USABLE),
         )
+        .arg(
+            Arg::with_name(OPTION_CATEGORY)
+                .long(OPTION_CATEGORY)
+                .takes_value(true)
+                .value_name("NAMES")
+                .multiple(false)
+                .help(HELP_CATEGORY),
+        )
         .arg(
             Arg::with_name(OPTION_IS_EASY)
                 .short("e")
@@ -68,9 +253,224 @@ pub fn build() -> App<'static, 'static> {
                 .validator(validate_symbols)
                 .help(HELP_SYMBOLS),
         )
+        .arg(
+            Arg::with_name(OPTION_EXCLUDE)
+                .short("x")
+                .long(OPTION_EXCLUDE)
+                .takes_value(true)
+                .value_name("CHARACTERS")
+                .multiple(false)
+                .help(HELP_EXCLUDE),
+        )
+        .arg(
+            Arg::with_name(OPTION_REQUIRE)
+                .short("r")
+                .long(OPTION_REQUIRE)
+                .takes_value(true)
+                .value_name("CHARACTERS")
+                .multiple(false)
+                .help(HELP_REQUIRE),
+        )
+        .arg(
+            Arg::with_name(OPTION_SAFE_FOR)
+                .long(OPTION_SAFE_FOR)
+                .takes_value(true)
+                .value_name("CONTEXTS")
+                .multiple(false)
+                .validator(validate_safe_for)
+                .help(HELP_SAFE_FOR),
+        )
+        .arg(
+            Arg::with_name(OPTION_EASY_PROFILE)
+                .long(OPTION_EASY_PROFILE)
+                .takes_value(true)
+                .value_name("PROFILES")
+                .multiple(false)
+                .help(HELP_EASY_PROFILE),
+        )
+        .arg(
+            Arg::with_name(OPTION_FIRST)
+                .long(OPTION_FIRST)
+                .takes_value(true)
+                .value_name("USABLE CHARACTER")
+                .multiple(false)
+                .help(HELP_FIRST),
+        )
+        .arg(
+            Arg::with_name(OPTION_LAST)
+                .long(OPTION_LAST)
+                .takes_value(true)
+                .value_name("USABLE CHARACTER")
+                .multiple(false)
+                .help(HELP_LAST),
+        )
+        .arg(
+            Arg::with_name(OPTION_NO_LEADING_SYMBOL)
+                .long(OPTION_NO_LEADING_SYMBOL)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_NO_LEADING_SYMBOL),
+        )
+        .arg(
+            Arg::with_name(OPTION_MAX_REPEAT)
+                .long(OPTION_MAX_REPEAT)
+                .takes_value(true)
+                .value_name("N")
+                .multiple(false)
+                .validator(validate_limit)
+                .help(HELP_MAX_REPEAT),
+        )
+        .arg(
+            Arg::with_name(OPTION_MAX_SEQUENCE)
+                .long(OPTION_MAX_SEQUENCE)
+                .takes_value(true)
+                .value_name("N")
+                .multiple(false)
+                .validator(validate_limit)
+                .help(HELP_MAX_SEQUENCE),
+        )
+        .arg(
+            Arg::with_name(OPTION_MAX_CLASS_REPEAT)
+                .long(OPTION_MAX_CLASS_REPEAT)
+                .takes_value(true)
+                .value_name("N")
+                .multiple(false)
+                .validator(validate_limit)
+                .help(HELP_MAX_CLASS_REPEAT),
+        )
+        .arg(
+            Arg::with_name(OPTION_NO_DUPLICATE_CHARS)
+                .long(OPTION_NO_DUPLICATE_CHARS)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_NO_DUPLICATE_CHARS),
+        )
+        .arg(
+            Arg::with_name(OPTION_MAX_BYTES)
+                .long(OPTION_MAX_BYTES)
+                .takes_value(true)
+                .value_name("N")
+                .multiple(false)
+                .validator(validate_limit)
+                .help(HELP_MAX_BYTES),
+        )
+        .arg(
+            Arg::with_name(OPTION_LAYOUT)
+                .long(OPTION_LAYOUT)
+                .takes_value(true)
+                .value_name("LAYOUTS")
+                .multiple(false)
+                .validator(validate_layout)
+                .help(HELP_LAYOUT),
+        )
+        .arg(
+            Arg::with_name(OPTION_MINIMIZE_SHIFT)
+                .long(OPTION_MINIMIZE_SHIFT)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_MINIMIZE_SHIFT),
+        )
+        .arg(
+            Arg::with_name(OPTION_MOBILE)
+                .long(OPTION_MOBILE)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_MOBILE),
+        )
+        .arg(
+            Arg::with_name(OPTION_GROUP)
+                .long(OPTION_GROUP)
+                .takes_value(true)
+                .value_name("N")
+                .multiple(false)
+                .validator(validate_group)
+                .help(HELP_GROUP),
+        )
+        .arg(
+            Arg::with_name(OPTION_SEPARATOR)
+                .long(OPTION_SEPARATOR)
+                .takes_value(true)
+                .value_name("SEPARATOR")
+                .multiple(false)
+                .requires(OPTION_GROUP)
+                .validator(validate_separator)
+                .help(HELP_SEPARATOR),
+        )
+        .arg(
+            Arg::with_name(OPTION_SPELL)
+                .long(OPTION_SPELL)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_SPELL),
+        )
+        .arg(
+            Arg::with_name(OPTION_LANGUAGE)
+                .long(OPTION_LANGUAGE)
+                .takes_value(true)
+                .value_name("LANGUAGE")
+                .multiple(false)
+                .requires(OPTION_SPELL)
+                .validator(validate_language)
+                .help(HELP_LANGUAGE),
+        )
+        .arg(
+            Arg::with_name(OPTION_COLOR)
+                .long(OPTION_COLOR)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_COLOR),
+        )
+        .arg(
+            Arg::with_name(OPTION_QR)
+                .long(OPTION_QR)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_QR),
+        )
+        .arg(
+            Arg::with_name(OPTION_QR_WIFI)
+                .long(OPTION_QR_WIFI)
+                .takes_value(true)
+                .value_name("SSID")
+                .multiple(false)
+                .help(HELP_QR_WIFI),
+        )
+        .arg(
+            Arg::with_name(OPTION_QR_OUTPUT)
+                .long(OPTION_QR_OUTPUT)
+                .takes_value(true)
+                .value_name("FILE")
+                .multiple(false)
+                .validator(validate_qr_output)
+                .help(HELP_QR_OUTPUT),
+        )
+        .arg(
+            Arg::with_name(OPTION_ROMAJI)
+                .long(OPTION_ROMAJI)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_ROMAJI),
+        )
+        .subcommand(apikey::build())
+        .subcommand(easy_profiles::build())
+        .subcommand(external::build())
+        .subcommand(kana_passphrase::build())
+        .subcommand(pin::build())
+        .subcommand(render::build())
+        .subcommand(rpc::build())
+        .subcommand(token::build());
+    #[cfg(not(target_arch = "wasm32"))]
+    let app = app
+        .subcommand(id::build())
+        .subcommand(mnemonic::build())
+        .subcommand(otp::build())
+        .subcommand(recovery_codes::build())
+        .subcommand(serve::build())
+        .subcommand(wifi::build());
+    app
 }
 
-fn validate_length(value: String) -> Result<(), String> {
+pub(crate) fn validate_length(value: String) -> Result<(), String> {
     let val = match value.parse::<i128>() {
         Ok(x) => x,
         Err(_) => return Err(format!("Not number value: {}", value)),
@@ -78,39 +478,153 @@ fn validate_length(value: String) -> Result<(), String> {
     match Generator::validate_length(val) {
         Ok(_) => Ok(()),
         Err(e) => match e {
-            Error::CategoryFlagErr(_) | Error::NotSymbolErr(_) | Error::CharactersErr(_) => Ok(()),
             Error::LengthExcessErr(_) => Err(format!("Needs {} or less: {}", MAX_LENGTH, val)),
             Error::LengthInsufficientErr(_) => {
                 Err(format!("Needs {} or more: {}", MIN_LENGTH, val))
             }
+            _ => Ok(()),
         },
     }
 }
 
-fn validate_usable(value: String) -> Result<(), String> {
+fn validate_group(value: String) -> Result<(), String> {
+    match value.parse() {
+        Ok(n) => Grouping::new(n, DEFAULT_SEPARATOR).map(|_| ()),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+fn validate_separator(value: String) -> Result<(), String> {
+    Grouping::new(1, &value).map(|_| ())
+}
+
+fn validate_qr_output(value: String) -> Result<(), String> {
+    match QrFormat::from_path(&value) {
+        Some(_) => Ok(()),
+        None => Err(format!("Not PNG nor SVG file: {}", value)),
+    }
+}
+
+fn validate_language(value: String) -> Result<(), String> {
+    Language::from_name(&value).map(|_| ())
+}
+
+pub(crate) fn validate_usable(value: String) -> Result<(), String> {
     match Category::validate_flag(&value) {
         Ok(_) => Ok(()),
         Err(cs) => Err(format!("unknown usable flags: {}", cs)),
     }
 }
 
-fn validate_symbols(value: String) -> Result<(), String> {
+pub(crate) fn validate_symbols(value: String) -> Result<(), String> {
     match Category::validate_symbols(&value) {
         Ok(_) => Ok(()),
         Err(cs) => Err(format!("unknown symbol character: {}", cs)),
     }
 }
 
+pub(crate) fn validate_require(value: String) -> Result<(), String> {
+    match Category::validate_characters(&value) {
+        Ok(_) => Ok(()),
+        Err(cs) => Err(format!("unknown required character: {}", cs)),
+    }
+}
+
+pub(crate) fn validate_safe_for(value: String) -> Result<(), String> {
+    match value
+        .split(',')
+        .filter(|n| !n.is_empty())
+        .try_for_each(|n| Context::from_name(n).map(|_| ()))
+    {
+        Ok(_) => Ok(()),
+        Err(cs) => Err(format!("unknown context: {}", cs)),
+    }
+}
+
+pub(crate) fn validate_layout(value: String) -> Result<(), String> {
+    match Layout::from_names(&value) {
+        Ok(_) => Ok(()),
+        Err(cs) => Err(format!("unknown layout: {}", cs)),
+    }
+}
+
+pub(crate) fn validate_easy_profile(value: String) -> Result<(), String> {
+    match value
+        .split(',')
+        .filter(|n| !n.is_empty())
+        .try_for_each(|n| Profile::from_name(n).map(|_| ()))
+    {
+        Ok(_) => Ok(()),
+        Err(cs) => Err(format!("unknown easy profile: {}", cs)),
+    }
+}
+
+fn validate_limit(value: String) -> Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+/// Image format of a QR code.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QrFormat {
+    Png,
+    Svg,
+}
+
+impl QrFormat {
+    /// Format of the extension of `path`.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => Some(QrFormat::Png),
+            "svg" => Some(QrFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
 pub struct CommandLine {
     pub length: usize,
     pub flags: String,
+    pub category: String,
     pub is_easy: bool,
     pub symbols: String,
+    pub exclude: String,
+    pub require: String,
+    pub safe_for: String,
+    pub easy_profile: String,
+    pub first: String,
+    pub last: String,
+    pub no_leading_symbol: bool,
+    pub max_repeat: usize,
+    pub max_sequence: usize,
+    pub max_class_repeat: usize,
+    pub no_duplicate_chars: bool,
+    pub max_bytes: usize,
+    pub layout: String,
+    pub minimize_shift: bool,
+    pub mobile: bool,
+    /// Display format of the password, `None` for the password as it is.
+    pub grouping: Option<Grouping>,
+    /// Language of the phonetic names, `None` for no spelling.
+    pub spell: Option<Language>,
+    pub color: bool,
+    pub qr: bool,
+    /// SSID of the Wi-Fi network in the QR code, `None` for the password only.
+    pub qr_wifi: Option<String>,
+    /// Image of the QR code, `None` for no image.
+    pub qr_output: Option<PathBuf>,
+    pub romaji: bool,
 }
 
 impl CommandLine {
     pub fn parse(app: App<'static, 'static>) -> Self {
-        let arg_matches = app.get_matches();
+        Self::from_matches(&app.get_matches())
+    }
+
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
         let length = match arg_matches.value_of(OPTION_LENGTH) {
             Some(l) => l.parse().unwrap(),
             None => MIN_LENGTH as usize,
@@ -121,6 +635,11 @@ impl CommandLine {
             None => Category::flags(),
         };
 
+        let category = match arg_matches.value_of(OPTION_CATEGORY) {
+            Some(c) => String::from(c),
+            None => String::default(),
+        };
+
         let is_easy = arg_matches.is_present(OPTION_IS_EASY);
 
         let symbols = match arg_matches.value_of(OPTION_SYMBOLS) {
@@ -128,11 +647,111 @@ impl CommandLine {
             None => String::default(),
         };
 
+        let exclude = match arg_matches.value_of(OPTION_EXCLUDE) {
+            Some(x) => String::from(x),
+            None => String::default(),
+        };
+
+        let require = match arg_matches.value_of(OPTION_REQUIRE) {
+            Some(r) => String::from(r),
+            None => String::default(),
+        };
+
+        let safe_for = match arg_matches.value_of(OPTION_SAFE_FOR) {
+            Some(c) => String::from(c),
+            None => String::default(),
+        };
+
+        let easy_profile = match arg_matches.value_of(OPTION_EASY_PROFILE) {
+            Some(p) => String::from(p),
+            None => String::default(),
+        };
+
+        let first = match arg_matches.value_of(OPTION_FIRST) {
+            Some(f) => String::from(f),
+            None => String::default(),
+        };
+
+        let last = match arg_matches.value_of(OPTION_LAST) {
+            Some(l) => String::from(l),
+            None => String::default(),
+        };
+
+        let no_leading_symbol = arg_matches.is_present(OPTION_NO_LEADING_SYMBOL);
+
+        let limit = |option| match arg_matches.value_of(option) {
+            Some(n) => n.parse().unwrap(),
+            None => 0,
+        };
+        let max_repeat = limit(OPTION_MAX_REPEAT);
+        let max_sequence = limit(OPTION_MAX_SEQUENCE);
+        let max_class_repeat = limit(OPTION_MAX_CLASS_REPEAT);
+
+        let no_duplicate_chars = arg_matches.is_present(OPTION_NO_DUPLICATE_CHARS);
+
+        let max_bytes = limit(OPTION_MAX_BYTES);
+
+        let layout = match arg_matches.value_of(OPTION_LAYOUT) {
+            Some(l) => String::from(l),
+            None => String::default(),
+        };
+
+        let minimize_shift = arg_matches.is_present(OPTION_MINIMIZE_SHIFT);
+
+        let mobile = arg_matches.is_present(OPTION_MOBILE);
+
+        let grouping = arg_matches.value_of(OPTION_GROUP).map(|n| {
+            let separator = arg_matches
+                .value_of(OPTION_SEPARATOR)
+                .unwrap_or(DEFAULT_SEPARATOR);
+            Grouping::new(n.parse().unwrap(), separator).unwrap()
+        });
+
+        let spell = if arg_matches.is_present(OPTION_SPELL) {
+            Some(match arg_matches.value_of(OPTION_LANGUAGE) {
+                Some(l) => Language::from_name(l).unwrap(),
+                None => Language::from_env(),
+            })
+        } else {
+            None
+        };
+
+        let color = arg_matches.is_present(OPTION_COLOR);
+
+        let qr = arg_matches.is_present(OPTION_QR);
+        let qr_wifi = arg_matches.value_of(OPTION_QR_WIFI).map(String::from);
+        let qr_output = arg_matches.value_of(OPTION_QR_OUTPUT).map(PathBuf::from);
+
+        let romaji = arg_matches.is_present(OPTION_ROMAJI);
+
         CommandLine {
-            length: length,
-            flags: flags,
-            is_easy: is_easy,
-            symbols: symbols,
+            length,
+            flags,
+            category,
+            is_easy,
+            symbols,
+            exclude,
+            require,
+            safe_for,
+            easy_profile,
+            first,
+            last,
+            no_leading_symbol,
+            max_repeat,
+            max_sequence,
+            max_class_repeat,
+            no_duplicate_chars,
+            max_bytes,
+            layout,
+            minimize_shift,
+            mobile,
+            grouping,
+            spell,
+            color,
+            qr,
+            qr_wifi,
+            qr_output,
+            romaji,
         }
     }
 }
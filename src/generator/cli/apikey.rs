@@ -0,0 +1,129 @@
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+
+use crate::apikey::{ApiKey, BASE62, DEFAULT_LENGTH};
+
+pub const SUBCOMMAND: &str = "apikey";
+pub const SUBCOMMAND_VERIFY: &str = "verify";
+
+const OPTION_PREFIX: &str = "prefix";
+const OPTION_LENGTH: &str = "length";
+const OPTION_ALPHABET: &str = "alphabet";
+const OPTION_REGEX: &str = "regex";
+const OPTION_KEY: &str = "KEY";
+
+const HELP_PREFIX: &str = "\
+Prefix of the keys, as acme_live_, which scanners detect when the keys leak.
+ASCII letters, numbers, _ and -, starting with a letter or a number.";
+
+const HELP_LENGTH: &str = "\
+Length of the random body between the prefix and the checksum.
+Default length is 30.
+Minimum length is 16.
+Maximum length is 255.";
+
+const HELP_ALPHABET: &str = "\
+ASCII letters and numbers of the body.
+Default alphabet is 0-9, A-Z and a-z.";
+
+const HELP_REGEX: &str = "\
+Also show a regex of the keys for secret scanners.";
+
+const HELP_KEY: &str = "\
+Key to verify.
+The key is read from the standard input if it is not specified,
+which keeps it out of the shell history and the process list.";
+
+pub fn build() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND)
+        .about("Generates an API key of a prefix, a random body and a CRC32 checksum.")
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .arg(
+            Arg::with_name(OPTION_PREFIX)
+                .long(OPTION_PREFIX)
+                .takes_value(true)
+                .value_name("PREFIX")
+                .multiple(false)
+                .required(true)
+                .validator(|v| ApiKey::validate_prefix(&v))
+                .help(HELP_PREFIX),
+        )
+        .arg(
+            Arg::with_name(OPTION_LENGTH)
+                .short("l")
+                .long(OPTION_LENGTH)
+                .takes_value(true)
+                .value_name("LENGTH")
+                .multiple(false)
+                .validator(validate_length)
+                .help(HELP_LENGTH),
+        )
+        .arg(
+            Arg::with_name(OPTION_ALPHABET)
+                .long(OPTION_ALPHABET)
+                .takes_value(true)
+                .value_name("CHARACTERS")
+                .multiple(false)
+                .validator(|v| ApiKey::validate_alphabet(&v).map(|_| ()))
+                .help(HELP_ALPHABET),
+        )
+        .arg(
+            Arg::with_name(OPTION_REGEX)
+                .long(OPTION_REGEX)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_REGEX),
+        )
+        .subcommand(
+            SubCommand::with_name(SUBCOMMAND_VERIFY)
+                .about("Verifies the checksum of an API key.")
+                .arg(
+                    Arg::with_name(OPTION_KEY)
+                        .takes_value(true)
+                        .multiple(false)
+                        .help(HELP_KEY),
+                ),
+        )
+}
+
+fn validate_length(value: String) -> Result<(), String> {
+    match value.parse() {
+        Ok(n) => ApiKey::validate_length(n),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+pub struct ApiKeyCommand {
+    pub api_key: ApiKey,
+    pub regex: bool,
+}
+
+impl ApiKeyCommand {
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
+        let prefix = arg_matches.value_of(OPTION_PREFIX).unwrap();
+
+        let length = match arg_matches.value_of(OPTION_LENGTH) {
+            Some(l) => l.parse().unwrap(),
+            None => DEFAULT_LENGTH,
+        };
+
+        let alphabet = arg_matches.value_of(OPTION_ALPHABET).unwrap_or(BASE62);
+
+        ApiKeyCommand {
+            // Each option is validated by clap.
+            api_key: ApiKey::new(prefix, length, alphabet).unwrap(),
+            regex: arg_matches.is_present(OPTION_REGEX),
+        }
+    }
+}
+
+pub struct VerifyCommand {
+    pub key: Option<String>,
+}
+
+impl VerifyCommand {
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
+        VerifyCommand {
+            key: arg_matches.value_of(OPTION_KEY).map(String::from),
+        }
+    }
+}
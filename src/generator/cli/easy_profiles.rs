@@ -0,0 +1,8 @@
+use clap::{App, SubCommand};
+
+pub const SUBCOMMAND: &str = "easy-profiles";
+
+pub fn build() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND)
+        .about("Shows the characters which each easy profile removes from all characters.")
+}
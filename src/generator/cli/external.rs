@@ -0,0 +1,8 @@
+use clap::{App, SubCommand};
+
+pub const SUBCOMMAND: &str = "external";
+
+pub fn build() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND)
+        .about("Speaks the Terraform external data source protocol on standard input and output.")
+}
@@ -0,0 +1,165 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::id::{Ids, Kind, DEFAULT_NANOID_SIZE, KINDS, NANOID_ALPHABET};
+
+pub const SUBCOMMAND: &str = "id";
+
+const OPTION_KIND: &str = "kind";
+const OPTION_COUNT: &str = "count";
+const OPTION_SIZE: &str = "size";
+const OPTION_ALPHABET: &str = "alphabet";
+const OPTION_USABLE: &str = "usable";
+const OPTION_IS_EASY: &str = "is_easy";
+
+const HELP_KIND: &str = "\
+Kind of the identifiers.
+uuid4 is 122 random bits.
+uuid7 and ulid are a timestamp in milliseconds followed by random bits, and sort by time.
+nanoid is random characters of an alphabet.
+Default kind is uuid4.";
+
+const HELP_COUNT: &str = "\
+Number of identifiers, one in a line.
+Those of uuid7 and ulid sort in the order they are generated.
+Default number is 1.
+Minimum number is 1.
+Maximum number is 10000.";
+
+const HELP_SIZE: &str = "\
+Characters of a Nano ID.
+Default size is 21.
+Maximum size is 255.";
+
+const HELP_ALPHABET: &str = "\
+Characters of a Nano ID.
+Default alphabet is A-Za-z0-9_-.";
+
+const HELP_USABLE: &str = "\
+Characters of a Nano ID in the categories of the same flags as usable of passwords,
+instead of an alphabet.";
+
+const HELP_IS_EASY: &str = "\
+Leave out the characters which are hard to identify from the categories of usable.";
+
+pub fn build() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND)
+        .about("Generates identifiers of UUID version 4 or 7, ULID or Nano ID.")
+        .arg(
+            Arg::with_name(OPTION_KIND)
+                .short("k")
+                .long(OPTION_KIND)
+                .takes_value(true)
+                .value_name("KIND")
+                .multiple(false)
+                .possible_values(&KINDS)
+                .help(HELP_KIND),
+        )
+        .arg(
+            Arg::with_name(OPTION_COUNT)
+                .short("c")
+                .long(OPTION_COUNT)
+                .takes_value(true)
+                .value_name("N")
+                .multiple(false)
+                .validator(validate_count)
+                .help(HELP_COUNT),
+        )
+        .arg(
+            Arg::with_name(OPTION_SIZE)
+                .long(OPTION_SIZE)
+                .takes_value(true)
+                .value_name("SIZE")
+                .multiple(false)
+                .validator(validate_size)
+                .help(HELP_SIZE),
+        )
+        .arg(
+            Arg::with_name(OPTION_ALPHABET)
+                .long(OPTION_ALPHABET)
+                .takes_value(true)
+                .value_name("CHARACTERS")
+                .multiple(false)
+                .conflicts_with(OPTION_USABLE)
+                .help(HELP_ALPHABET),
+        )
+        .arg(
+            Arg::with_name(OPTION_USABLE)
+                .short("u")
+                .long(OPTION_USABLE)
+                .takes_value(true)
+                .value_name("USABLE CHARACTER")
+                .multiple(false)
+                .validator(super::validate_usable)
+                .help(HELP_USABLE),
+        )
+        .arg(
+            Arg::with_name(OPTION_IS_EASY)
+                .short("e")
+                .long(OPTION_IS_EASY)
+                .takes_value(false)
+                .multiple(false)
+                .requires(OPTION_USABLE)
+                .help(HELP_IS_EASY),
+        )
+}
+
+fn validate_count(value: String) -> Result<(), String> {
+    match value.parse() {
+        Ok(n) => Ids::validate_count(n),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+fn validate_size(value: String) -> Result<(), String> {
+    match value.parse() {
+        Ok(n) => Kind::validate_size(n),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+pub struct IdCommand {
+    pub kind: String,
+    pub count: usize,
+    pub size: Option<usize>,
+    pub alphabet: Option<String>,
+    pub usable: Option<String>,
+    pub is_easy: bool,
+}
+
+impl IdCommand {
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
+        let count = match arg_matches.value_of(OPTION_COUNT) {
+            Some(n) => n.parse().unwrap(),
+            None => 1,
+        };
+
+        IdCommand {
+            kind: String::from(arg_matches.value_of(OPTION_KIND).unwrap_or("uuid4")),
+            count,
+            size: arg_matches
+                .value_of(OPTION_SIZE)
+                .map(|n| n.parse().unwrap()),
+            alphabet: arg_matches.value_of(OPTION_ALPHABET).map(String::from),
+            usable: arg_matches.value_of(OPTION_USABLE).map(String::from),
+            is_easy: arg_matches.is_present(OPTION_IS_EASY),
+        }
+    }
+
+    /// Kind of the options, whose size, alphabet and usable are only for Nano ID.
+    pub fn kind(&self) -> Result<Kind, String> {
+        if self.kind != "nanoid" {
+            if self.size.is_some() || self.alphabet.is_some() || self.usable.is_some() {
+                return Err(String::from(
+                    "--size, --alphabet and --usable are only for --kind nanoid",
+                ));
+            }
+            return Kind::from_name(&self.kind);
+        }
+        let size = self.size.unwrap_or(DEFAULT_NANOID_SIZE);
+        match (&self.alphabet, &self.usable) {
+            (_, Some(usable)) => Kind::nanoid_of_categories(usable, self.is_easy, size),
+            (Some(alphabet), None) => Kind::nanoid(alphabet, size),
+            (None, None) => Kind::nanoid(NANOID_ALPHABET, size),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::kana::{Passphrase, DEFAULT_SEPARATOR, DEFAULT_WORDS, MIN_WORDS};
+
+pub const SUBCOMMAND: &str = "kana-passphrase";
+
+const OPTION_WORDS: &str = "words";
+const OPTION_SEPARATOR: &str = "separator";
+const OPTION_ROMAJI: &str = "romaji";
+
+const HELP_WORDS: &str = "\
+Number of words.
+Default number is 6.
+Minimum number is 4.
+Maximum number is 32.";
+
+const HELP_SEPARATOR: &str = "\
+Characters between the words, which must not be kana.
+Default separator is a space, which IME types as an ideographic space.";
+
+const HELP_ROMAJI: &str = "\
+Also show the passphrase in romaji to type on an ASCII keyboard.";
+
+pub fn build() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND)
+        .about("Generates a passphrase of hiragana words.")
+        .arg(
+            Arg::with_name(OPTION_WORDS)
+                .short("w")
+                .long(OPTION_WORDS)
+                .takes_value(true)
+                .value_name("N")
+                .multiple(false)
+                .validator(validate_words)
+                .help(HELP_WORDS),
+        )
+        .arg(
+            Arg::with_name(OPTION_SEPARATOR)
+                .long(OPTION_SEPARATOR)
+                .takes_value(true)
+                .value_name("SEPARATOR")
+                .multiple(false)
+                .validator(validate_separator)
+                .help(HELP_SEPARATOR),
+        )
+        .arg(
+            Arg::with_name(OPTION_ROMAJI)
+                .long(OPTION_ROMAJI)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_ROMAJI),
+        )
+}
+
+fn validate_words(value: String) -> Result<(), String> {
+    match value.parse() {
+        Ok(n) => Passphrase::new(n, DEFAULT_SEPARATOR).map(|_| ()),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+fn validate_separator(value: String) -> Result<(), String> {
+    Passphrase::new(MIN_WORDS, &value).map(|_| ())
+}
+
+pub struct KanaPassphraseCommand {
+    pub passphrase: Passphrase,
+    pub romaji: bool,
+}
+
+impl KanaPassphraseCommand {
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
+        let words = match arg_matches.value_of(OPTION_WORDS) {
+            Some(w) => w.parse().unwrap(),
+            None => DEFAULT_WORDS,
+        };
+
+        let separator = arg_matches
+            .value_of(OPTION_SEPARATOR)
+            .unwrap_or(DEFAULT_SEPARATOR);
+
+        let romaji = arg_matches.is_present(OPTION_ROMAJI);
+
+        KanaPassphraseCommand {
+            passphrase: Passphrase::new(words, separator).unwrap(),
+            romaji,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::mnemonic::{Mnemonic, DEFAULT_WORDS};
+
+pub const SUBCOMMAND: &str = "mnemonic";
+pub const SUBCOMMAND_VERIFY: &str = "verify";
+
+const OPTION_WORDS: &str = "words";
+const OPTION_PHRASE: &str = "PHRASE";
+
+const HELP_WORDS: &str = "\
+Number of words, which is 12, 15, 18, 21 or 24 for 128 to 256 bits of entropy.
+Default number is 12.";
+
+const HELP_PHRASE: &str = "\
+Words of the mnemonic.
+The mnemonic is read from the standard input if it is not specified,
+which keeps it out of the shell history and the process list.";
+
+pub fn build() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND)
+        .about("Generates a BIP39 mnemonic of English words with a checksum.")
+        .arg(
+            Arg::with_name(OPTION_WORDS)
+                .short("w")
+                .long(OPTION_WORDS)
+                .takes_value(true)
+                .value_name("N")
+                .multiple(false)
+                .validator(validate_words)
+                .help(HELP_WORDS),
+        )
+        .subcommand(
+            SubCommand::with_name(SUBCOMMAND_VERIFY)
+                .about("Verifies the words and the checksum of a BIP39 mnemonic.")
+                .arg(
+                    Arg::with_name(OPTION_PHRASE)
+                        .takes_value(true)
+                        .multiple(true)
+                        .help(HELP_PHRASE),
+                ),
+        )
+}
+
+fn validate_words(value: String) -> Result<(), String> {
+    match value.parse() {
+        Ok(n) => Mnemonic::new(n).map(|_| ()),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+pub struct MnemonicCommand {
+    pub mnemonic: Mnemonic,
+}
+
+impl MnemonicCommand {
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
+        let words = match arg_matches.value_of(OPTION_WORDS) {
+            Some(w) => w.parse().unwrap(),
+            None => DEFAULT_WORDS,
+        };
+
+        MnemonicCommand {
+            // The number of words is validated by clap.
+            mnemonic: Mnemonic::new(words).unwrap(),
+        }
+    }
+}
+
+pub struct VerifyCommand {
+    pub phrase: Option<String>,
+}
+
+impl VerifyCommand {
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
+        VerifyCommand {
+            phrase: arg_matches
+                .values_of(OPTION_PHRASE)
+                .map(|words| words.collect::<Vec<&str>>().join(" ")),
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::path::PathBuf;
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+
+use crate::otp::{Algorithm, Kind, Otp, DEFAULT_BYTES, DEFAULT_DIGITS, DEFAULT_PERIOD};
+
+pub const SUBCOMMAND: &str = "otp";
+pub const SUBCOMMAND_CODE: &str = "code";
+
+const OPTION_ISSUER: &str = "issuer";
+const OPTION_ACCOUNT: &str = "account";
+const OPTION_BYTES: &str = "bytes";
+const OPTION_DIGITS: &str = "digits";
+const OPTION_PERIOD: &str = "period";
+const OPTION_COUNTER: &str = "counter";
+const OPTION_ALGORITHM: &str = "algorithm";
+const OPTION_QR: &str = "qr";
+const OPTION_QR_OUTPUT: &str = "qr-output";
+const OPTION_SECRET: &str = "secret";
+const OPTION_TIME: &str = "time";
+
+const HELP_ISSUER: &str = "\
+Issuer of the account, such as the name of the service.";
+
+const HELP_ACCOUNT: &str = "\
+Name of the account, such as the user name or the email address.";
+
+const HELP_BYTES: &str = "\
+Bytes of the secret.
+Default bytes are 20.
+Minimum bytes are 16.
+Maximum bytes are 64.";
+
+const HELP_DIGITS: &str = "\
+Digits of codes.
+Default digits are 6.
+Minimum digits are 6.
+Maximum digits are 8.";
+
+const HELP_PERIOD: &str = "\
+Seconds in which a code of TOTP is valid.
+Default period is 30.";
+
+const HELP_COUNTER: &str = "\
+Counter of HOTP, which is used instead of TOTP.";
+
+const HELP_ALGORITHM: &str = "\
+Hash function of HMAC.
+    SHA1   : Supported by all authenticator apps.
+    SHA256 : SHA-256.
+    SHA512 : SHA-512.
+Default algorithm is SHA1.";
+
+const HELP_QR: &str = "\
+Also show the QR code of the otpauth URI in the terminal, which authenticator apps scan.";
+
+const HELP_QR_OUTPUT: &str = "\
+Write the QR code of the otpauth URI to a PNG or SVG image, chosen by the extension of FILE.
+The file is readable only by the owner.";
+
+const HELP_SECRET: &str = "\
+Base32 secret.
+The secret is read from the standard input if it is not specified,
+which keeps it out of the shell history and the process list.";
+
+const HELP_TIME: &str = "\
+Unix time in seconds of the code.
+Default time is now.";
+
+pub fn build() -> App<'static, 'static> {
+    let app = SubCommand::with_name(SUBCOMMAND)
+        .about("Generates a secret of TOTP or HOTP and its otpauth URI.")
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .arg(
+            Arg::with_name(OPTION_ISSUER)
+                .long(OPTION_ISSUER)
+                .takes_value(true)
+                .value_name("NAME")
+                .multiple(false)
+                .required(true)
+                .help(HELP_ISSUER),
+        )
+        .arg(
+            Arg::with_name(OPTION_ACCOUNT)
+                .long(OPTION_ACCOUNT)
+                .takes_value(true)
+                .value_name("NAME")
+                .multiple(false)
+                .required(true)
+                .help(HELP_ACCOUNT),
+        )
+        .arg(
+            Arg::with_name(OPTION_BYTES)
+                .long(OPTION_BYTES)
+                .takes_value(true)
+                .value_name("BYTES")
+                .multiple(false)
+                .validator(validate_bytes)
+                .help(HELP_BYTES),
+        );
+    let app = code_args(app)
+        .arg(
+            Arg::with_name(OPTION_QR)
+                .long(OPTION_QR)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_QR),
+        )
+        .arg(
+            Arg::with_name(OPTION_QR_OUTPUT)
+                .long(OPTION_QR_OUTPUT)
+                .takes_value(true)
+                .value_name("FILE")
+                .multiple(false)
+                .validator(super::validate_qr_output)
+                .help(HELP_QR_OUTPUT),
+        );
+    let code = SubCommand::with_name(SUBCOMMAND_CODE)
+        .about("Shows the code of a secret to verify the setup of an authenticator app.")
+        .arg(
+            Arg::with_name(OPTION_SECRET)
+                .long(OPTION_SECRET)
+                .takes_value(true)
+                .value_name("SECRET")
+                .multiple(false)
+                .validator(|v| crate::encoding::decode_base32(&v).map(|_| ()))
+                .help(HELP_SECRET),
+        )
+        .arg(
+            Arg::with_name(OPTION_TIME)
+                .long(OPTION_TIME)
+                .takes_value(true)
+                .value_name("SECONDS")
+                .multiple(false)
+                .conflicts_with(OPTION_COUNTER)
+                .validator(validate_number)
+                .help(HELP_TIME),
+        );
+    app.subcommand(code_args(code))
+}
+
+/// Options of the parameters of codes, shared by the secret and the code.
+fn code_args(app: App<'static, 'static>) -> App<'static, 'static> {
+    app.arg(
+        Arg::with_name(OPTION_DIGITS)
+            .long(OPTION_DIGITS)
+            .takes_value(true)
+            .value_name("DIGITS")
+            .multiple(false)
+            .validator(validate_digits)
+            .help(HELP_DIGITS),
+    )
+    .arg(
+        Arg::with_name(OPTION_PERIOD)
+            .long(OPTION_PERIOD)
+            .takes_value(true)
+            .value_name("SECONDS")
+            .multiple(false)
+            .conflicts_with(OPTION_COUNTER)
+            .validator(validate_period)
+            .help(HELP_PERIOD),
+    )
+    .arg(
+        Arg::with_name(OPTION_COUNTER)
+            .long(OPTION_COUNTER)
+            .takes_value(true)
+            .value_name("COUNTER")
+            .multiple(false)
+            .validator(validate_number)
+            .help(HELP_COUNTER),
+    )
+    .arg(
+        Arg::with_name(OPTION_ALGORITHM)
+            .long(OPTION_ALGORITHM)
+            .takes_value(true)
+            .value_name("ALGORITHM")
+            .multiple(false)
+            .validator(|v| Algorithm::from_name(&v).map(|_| ()))
+            .help(HELP_ALGORITHM),
+    )
+}
+
+fn validate_bytes(value: String) -> Result<(), String> {
+    match value.parse() {
+        Ok(n) => Otp::validate_bytes(n),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+fn validate_digits(value: String) -> Result<(), String> {
+    match value.parse() {
+        Ok(n) => Otp::validate_digits(n),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+fn validate_period(value: String) -> Result<(), String> {
+    match value.parse::<u64>() {
+        Ok(0) => Err(String::from("Needs 1 or more seconds: 0")),
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+fn validate_number(value: String) -> Result<(), String> {
+    match value.parse::<u64>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+/// Parameters of codes in `arg_matches`.
+fn parse_code_args(arg_matches: &ArgMatches) -> (Kind, u32, Algorithm) {
+    let kind = match arg_matches.value_of(OPTION_COUNTER) {
+        Some(c) => Kind::Hotp {
+            counter: c.parse().unwrap(),
+        },
+        None => Kind::Totp {
+            period: arg_matches
+                .value_of(OPTION_PERIOD)
+                .map_or(DEFAULT_PERIOD, |p| p.parse().unwrap()),
+        },
+    };
+    let digits = arg_matches
+        .value_of(OPTION_DIGITS)
+        .map_or(DEFAULT_DIGITS, |d| d.parse().unwrap());
+    let algorithm = arg_matches
+        .value_of(OPTION_ALGORITHM)
+        .map_or(Algorithm::Sha1, |a| Algorithm::from_name(a).unwrap());
+    (kind, digits, algorithm)
+}
+
+pub struct OtpCommand {
+    pub issuer: String,
+    pub account: String,
+    pub bytes: usize,
+    pub kind: Kind,
+    pub digits: u32,
+    pub algorithm: Algorithm,
+    pub qr: bool,
+    pub qr_output: Option<PathBuf>,
+}
+
+impl OtpCommand {
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
+        let (kind, digits, algorithm) = parse_code_args(arg_matches);
+        OtpCommand {
+            issuer: String::from(arg_matches.value_of(OPTION_ISSUER).unwrap()),
+            account: String::from(arg_matches.value_of(OPTION_ACCOUNT).unwrap()),
+            bytes: arg_matches
+                .value_of(OPTION_BYTES)
+                .map_or(DEFAULT_BYTES, |b| b.parse().unwrap()),
+            kind,
+            digits,
+            algorithm,
+            qr: arg_matches.is_present(OPTION_QR),
+            qr_output: arg_matches.value_of(OPTION_QR_OUTPUT).map(PathBuf::from),
+        }
+    }
+}
+
+pub struct CodeCommand {
+    pub secret: Option<String>,
+    pub time: Option<u64>,
+    pub kind: Kind,
+    pub digits: u32,
+    pub algorithm: Algorithm,
+}
+
+impl CodeCommand {
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
+        let (kind, digits, algorithm) = parse_code_args(arg_matches);
+        CodeCommand {
+            secret: arg_matches.value_of(OPTION_SECRET).map(String::from),
+            time: arg_matches
+                .value_of(OPTION_TIME)
+                .map(|t| t.parse().unwrap()),
+            kind,
+            digits,
+            algorithm,
+        }
+    }
+}
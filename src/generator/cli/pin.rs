@@ -0,0 +1,53 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::pin::{Pin, DEFAULT_LENGTH};
+
+pub const SUBCOMMAND: &str = "pin";
+
+const OPTION_LENGTH: &str = "length";
+
+const HELP_LENGTH: &str = "\
+Number of digits.
+Default length is 6.
+Minimum length is 4.
+Maximum length is 10.";
+
+pub fn build() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND)
+        .about(
+            "Generates a numeric PIN, leaving out repeated digits, runs, dates, keypad patterns \
+             and common PINs.",
+        )
+        .arg(
+            Arg::with_name(OPTION_LENGTH)
+                .short("l")
+                .long(OPTION_LENGTH)
+                .takes_value(true)
+                .value_name("LENGTH")
+                .multiple(false)
+                .validator(validate_length)
+                .help(HELP_LENGTH),
+        )
+}
+
+fn validate_length(value: String) -> Result<(), String> {
+    match value.parse() {
+        Ok(n) => Pin::validate_length(n),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+pub struct PinCommand {
+    pub length: usize,
+}
+
+impl PinCommand {
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
+        let length = match arg_matches.value_of(OPTION_LENGTH) {
+            Some(l) => l.parse().unwrap(),
+            None => DEFAULT_LENGTH,
+        };
+
+        PinCommand { length }
+    }
+}
@@ -0,0 +1,151 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::format::DEFAULT_SEPARATOR;
+use crate::recovery::{
+    RecoveryCodes, DEFAULT_COUNT, DEFAULT_GROUPS, DEFAULT_GROUP_LENGTH, DEFAULT_USABLE,
+};
+
+pub const SUBCOMMAND: &str = "recovery-codes";
+
+const OPTION_COUNT: &str = "count";
+const OPTION_GROUPS: &str = "groups";
+const OPTION_GROUP_LENGTH: &str = "group-len";
+const OPTION_USABLE: &str = "usable";
+const OPTION_SEPARATOR: &str = "separator";
+const OPTION_HASH: &str = "hash";
+
+const HELP_COUNT: &str = "\
+Number of codes, which are all different.
+Default number is 10.
+Minimum number is 1.
+Maximum number is 100.";
+
+const HELP_GROUPS: &str = "\
+Number of groups in a code.
+Default number is 2.";
+
+const HELP_GROUP_LENGTH: &str = "\
+Characters in a group.
+Default length is 4.
+A code has 8 to 64 characters without the separators.";
+
+const HELP_USABLE: &str = "\
+The categories of the characters, in the same flags as usable of passwords.
+Only easy to identify characters are used.
+Default categories are ln.";
+
+const HELP_SEPARATOR: &str = "\
+Characters between the groups, which must not be characters of the codes.
+Default separator is -.";
+
+const HELP_HASH: &str = "\
+Also show a salted hash of each code without the separators, to store on the server,
+as salt$hash in hex of PBKDF2-HMAC-SHA256 of 600000 rounds.";
+
+pub fn build() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND)
+        .about("Generates a set of one-time recovery codes of easy to identify characters.")
+        .arg(
+            Arg::with_name(OPTION_COUNT)
+                .short("c")
+                .long(OPTION_COUNT)
+                .takes_value(true)
+                .value_name("N")
+                .multiple(false)
+                .validator(validate_count)
+                .help(HELP_COUNT),
+        )
+        .arg(
+            Arg::with_name(OPTION_GROUPS)
+                .long(OPTION_GROUPS)
+                .takes_value(true)
+                .value_name("N")
+                .multiple(false)
+                .validator(validate_number)
+                .help(HELP_GROUPS),
+        )
+        .arg(
+            Arg::with_name(OPTION_GROUP_LENGTH)
+                .long(OPTION_GROUP_LENGTH)
+                .takes_value(true)
+                .value_name("LENGTH")
+                .multiple(false)
+                .validator(validate_number)
+                .help(HELP_GROUP_LENGTH),
+        )
+        .arg(
+            Arg::with_name(OPTION_USABLE)
+                .short("u")
+                .long(OPTION_USABLE)
+                .takes_value(true)
+                .value_name("USABLE CHARACTER")
+                .multiple(false)
+                .validator(super::validate_usable)
+                .help(HELP_USABLE),
+        )
+        .arg(
+            Arg::with_name(OPTION_SEPARATOR)
+                .long(OPTION_SEPARATOR)
+                .takes_value(true)
+                .value_name("SEPARATOR")
+                .multiple(false)
+                .help(HELP_SEPARATOR),
+        )
+        .arg(
+            Arg::with_name(OPTION_HASH)
+                .long(OPTION_HASH)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_HASH),
+        )
+}
+
+fn validate_count(value: String) -> Result<(), String> {
+    match value.parse() {
+        Ok(n) => RecoveryCodes::validate_count(n),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+fn validate_number(value: String) -> Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err(String::from("Needs 1 or more: 0")),
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+pub struct RecoveryCodesCommand {
+    pub count: usize,
+    pub groups: usize,
+    pub group_length: usize,
+    pub usable: String,
+    pub separator: String,
+    pub hash: bool,
+}
+
+impl RecoveryCodesCommand {
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
+        let number = |name, default| match arg_matches.value_of(name) {
+            Some(n) => n.parse().unwrap(),
+            None => default,
+        };
+
+        RecoveryCodesCommand {
+            count: number(OPTION_COUNT, DEFAULT_COUNT),
+            groups: number(OPTION_GROUPS, DEFAULT_GROUPS),
+            group_length: number(OPTION_GROUP_LENGTH, DEFAULT_GROUP_LENGTH),
+            usable: String::from(
+                arg_matches
+                    .value_of(OPTION_USABLE)
+                    .unwrap_or(DEFAULT_USABLE),
+            ),
+            separator: String::from(
+                arg_matches
+                    .value_of(OPTION_SEPARATOR)
+                    .unwrap_or(DEFAULT_SEPARATOR),
+            ),
+            hash: arg_matches.is_present(OPTION_HASH),
+        }
+    }
+}
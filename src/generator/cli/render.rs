@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub const SUBCOMMAND: &str = "render";
+
+const OPTION_TEMPLATE: &str = "template";
+const OPTION_OUTPUT: &str = "output";
+const OPTION_KEEP: &str = "keep";
+
+const HELP_TEMPLATE: &str = "\
+Template file, or - for standard input.
+{{ pswdgn length=24 usable=lun easy=true symbols=!@# }} is replaced with a new password.
+{{ pswdgn:NAME }} is replaced with the same password wherever NAME appears.";
+
+const HELP_OUTPUT: &str = "\
+File to write instead of standard output.
+The file is readable and writable only by the owner.";
+
+const HELP_KEEP: &str = "\
+Keep the passwords already filled in the output file.";
+
+pub fn build() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND)
+        .about("Replaces the markers in a template with generated passwords.")
+        .arg(
+            Arg::with_name(OPTION_TEMPLATE)
+                .required(true)
+                .value_name("TEMPLATE")
+                .help(HELP_TEMPLATE),
+        )
+        .arg(
+            Arg::with_name(OPTION_OUTPUT)
+                .short("o")
+                .long(OPTION_OUTPUT)
+                .takes_value(true)
+                .value_name("FILE")
+                .multiple(false)
+                .help(HELP_OUTPUT),
+        )
+        .arg(
+            Arg::with_name(OPTION_KEEP)
+                .long(OPTION_KEEP)
+                .takes_value(false)
+                .multiple(false)
+                .requires(OPTION_OUTPUT)
+                .help(HELP_KEEP),
+        )
+}
+
+pub struct RenderCommand {
+    /// `None` is standard input.
+    pub template: Option<PathBuf>,
+    /// `None` is standard output.
+    pub output: Option<PathBuf>,
+    pub keep: bool,
+}
+
+impl RenderCommand {
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
+        let template = match arg_matches.value_of(OPTION_TEMPLATE) {
+            Some("-") | None => None,
+            Some(t) => Some(PathBuf::from(t)),
+        };
+
+        let output = arg_matches.value_of(OPTION_OUTPUT).map(PathBuf::from);
+
+        let keep = arg_matches.is_present(OPTION_KEEP);
+
+        RenderCommand {
+            template,
+            output,
+            keep,
+        }
+    }
+}
@@ -0,0 +1,8 @@
+use clap::{App, SubCommand};
+
+pub const SUBCOMMAND: &str = "rpc";
+
+pub fn build() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND)
+        .about("Speaks line-delimited JSON-RPC 2.0 on standard input and output.")
+}
@@ -0,0 +1,109 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::server::{Config, Listen, DEFAULT_MAX_BODY, DEFAULT_WORKERS};
+
+pub const SUBCOMMAND: &str = "serve";
+
+const OPTION_LISTEN: &str = "listen";
+const OPTION_TOKEN: &str = "token";
+const OPTION_MAX_BODY: &str = "max-body";
+const OPTION_WORKERS: &str = "workers";
+
+const DEFAULT_LISTEN: &str = "127.0.0.1:8080";
+
+const HELP_LISTEN: &str = "\
+Address to listen on.
+Default address is 127.0.0.1:8080.
+unix:PATH listens on a Unix domain socket.";
+
+const HELP_TOKEN: &str = "\
+Bearer token required by every request.";
+
+const HELP_MAX_BODY: &str = "\
+Maximum size of request bodies in bytes.
+Default size is 16384.";
+
+const HELP_WORKERS: &str = "\
+Number of threads which handle requests.
+Default number is 4.";
+
+pub fn build() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND)
+        .about("Serves JSON endpoints over HTTP.")
+        .arg(
+            Arg::with_name(OPTION_LISTEN)
+                .long(OPTION_LISTEN)
+                .takes_value(true)
+                .value_name("ADDRESS")
+                .multiple(false)
+                .validator(validate_listen)
+                .help(HELP_LISTEN),
+        )
+        .arg(
+            Arg::with_name(OPTION_TOKEN)
+                .long(OPTION_TOKEN)
+                .takes_value(true)
+                .value_name("TOKEN")
+                .multiple(false)
+                .env("PSWDGN_TOKEN")
+                .hide_env_values(true)
+                .help(HELP_TOKEN),
+        )
+        .arg(
+            Arg::with_name(OPTION_MAX_BODY)
+                .long(OPTION_MAX_BODY)
+                .takes_value(true)
+                .value_name("BYTES")
+                .multiple(false)
+                .validator(validate_positive)
+                .help(HELP_MAX_BODY),
+        )
+        .arg(
+            Arg::with_name(OPTION_WORKERS)
+                .long(OPTION_WORKERS)
+                .takes_value(true)
+                .value_name("THREADS")
+                .multiple(false)
+                .validator(validate_positive)
+                .help(HELP_WORKERS),
+        )
+}
+
+fn validate_listen(value: String) -> Result<(), String> {
+    value.parse::<Listen>().map(|_| ())
+}
+
+fn validate_positive(value: String) -> Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err(format!("Needs 1 or more: {}", value)),
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+pub fn parse(arg_matches: &ArgMatches) -> Config {
+    let listen = arg_matches
+        .value_of(OPTION_LISTEN)
+        .unwrap_or(DEFAULT_LISTEN)
+        .parse()
+        .unwrap();
+
+    let token = arg_matches.value_of(OPTION_TOKEN).map(String::from);
+
+    let max_body = match arg_matches.value_of(OPTION_MAX_BODY) {
+        Some(b) => b.parse().unwrap(),
+        None => DEFAULT_MAX_BODY,
+    };
+
+    let workers = match arg_matches.value_of(OPTION_WORKERS) {
+        Some(w) => w.parse().unwrap(),
+        None => DEFAULT_WORKERS,
+    };
+
+    Config {
+        listen,
+        token,
+        max_body,
+        workers,
+    }
+}
@@ -0,0 +1,114 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::encoding::Encoding;
+use crate::token::{Token, DEFAULT_BYTES};
+
+pub const SUBCOMMAND: &str = "token";
+
+const OPTION_BYTES: &str = "bytes";
+const OPTION_ENCODING: &str = "encoding";
+const OPTION_PADDING: &str = "padding";
+const OPTION_NO_PADDING: &str = "no-padding";
+
+const HELP_BYTES: &str = "\
+Random bytes of the token.
+Default bytes are 32.
+Minimum bytes are 8.
+Maximum bytes are 1024.";
+
+const HELP_ENCODING: &str = "\
+Encoding of the bytes.
+    hex       : Lowercase hexadecimal.
+    base32    : Base32 of RFC 4648.
+    crockford : Crockford's Base32, without I, L, O and U.
+    base58    : Base58 of Bitcoin, without 0, O, I and l.
+    base64    : Base64 of RFC 4648.
+    base64url : Base64 of RFC 4648 for URLs and file names.
+Default encoding is base64url.";
+
+const HELP_PADDING: &str = "\
+Pad Base32 and Base64 with '=', which base32 and base64 do by default.";
+
+const HELP_NO_PADDING: &str = "\
+Leave out the padding of Base32 and Base64.";
+
+pub fn build() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND)
+        .about("Generates a token of random bytes in a text encoding.")
+        .arg(
+            Arg::with_name(OPTION_BYTES)
+                .short("b")
+                .long(OPTION_BYTES)
+                .takes_value(true)
+                .value_name("BYTES")
+                .multiple(false)
+                .validator(validate_bytes)
+                .help(HELP_BYTES),
+        )
+        .arg(
+            Arg::with_name(OPTION_ENCODING)
+                .short("e")
+                .long(OPTION_ENCODING)
+                .takes_value(true)
+                .value_name("ENCODING")
+                .multiple(false)
+                .validator(|v| Encoding::from_name(&v).map(|_| ()))
+                .help(HELP_ENCODING),
+        )
+        .arg(
+            Arg::with_name(OPTION_PADDING)
+                .long(OPTION_PADDING)
+                .takes_value(false)
+                .multiple(false)
+                .conflicts_with(OPTION_NO_PADDING)
+                .help(HELP_PADDING),
+        )
+        .arg(
+            Arg::with_name(OPTION_NO_PADDING)
+                .long(OPTION_NO_PADDING)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_NO_PADDING),
+        )
+}
+
+fn validate_bytes(value: String) -> Result<(), String> {
+    match value.parse() {
+        Ok(n) => Token::validate_bytes(n),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+pub struct TokenCommand {
+    pub bytes: usize,
+    pub encoding: Encoding,
+    pub padding: Option<bool>,
+}
+
+impl TokenCommand {
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
+        let bytes = match arg_matches.value_of(OPTION_BYTES) {
+            Some(b) => b.parse().unwrap(),
+            None => DEFAULT_BYTES,
+        };
+
+        let encoding = match arg_matches.value_of(OPTION_ENCODING) {
+            Some(e) => Encoding::from_name(e).unwrap(),
+            None => Encoding::Base64Url,
+        };
+
+        let padding = if arg_matches.is_present(OPTION_PADDING) {
+            Some(true)
+        } else if arg_matches.is_present(OPTION_NO_PADDING) {
+            Some(false)
+        } else {
+            None
+        };
+
+        TokenCommand {
+            bytes,
+            encoding,
+            padding,
+        }
+    }
+}
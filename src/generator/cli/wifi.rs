@@ -0,0 +1,164 @@
+use std::path::PathBuf;
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::wifi::{Network, Security, DEFAULT_LENGTH};
+
+pub const SUBCOMMAND: &str = "wifi";
+
+const OPTION_SSID: &str = "ssid";
+const OPTION_LENGTH: &str = "length";
+const OPTION_SECURITY: &str = "security";
+const OPTION_PSK: &str = "psk";
+const OPTION_CONF: &str = "conf";
+const OPTION_URI: &str = "uri";
+const OPTION_QR: &str = "qr";
+const OPTION_QR_OUTPUT: &str = "qr-output";
+
+const HELP_SSID: &str = "\
+SSID of the network, which is 1 to 32 bytes.";
+
+const HELP_LENGTH: &str = "\
+Length of the passphrase.
+Default length is 20.
+Minimum length is 8.
+Maximum length is 63.";
+
+const HELP_SECURITY: &str = "\
+Security of the network.
+    wpa2 : WPA2-Personal.
+    wpa3 : WPA3-Personal.
+Default security is wpa2.";
+
+const HELP_PSK: &str = "\
+Also show the PSK derived from the passphrase and the SSID by PBKDF2-SHA1.";
+
+const HELP_CONF: &str = "\
+Also show a network block of wpa_supplicant.conf.";
+
+const HELP_URI: &str = "\
+Also show the payload of QR codes, as WIFI:T:WPA;S:...;P:...;;
+or WIFI:T:WPA;R:1;S:...;P:...;; for wpa3.";
+
+const HELP_QR: &str = "\
+Also show the QR code of the network in the terminal, which phones join when they scan it.";
+
+const HELP_QR_OUTPUT: &str = "\
+Write the QR code of the network to a PNG or SVG image, chosen by the extension of FILE.
+The file is readable only by the owner.";
+
+pub fn build() -> App<'static, 'static> {
+    SubCommand::with_name(SUBCOMMAND)
+        .about("Generates a WPA passphrase of letters, numbers and device-safe symbols.")
+        .arg(
+            Arg::with_name(OPTION_SSID)
+                .long(OPTION_SSID)
+                .takes_value(true)
+                .value_name("NAME")
+                .multiple(false)
+                .required(true)
+                .validator(|v| Network::validate_ssid(&v))
+                .help(HELP_SSID),
+        )
+        .arg(
+            Arg::with_name(OPTION_LENGTH)
+                .short("l")
+                .long(OPTION_LENGTH)
+                .takes_value(true)
+                .value_name("LENGTH")
+                .multiple(false)
+                .validator(validate_length)
+                .help(HELP_LENGTH),
+        )
+        .arg(
+            Arg::with_name(OPTION_SECURITY)
+                .long(OPTION_SECURITY)
+                .takes_value(true)
+                .value_name("SECURITY")
+                .multiple(false)
+                .validator(|v| Security::from_name(&v).map(|_| ()))
+                .help(HELP_SECURITY),
+        )
+        .arg(
+            Arg::with_name(OPTION_PSK)
+                .long(OPTION_PSK)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_PSK),
+        )
+        .arg(
+            Arg::with_name(OPTION_CONF)
+                .long(OPTION_CONF)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_CONF),
+        )
+        .arg(
+            Arg::with_name(OPTION_URI)
+                .long(OPTION_URI)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_URI),
+        )
+        .arg(
+            Arg::with_name(OPTION_QR)
+                .long(OPTION_QR)
+                .takes_value(false)
+                .multiple(false)
+                .help(HELP_QR),
+        )
+        .arg(
+            Arg::with_name(OPTION_QR_OUTPUT)
+                .long(OPTION_QR_OUTPUT)
+                .takes_value(true)
+                .value_name("FILE")
+                .multiple(false)
+                .validator(super::validate_qr_output)
+                .help(HELP_QR_OUTPUT),
+        )
+}
+
+fn validate_length(value: String) -> Result<(), String> {
+    match value.parse() {
+        Ok(n) => Network::validate_length(n),
+        Err(_) => Err(format!("Not number value: {}", value)),
+    }
+}
+
+pub struct WifiCommand {
+    pub ssid: String,
+    pub length: usize,
+    pub security: Security,
+    pub psk: bool,
+    pub conf: bool,
+    pub uri: bool,
+    pub qr: bool,
+    pub qr_output: Option<PathBuf>,
+}
+
+impl WifiCommand {
+    pub fn from_matches(arg_matches: &ArgMatches) -> Self {
+        let ssid = String::from(arg_matches.value_of(OPTION_SSID).unwrap());
+
+        let length = match arg_matches.value_of(OPTION_LENGTH) {
+            Some(l) => l.parse().unwrap(),
+            None => DEFAULT_LENGTH,
+        };
+
+        let security = match arg_matches.value_of(OPTION_SECURITY) {
+            Some(s) => Security::from_name(s).unwrap(),
+            None => Security::Wpa2,
+        };
+
+        WifiCommand {
+            ssid,
+            length,
+            security,
+            psk: arg_matches.is_present(OPTION_PSK),
+            conf: arg_matches.is_present(OPTION_CONF),
+            uri: arg_matches.is_present(OPTION_URI),
+            qr: arg_matches.is_present(OPTION_QR),
+            qr_output: arg_matches.value_of(OPTION_QR_OUTPUT).map(PathBuf::from),
+        }
+    }
+}
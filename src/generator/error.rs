@@ -7,14 +7,68 @@ pub enum Error {
     CategoryFlagErr(String),
     NotSymbolErr(String),
     CharactersErr(()),
+    UnusableCharacterErr(String),
+    CategoryMissingErr(()),
+    CategoryExcludedErr(String),
+    NotUsableErr(String),
+    RequireExcessErr(usize),
+    RequireMissingErr(String),
+    InfeasibleErr(()),
+    ContextErr(String),
+    FirstCharacterErr(String),
+    LastCharacterErr(String),
+    RepeatExcessErr(String),
+    SequenceExcessErr(String),
+    ClassRepeatExcessErr(String),
+    DuplicateErr(String),
+    ComplexityErr(()),
+    ProfileErr(String),
+    CustomCharacterErr(String),
+    CustomNameErr(String),
+    CustomFlagErr(String),
+    ByteExcessErr(usize),
+    LayoutErr(String),
+    PagesConflictErr(()),
+    UniqueConflictErr(()),
+    PageSwitchErr(String),
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
         match self {
             Error::LengthInsufficientErr(i) | Error::LengthExcessErr(i) => i.fmt(f),
-            Error::CategoryFlagErr(e) | Error::NotSymbolErr(e) => e.fmt(f),
+            Error::CategoryFlagErr(e)
+            | Error::NotSymbolErr(e)
+            | Error::UnusableCharacterErr(e)
+            | Error::CategoryExcludedErr(e)
+            | Error::NotUsableErr(e)
+            | Error::RequireMissingErr(e)
+            | Error::ContextErr(e)
+            | Error::FirstCharacterErr(e)
+            | Error::LastCharacterErr(e)
+            | Error::RepeatExcessErr(e)
+            | Error::SequenceExcessErr(e)
+            | Error::ClassRepeatExcessErr(e)
+            | Error::DuplicateErr(e)
+            | Error::ProfileErr(e)
+            | Error::CustomCharacterErr(e)
+            | Error::CustomNameErr(e)
+            | Error::CustomFlagErr(e)
+            | Error::LayoutErr(e)
+            | Error::PageSwitchErr(e) => e.fmt(f),
+            Error::RequireExcessErr(i) | Error::ByteExcessErr(i) => i.fmt(f),
             Error::CharactersErr(_) => "Usable character category is not found".fmt(f),
+            Error::CategoryMissingErr(_) => "Usable character category is missing".fmt(f),
+            Error::InfeasibleErr(_) => "No password satisfies the constraints".fmt(f),
+            Error::ComplexityErr(_) => "Constraints are too complex to sample".fmt(f),
+            Error::PagesConflictErr(_) => {
+                "Mobile mode cannot be combined with run, duplicate or byte limits nor shift weighting"
+                    .fmt(f)
+            }
+            Error::UniqueConflictErr(_) => {
+                "No duplicate characters cannot be combined with sequence, class run or byte limits nor shift weighting"
+                    .fmt(f)
+            }
         }
     }
 }
@@ -26,7 +80,31 @@ impl std::error::Error for Error {
             | Error::LengthExcessErr(_)
             | Error::CategoryFlagErr(_)
             | Error::NotSymbolErr(_)
-            | Error::CharactersErr(_) => None,
+            | Error::CharactersErr(_)
+            | Error::UnusableCharacterErr(_)
+            | Error::CategoryMissingErr(_)
+            | Error::CategoryExcludedErr(_)
+            | Error::NotUsableErr(_)
+            | Error::RequireExcessErr(_)
+            | Error::RequireMissingErr(_)
+            | Error::InfeasibleErr(_)
+            | Error::ContextErr(_)
+            | Error::FirstCharacterErr(_)
+            | Error::LastCharacterErr(_)
+            | Error::RepeatExcessErr(_)
+            | Error::SequenceExcessErr(_)
+            | Error::ClassRepeatExcessErr(_)
+            | Error::DuplicateErr(_)
+            | Error::ComplexityErr(_)
+            | Error::ProfileErr(_)
+            | Error::CustomCharacterErr(_)
+            | Error::CustomNameErr(_)
+            | Error::CustomFlagErr(_)
+            | Error::ByteExcessErr(_)
+            | Error::LayoutErr(_)
+            | Error::PagesConflictErr(_)
+            | Error::UniqueConflictErr(_)
+            | Error::PageSwitchErr(_) => None,
         }
     }
 }
@@ -0,0 +1,780 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::generator::{
+    character::{with_presets, Category, CustomCategory},
+    cli,
+    error::Error,
+    Generator, MAX_LENGTH, MAX_REQUIRED, MIN_LENGTH,
+};
+
+/// Options of generated passwords, shared by the command line and the services.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Policy {
+    pub length: usize,
+    pub usable: String,
+    /// Definitions of custom categories, whose flags can be used in `usable`.
+    pub categories: Vec<CustomCategory>,
+    /// Comma separated names of custom categories used besides `usable`.
+    pub category: String,
+    #[serde(alias = "easy")]
+    pub is_easy: bool,
+    pub symbols: String,
+    pub exclude: String,
+    pub require: String,
+    /// Comma separated contexts, such as `url,shell`.
+    pub safe_for: String,
+    /// Comma separated easy profiles, such as `monospace,ocr`.
+    pub easy_profile: String,
+    /// Groups of confused characters separated by spaces, such as `5 S` or `rn m`.
+    pub confusables: Vec<String>,
+    /// Flags of the categories of the first character, empty for any category.
+    pub first: String,
+    /// Flags of the categories of the last character, empty for any category.
+    pub last: String,
+    pub no_leading_symbol: bool,
+    /// 0 is no limit, as well as `max_sequence` and `max_class_repeat`.
+    pub max_repeat: usize,
+    pub max_sequence: usize,
+    pub max_class_repeat: usize,
+    pub no_duplicate_chars: bool,
+    /// Maximum number of bytes in UTF-8, 0 for no limit.
+    pub max_bytes: usize,
+    /// Comma separated keyboard layouts, such as `us,jis`, or `any`.
+    pub layout: String,
+    /// Whether the characters typed with Shift or AltGr are drawn less often.
+    pub minimize_shift: bool,
+    /// Whether the characters of each page of phone keyboards are typed in one run.
+    pub mobile: bool,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy {
+            length: MIN_LENGTH as usize,
+            usable: Category::flags(),
+            categories: Vec::new(),
+            category: String::default(),
+            is_easy: false,
+            symbols: String::default(),
+            exclude: String::default(),
+            require: String::default(),
+            safe_for: String::default(),
+            easy_profile: String::default(),
+            confusables: Vec::new(),
+            first: String::default(),
+            last: String::default(),
+            no_leading_symbol: false,
+            max_repeat: 0,
+            max_sequence: 0,
+            max_class_repeat: 0,
+            no_duplicate_chars: false,
+            max_bytes: 0,
+            layout: String::default(),
+            minimize_shift: false,
+            mobile: false,
+        }
+    }
+}
+
+impl From<cli::CommandLine> for Policy {
+    fn from(cli: cli::CommandLine) -> Self {
+        Policy {
+            length: cli.length,
+            usable: cli.flags,
+            categories: Vec::new(),
+            category: cli.category,
+            is_easy: cli.is_easy,
+            symbols: cli.symbols,
+            exclude: cli.exclude,
+            require: cli.require,
+            safe_for: cli.safe_for,
+            easy_profile: cli.easy_profile,
+            confusables: Vec::new(),
+            first: cli.first,
+            last: cli.last,
+            no_leading_symbol: cli.no_leading_symbol,
+            max_repeat: cli.max_repeat,
+            max_sequence: cli.max_sequence,
+            max_class_repeat: cli.max_class_repeat,
+            no_duplicate_chars: cli.no_duplicate_chars,
+            max_bytes: cli.max_bytes,
+            layout: cli.layout,
+            minimize_shift: cli.minimize_shift,
+            mobile: cli.mobile,
+        }
+    }
+}
+
+impl Policy {
+    /// Sets an option from its textual form, as in templates and Terraform queries.
+    pub fn set_option(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "length" => {
+                self.length = value
+                    .parse()
+                    .map_err(|_| format!("Not number value: {}", value))?
+            }
+            "usable" => self.usable = String::from(value),
+            "category" => self.category = String::from(value),
+            "easy" | "is_easy" => {
+                self.is_easy = value
+                    .parse()
+                    .map_err(|_| format!("Not boolean value: {}", value))?
+            }
+            "symbols" => self.symbols = String::from(value),
+            "exclude" => self.exclude = String::from(value),
+            "require" => self.require = String::from(value),
+            "safe_for" | "safe-for" => self.safe_for = String::from(value),
+            "easy_profile" | "easy-profile" => self.easy_profile = String::from(value),
+            "first" => self.first = String::from(value),
+            "last" => self.last = String::from(value),
+            "no_leading_symbol" | "no-leading-symbol" => {
+                self.no_leading_symbol = value
+                    .parse()
+                    .map_err(|_| format!("Not boolean value: {}", value))?
+            }
+            "max_repeat" | "max-repeat" => self.max_repeat = Self::parse_number(value)?,
+            "max_sequence" | "max-sequence" => self.max_sequence = Self::parse_number(value)?,
+            "max_class_repeat" | "max-class-repeat" => {
+                self.max_class_repeat = Self::parse_number(value)?
+            }
+            "no_duplicate_chars" | "no-duplicate-chars" => {
+                self.no_duplicate_chars = value
+                    .parse()
+                    .map_err(|_| format!("Not boolean value: {}", value))?
+            }
+            "max_bytes" | "max-bytes" => self.max_bytes = Self::parse_number(value)?,
+            "layout" => self.layout = String::from(value),
+            "minimize_shift" | "minimize-shift" => {
+                self.minimize_shift = value
+                    .parse()
+                    .map_err(|_| format!("Not boolean value: {}", value))?
+            }
+            "mobile" => {
+                self.mobile = value
+                    .parse()
+                    .map_err(|_| format!("Not boolean value: {}", value))?
+            }
+            _ => return Err(format!("unknown option: {}", key)),
+        }
+        Ok(())
+    }
+
+    fn parse_number(value: &str) -> Result<usize, String> {
+        value
+            .parse()
+            .map_err(|_| format!("Not number value: {}", value))
+    }
+
+    /// Validates the options in the same way as the command line.
+    pub fn validate(&self) -> Result<(), String> {
+        cli::validate_length(self.length.to_string())?;
+        let categories = self.custom_categories();
+        CustomCategory::validate(&categories).map_err(|e| self.message(e))?;
+        self.validate_flags(&self.usable)?;
+        for name in self.category.split(',').filter(|n| !n.is_empty()) {
+            if categories.iter().all(|c| c.name != name) {
+                return Err(format!("unknown category: {}", name));
+            }
+        }
+        cli::validate_symbols(self.symbols.clone())?;
+        self.validate_require()?;
+        cli::validate_safe_for(self.safe_for.clone())?;
+        cli::validate_easy_profile(self.easy_profile.clone())?;
+        cli::validate_layout(self.layout.clone())?;
+        if !self.first.is_empty() {
+            self.validate_flags(&self.first)?;
+        }
+        if !self.last.is_empty() {
+            self.validate_flags(&self.last)?;
+        }
+        Ok(())
+    }
+
+    /// Custom categories with the kana presets which they leave available.
+    fn custom_categories(&self) -> Vec<CustomCategory> {
+        with_presets(&self.categories)
+    }
+
+    /// Validates `flags` of the built-in and custom categories.
+    fn validate_flags(&self, flags: &str) -> Result<(), String> {
+        let categories = self.custom_categories();
+        cli::validate_usable(
+            flags
+                .chars()
+                .filter(|f| categories.iter().all(|c| c.flag != *f))
+                .collect(),
+        )
+    }
+
+    /// Validates the required characters which are not in any custom category.
+    fn validate_require(&self) -> Result<(), String> {
+        let custom: HashSet<char> = self
+            .custom_categories()
+            .iter()
+            .filter_map(|c| c.char_set().ok())
+            .flatten()
+            .collect();
+        cli::validate_require(self.require.nfc().filter(|c| !custom.contains(c)).collect())
+    }
+
+    /// Usable flags with the flags of the categories of `category`.
+    fn flags(&self) -> String {
+        let mut flags = self.usable.clone();
+        let categories = self.custom_categories();
+        for name in self.category.split(',') {
+            if let Some(c) = categories.iter().find(|c| c.name == name) {
+                flags.push(c.flag);
+            }
+        }
+        flags
+    }
+
+    pub fn generator(&self) -> Result<Generator, String> {
+        self.validate()?;
+        Generator::from_custom(
+            self.length,
+            self.flags(),
+            self.is_easy,
+            self.symbols.clone(),
+            &self.custom_categories(),
+        )
+        .and_then(|g| g.exclude(&self.exclude))
+        .and_then(|g| g.safe_for(&self.safe_for))
+        .and_then(|g| match self.layout.as_str() {
+            "" => Ok(g),
+            l => g.layout(l),
+        })
+        .and_then(|g| g.easy_profile(&self.easy_profile))
+        .and_then(|g| g.confusables(&self.confusables))
+        .and_then(|g| g.require(&self.require))
+        .and_then(|g| match self.first.as_str() {
+            "" => Ok(g),
+            f => g.first(f),
+        })
+        .and_then(|g| match self.last.as_str() {
+            "" => Ok(g),
+            l => g.last(l),
+        })
+        .and_then(|g| {
+            if self.no_leading_symbol {
+                g.no_leading_symbol()
+            } else {
+                Ok(g)
+            }
+        })
+        .and_then(|g| match self.max_repeat {
+            0 => Ok(g),
+            n => g.max_repeat(n),
+        })
+        .and_then(|g| match self.max_sequence {
+            0 => Ok(g),
+            n => g.max_sequence(n),
+        })
+        .and_then(|g| match self.max_class_repeat {
+            0 => Ok(g),
+            n => g.max_class_repeat(n),
+        })
+        .and_then(|g| {
+            if self.no_duplicate_chars {
+                g.no_duplicate_chars()
+            } else {
+                Ok(g)
+            }
+        })
+        .and_then(|g| match self.max_bytes {
+            0 => Ok(g),
+            n => g.max_bytes(n),
+        })
+        .and_then(|g| {
+            if self.minimize_shift {
+                g.minimize_shift()
+            } else {
+                Ok(g)
+            }
+        })
+        .and_then(|g| if self.mobile { g.mobile() } else { Ok(g) })
+        .map_err(|e| self.message(e))
+    }
+
+    /// Checks that `password` is at least as long as `length` and satisfies the other options.
+    pub fn check(&self, password: &str) -> Result<(), String> {
+        match self.generator()?.check(password) {
+            Ok(_) => Ok(()),
+            Err(Error::LengthInsufficientErr(l)) => {
+                Err(format!("Needs {} or more: {}", self.length, l))
+            }
+            Err(e) => Err(self.message(e)),
+        }
+    }
+
+    fn message(&self, e: Error) -> String {
+        match e {
+            Error::LengthInsufficientErr(l) => format!("Needs {} or more: {}", MIN_LENGTH, l),
+            Error::LengthExcessErr(l) => format!("Needs {} or less: {}", MAX_LENGTH, l),
+            Error::CategoryFlagErr(cs) => format!("unknown usable flags: {}", cs),
+            Error::NotSymbolErr(cs) => format!("unknown symbol character: {}", cs),
+            Error::UnusableCharacterErr(cs) => format!("unusable character: {}", cs),
+            Error::CategoryExcludedErr(fs) => {
+                format!("all characters are excluded from usable flags: {}", fs)
+            }
+            Error::NotUsableErr(cs) => format!("required character is not usable: {}", cs),
+            Error::RequireExcessErr(n) => {
+                format!("Needs {} or less required characters: {}", MAX_REQUIRED, n)
+            }
+            Error::RequireMissingErr(cs) => format!("required character is missing: {}", cs),
+            Error::ContextErr(cs) => format!("unknown context: {}", cs),
+            Error::ProfileErr(cs) => format!("unknown easy profile: {}", cs),
+            Error::LayoutErr(cs) => format!("unknown layout: {}", cs),
+            Error::FirstCharacterErr(c) => format!("first character is not allowed: {}", c),
+            Error::LastCharacterErr(c) => format!("last character is not allowed: {}", c),
+            Error::RepeatExcessErr(cs) => format!("too many repeated characters: {}", cs),
+            Error::SequenceExcessErr(cs) => format!("too long sequence: {}", cs),
+            Error::ClassRepeatExcessErr(cs) => {
+                format!("too many consecutive characters of a category: {}", cs)
+            }
+            Error::DuplicateErr(cs) => format!("duplicated characters: {}", cs),
+            Error::PageSwitchErr(cs) => format!("keyboard page is switched back to: {}", cs),
+            Error::CustomCharacterErr(cs) if cs.is_empty() => {
+                String::from("a custom category has no characters")
+            }
+            Error::CustomCharacterErr(cs) => {
+                format!("character cannot stand alone in a category: {}", cs)
+            }
+            Error::CustomNameErr(n) => format!("category name is empty or duplicated: {}", n),
+            Error::CustomFlagErr(f) => format!("category flag is already used: {}", f),
+            Error::ByteExcessErr(n) => format!("Needs {} bytes or less: {}", self.max_bytes, n),
+            e => e.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default() {
+        assert_eq!(
+            Policy::default(),
+            Policy {
+                length: 8,
+                usable: String::from("luns"),
+                categories: Vec::new(),
+                category: String::default(),
+                is_easy: false,
+                symbols: String::default(),
+                exclude: String::default(),
+                require: String::default(),
+                safe_for: String::default(),
+                easy_profile: String::default(),
+                confusables: Vec::new(),
+                first: String::default(),
+                last: String::default(),
+                no_leading_symbol: false,
+                max_repeat: 0,
+                max_sequence: 0,
+                max_class_repeat: 0,
+                no_duplicate_chars: false,
+                max_bytes: 0,
+                layout: String::default(),
+                minimize_shift: false,
+                mobile: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let policy: Policy = serde_json::from_str(r#"{"length": 12, "easy": true}"#).unwrap();
+        assert_eq!(policy.length, 12);
+        assert!(policy.is_easy);
+        assert_eq!(policy.usable, Category::flags());
+
+        assert!(serde_json::from_str::<Policy>(r#"{"lenght": 12}"#).is_err());
+    }
+
+    #[test]
+    fn test_set_option() {
+        let mut policy = Policy::default();
+        policy.set_option("length", "12").unwrap();
+        policy.set_option("usable", "ln").unwrap();
+        policy.set_option("category", "greek").unwrap();
+        policy.set_option("easy", "true").unwrap();
+        policy.set_option("symbols", "!@").unwrap();
+        policy.set_option("exclude", "0o").unwrap();
+        policy.set_option("require", "x").unwrap();
+        policy.set_option("safe-for", "url,xml").unwrap();
+        policy.set_option("easy-profile", "ocr").unwrap();
+        policy.set_option("first", "l").unwrap();
+        policy.set_option("last", "ln").unwrap();
+        policy.set_option("no-leading-symbol", "true").unwrap();
+        policy.set_option("max-repeat", "2").unwrap();
+        policy.set_option("max_sequence", "3").unwrap();
+        policy.set_option("max-class-repeat", "4").unwrap();
+        policy.set_option("no-duplicate-chars", "false").unwrap();
+        policy.set_option("max-bytes", "64").unwrap();
+        policy.set_option("layout", "jis").unwrap();
+        policy.set_option("minimize-shift", "true").unwrap();
+        policy.set_option("mobile", "true").unwrap();
+        assert_eq!(
+            policy,
+            Policy {
+                length: 12,
+                usable: String::from("ln"),
+                categories: Vec::new(),
+                category: String::from("greek"),
+                is_easy: true,
+                symbols: String::from("!@"),
+                exclude: String::from("0o"),
+                require: String::from("x"),
+                safe_for: String::from("url,xml"),
+                easy_profile: String::from("ocr"),
+                confusables: Vec::new(),
+                first: String::from("l"),
+                last: String::from("ln"),
+                no_leading_symbol: true,
+                max_repeat: 2,
+                max_sequence: 3,
+                max_class_repeat: 4,
+                no_duplicate_chars: false,
+                max_bytes: 64,
+                layout: String::from("jis"),
+                minimize_shift: true,
+                mobile: true,
+            }
+        );
+        assert_eq!(
+            policy.set_option("length", "twelve"),
+            Err(String::from("Not number value: twelve"))
+        );
+        assert_eq!(
+            policy.set_option("is_easy", "yes"),
+            Err(String::from("Not boolean value: yes"))
+        );
+        assert_eq!(
+            policy.set_option("max-repeat", "-1"),
+            Err(String::from("Not number value: -1"))
+        );
+        assert_eq!(
+            policy.set_option("lenght", "12"),
+            Err(String::from("unknown option: lenght"))
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(Policy::default().validate(), Ok(()));
+        let policy = Policy {
+            length: 7,
+            ..Policy::default()
+        };
+        assert_eq!(policy.validate(), Err(String::from("Needs 8 or more: 7")));
+        let policy = Policy {
+            usable: String::from("lx"),
+            ..Policy::default()
+        };
+        assert_eq!(
+            policy.validate(),
+            Err(String::from("unknown usable flags: x"))
+        );
+        let policy = Policy {
+            symbols: String::from("!a"),
+            ..Policy::default()
+        };
+        assert_eq!(
+            policy.validate(),
+            Err(String::from("unknown symbol character: a"))
+        );
+        let policy = Policy {
+            safe_for: String::from("url,toml"),
+            ..Policy::default()
+        };
+        assert_eq!(
+            policy.validate(),
+            Err(String::from("unknown context: toml"))
+        );
+        let policy = Policy {
+            easy_profile: String::from("phone,fax"),
+            ..Policy::default()
+        };
+        assert_eq!(
+            policy.validate(),
+            Err(String::from("unknown easy profile: fax"))
+        );
+    }
+
+    #[test]
+    fn test_generator() {
+        let policy = Policy {
+            usable: String::from("ns"),
+            symbols: String::from("!@"),
+            exclude: String::from("!@"),
+            ..Policy::default()
+        };
+        assert_eq!(
+            policy.generator().err(),
+            Some(String::from(
+                "all characters are excluded from usable flags: s"
+            ))
+        );
+        let policy = Policy {
+            usable: String::from("n"),
+            require: String::from("a"),
+            ..Policy::default()
+        };
+        assert_eq!(
+            policy.generator().err(),
+            Some(String::from("required character is not usable: a"))
+        );
+        let policy = Policy {
+            usable: String::from("n"),
+            require: String::from("0123456789"),
+            ..Policy::default()
+        };
+        assert_eq!(
+            policy.generator().err(),
+            Some(String::from("Needs 8 or less required characters: 10"))
+        );
+        let policy = Policy {
+            usable: String::from("ln"),
+            require: String::from("abcdefgh"),
+            ..Policy::default()
+        };
+        assert_eq!(
+            policy.generator().err(),
+            Some(String::from("No password satisfies the constraints"))
+        );
+    }
+
+    #[test]
+    fn test_custom_categories() {
+        let policy: Policy = serde_json::from_str(
+            r#"{
+                "length": 10,
+                "usable": "n",
+                "categories": [{"name": "cyrillic", "flag": "c", "characters": "абвгдежз"}],
+                "category": "cyrillic",
+                "max_bytes": 14
+            }"#,
+        )
+        .unwrap();
+        let generator = policy.generator().unwrap();
+        for _ in 0..100 {
+            let password = generator.generate();
+            assert_eq!(policy.check(&password), Ok(()));
+        }
+        assert_eq!(
+            policy.check("абвгд12345"),
+            Err(String::from("Needs 14 bytes or less: 15"))
+        );
+        let policy = Policy {
+            usable: String::from("nx"),
+            ..policy
+        };
+        assert_eq!(
+            policy.validate(),
+            Err(String::from("unknown usable flags: x"))
+        );
+        let policy = Policy {
+            usable: String::from("n"),
+            category: String::from("greek"),
+            ..policy
+        };
+        assert_eq!(
+            policy.validate(),
+            Err(String::from("unknown category: greek"))
+        );
+        let policy = Policy {
+            category: String::default(),
+            usable: String::from("c"),
+            require: String::from("ж☃"),
+            ..policy
+        };
+        assert_eq!(
+            policy.validate(),
+            Err(String::from("unknown required character: ☃"))
+        );
+        let policy = Policy {
+            require: String::default(),
+            categories: vec![CustomCategory {
+                name: String::from("marks"),
+                flag: 'c',
+                characters: String::from("a\u{301}\u{20dd}"),
+            }],
+            ..policy
+        };
+        assert_eq!(
+            policy.validate(),
+            Err(String::from(
+                "character cannot stand alone in a category: \u{20dd}"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_kana() {
+        let policy = Policy {
+            length: 12,
+            usable: String::from("h"),
+            category: String::from("katakana"),
+            is_easy: true,
+            ..Policy::default()
+        };
+        let generator = policy.generator().unwrap();
+        for _ in 0..100 {
+            let password = generator.generate();
+            assert!(!password.contains(|c| "へべぺりヘベペリ".contains(c)));
+            assert_eq!(policy.check(&password), Ok(()));
+            // The same password with decomposed dakuten.
+            assert_eq!(policy.check(&password.nfd().collect::<String>()), Ok(()));
+        }
+        // Halfwidth katakana are normalized by NFKC.
+        assert_eq!(policy.check("ｶﾞｷﾞﾊﾟあいうえおかきくけ"), Ok(()));
+        assert_eq!(
+            policy.check("ガギパあいうえおかきくけ１"),
+            Err(String::from("unusable character: １"))
+        );
+
+        // A custom category takes the flag of hiragana.
+        let policy = Policy {
+            categories: vec![CustomCategory {
+                name: String::from("hangul"),
+                flag: 'h',
+                characters: String::from("가나다라"),
+            }],
+            ..policy
+        };
+        let password = policy.generator().unwrap().generate();
+        assert!(!password
+            .chars()
+            .any(|c| ('\u{3041}'..='\u{309f}').contains(&c)));
+    }
+
+    #[test]
+    fn test_layout() {
+        let policy: Policy =
+            serde_json::from_str(r#"{"length": 12, "usable": "lns", "layout": "qwertz"}"#).unwrap();
+        let generator = policy.generator().unwrap();
+        for _ in 0..100 {
+            assert!(!generator.generate().contains(|c| "^`".contains(c)));
+        }
+        let minimized = Policy {
+            minimize_shift: true,
+            ..policy.clone()
+        };
+        assert!(minimized.generator().unwrap().entropy() < generator.entropy());
+        assert_eq!(minimized.check("abcdefgh123-"), Ok(()));
+
+        let policy = Policy {
+            layout: String::from("us,bepo"),
+            ..policy
+        };
+        assert_eq!(policy.validate(), Err(String::from("unknown layout: bepo")));
+    }
+
+    #[test]
+    fn test_mobile() {
+        let policy: Policy =
+            serde_json::from_str(r#"{"length": 12, "usable": "lns", "mobile": true}"#).unwrap();
+        let generator = policy.generator().unwrap();
+        assert!(generator.entropy() < generator.uniform_entropy().unwrap());
+        for _ in 0..100 {
+            assert_eq!(generator.check(&generator.generate()), Ok(()));
+        }
+        assert_eq!(policy.check("abcdef123-#%"), Ok(()));
+        assert_eq!(
+            policy.check("abc123def#%-"),
+            Err(String::from("keyboard page is switched back to: def"))
+        );
+
+        let policy = Policy {
+            max_repeat: 2,
+            ..policy
+        };
+        assert_eq!(
+            policy.generator().err(),
+            Some(String::from(
+                "Mobile mode cannot be combined with run, duplicate or byte limits nor shift weighting"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_deserialize_confusables() {
+        let policy: Policy =
+            serde_json::from_str(r#"{"usable": "ln", "confusables": ["rn m", "a o"]}"#).unwrap();
+        assert_eq!(policy.confusables, vec!["rn m", "a o"]);
+        assert_eq!(
+            policy.check("abcde12345"),
+            Err(String::from("unusable character: a"))
+        );
+        assert_eq!(policy.check("bcdef12345"), Ok(()));
+    }
+
+    #[test]
+    fn test_check() {
+        let policy = Policy {
+            length: 10,
+            usable: String::from("ln"),
+            ..Policy::default()
+        };
+        assert_eq!(policy.check("abcde12345"), Ok(()));
+        assert_eq!(
+            policy.check("abcde1234"),
+            Err(String::from("Needs 10 or more: 9"))
+        );
+        assert_eq!(
+            policy.check("abcde1234!"),
+            Err(String::from("unusable character: !"))
+        );
+        assert_eq!(
+            policy.check("abcdefghij"),
+            Err(String::from("Usable character category is missing"))
+        );
+        let policy = Policy {
+            require: String::from("z"),
+            ..policy
+        };
+        assert_eq!(
+            policy.check("abcde12345"),
+            Err(String::from("required character is missing: z"))
+        );
+        let policy = Policy {
+            require: String::default(),
+            first: String::from("l"),
+            last: String::from("n"),
+            ..policy
+        };
+        assert_eq!(policy.check("abcde12345"), Ok(()));
+        assert_eq!(
+            policy.check("1bcde12345"),
+            Err(String::from("first character is not allowed: 1"))
+        );
+        assert_eq!(
+            policy.check("abcde1234e"),
+            Err(String::from("last character is not allowed: e"))
+        );
+        let policy = Policy {
+            max_sequence: 4,
+            no_duplicate_chars: true,
+            ..policy
+        };
+        // Distinct characters are not sampled uniformly with sequence limits.
+        assert_eq!(
+            policy.generator().err(),
+            Some(Error::UniqueConflictErr(()).to_string())
+        );
+        let policy = Policy {
+            no_duplicate_chars: false,
+            ..policy
+        };
+        assert_eq!(
+            policy.check("abcde12345"),
+            Err(String::from("too long sequence: abcde"))
+        );
+    }
+}
@@ -0,0 +1,1052 @@
+mod pages;
+mod rejection;
+mod unique;
+
+use self::pages::Pages;
+use self::rejection::Rejection;
+use self::unique::Unique;
+use crate::generator::error::Error;
+use rand::Rng;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Maximum number of character sets which every string must intersect.
+pub(crate) const MAX_SETS: usize = 12;
+/// Maximum number of entries of the table of a sampler.
+pub(crate) const MAX_TABLE: usize = 1 << 23;
+
+/// Limits on runs and duplicates of characters, and on the UTF-8 length. 0 is no limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct Limits {
+    /// Maximum number of the same consecutive characters.
+    pub(crate) max_repeat: usize,
+    /// Maximum length of ascending or descending sequences such as `abc` and `321`.
+    pub(crate) max_sequence: usize,
+    /// Maximum number of consecutive characters of the same class.
+    pub(crate) max_class_repeat: usize,
+    /// Whether every character differs.
+    pub(crate) unique: bool,
+    /// Maximum number of bytes in UTF-8.
+    pub(crate) max_bytes: usize,
+}
+
+/// Samples strings with the sampler which supports the limits.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Sampling {
+    Table(Sampler),
+    Unique(Unique),
+    Pages(Pages),
+    Rejection(Rejection),
+}
+
+impl Sampling {
+    /// `classes` are the classes of characters for `max_class_repeat`, and `weights` are the
+    /// weights of the characters other than 1.
+    /// With `pages`, the characters of each page are typed in one run, without limits nor
+    /// weights.
+    pub(crate) fn new(
+        alphabets: &[Vec<char>],
+        sets: &[HashSet<char>],
+        classes: &[HashSet<char>],
+        limits: Limits,
+        weights: &HashMap<char, f64>,
+        pages: Option<&[HashSet<char>]>,
+    ) -> Result<Self, Error> {
+        if let Some(pages) = pages {
+            if limits != Limits::default() || !weights.is_empty() {
+                Err(Error::PagesConflictErr(()))
+            } else {
+                Pages::new(alphabets, sets, pages).map(Sampling::Pages)
+            }
+        } else if !limits.unique {
+            match Sampler::new(alphabets, sets, classes, limits, weights) {
+                Err(Error::ComplexityErr(_)) => {
+                    Rejection::new(alphabets, sets, classes, limits, weights)
+                        .map(Sampling::Rejection)
+                }
+                sampler => sampler.map(Sampling::Table),
+            }
+        } else if Self::binds(alphabets, limits) || !weights.is_empty() {
+            // Characters of distinct strings are interchangeable only without these limits.
+            Err(Error::UniqueConflictErr(()))
+        } else {
+            match Unique::new(alphabets, sets) {
+                Err(Error::ComplexityErr(_)) => {
+                    Rejection::new(alphabets, sets, classes, limits, weights)
+                        .map(Sampling::Rejection)
+                }
+                unique => unique.map(Sampling::Unique),
+            }
+        }
+    }
+
+    /// Whether any string of `alphabets` exceeds the sequence, class or byte limits.
+    fn binds(alphabets: &[Vec<char>], limits: Limits) -> bool {
+        let longest: usize = alphabets
+            .iter()
+            .map(|a| a.iter().map(|c| c.len_utf8()).max().unwrap_or(0))
+            .sum();
+        let runs = |limit: usize| limit > 0 && limit < alphabets.len();
+        runs(limits.max_sequence)
+            || runs(limits.max_class_repeat)
+            || (limits.max_bytes > 0 && limits.max_bytes < longest)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        match self {
+            Sampling::Table(s) => s.is_empty(),
+            Sampling::Unique(s) => s.is_empty(),
+            Sampling::Pages(s) => s.is_empty(),
+            Sampling::Rejection(s) => s.is_empty(),
+        }
+    }
+
+    pub(crate) fn entropy(&self) -> f64 {
+        match self {
+            Sampling::Table(s) => s.entropy(),
+            Sampling::Unique(s) => s.entropy(),
+            Sampling::Pages(s) => s.entropy(),
+            Sampling::Rejection(s) => s.entropy(),
+        }
+    }
+
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        match self {
+            Sampling::Table(s) => s.sample(rng),
+            Sampling::Unique(s) => s.sample(rng),
+            Sampling::Pages(s) => s.sample(rng),
+            Sampling::Rejection(s) => s.sample(rng),
+        }
+    }
+}
+
+/// Run which the last character continues.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Run {
+    Single,
+    Repeat(usize),
+    Up(usize),
+    Down(usize),
+}
+
+/// What the rest of a string depends on after a character.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct State {
+    /// Sets which are not intersected yet.
+    mask: usize,
+    /// The last character, its class or nothing, whichever the limits depend on.
+    last: usize,
+    run: Run,
+    class_run: usize,
+    /// Bytes of the string so far when they are limited.
+    bytes: usize,
+}
+
+/// Characters of a position which lead to the same states.
+#[derive(Debug, PartialEq)]
+struct Group {
+    mask: usize,
+    last: usize,
+    class: usize,
+    /// Bytes of the characters in UTF-8 when they are limited.
+    width: usize,
+    /// Weight of each character relative to the others.
+    weight: f64,
+    characters: Vec<char>,
+}
+
+impl Group {
+    /// Sum of the weights of the characters.
+    fn weight_sum(&self) -> f64 {
+        self.characters.len() as f64 * self.weight
+    }
+}
+
+/// Samples strings uniformly from the strings which take every character from the alphabet
+/// of its position, which intersect every set and which keep the limits of runs and bytes.
+/// With weights, each string is sampled in proportion to the product of the weights of its
+/// characters instead.
+///
+/// The strings are built character by character, weighted by the number of ways to complete
+/// them, so that no string is rejected.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Sampler {
+    positions: Vec<Vec<Group>>,
+    /// Characters of `last` when repeats or sequences are limited.
+    characters: Vec<char>,
+    /// Class of each `last`.
+    classes: Vec<usize>,
+    limits: Limits,
+    lasts: usize,
+    /// `(mask, last)` of each slot of the states. The sets which `last` intersects are never
+    /// in `mask`, so that those masks are left out.
+    slots: Vec<(usize, usize)>,
+    /// Slot of each `mask * lasts + last`.
+    slot_of: Vec<usize>,
+    runs: usize,
+    class_runs: usize,
+    /// Number of the bytes tracked in states, 1 when the bytes are not limited.
+    budget: usize,
+    /// `table[r][state]` is the ratio of the ways to fill the positions after `r` from `state`
+    /// intersecting the rest of the sets to all the ways to fill them.
+    table: Vec<Vec<f64>>,
+    /// The same ratio for all the positions.
+    ratio: f64,
+    /// Expected log2 of the product of the weights of a sampled string.
+    log_weight: f64,
+    full: usize,
+}
+
+impl Sampler {
+    /// `sets` must be `MAX_SETS` or less.
+    pub(crate) fn new(
+        alphabets: &[Vec<char>],
+        sets: &[HashSet<char>],
+        classes: &[HashSet<char>],
+        limits: Limits,
+        weights: &HashMap<char, f64>,
+    ) -> Result<Self, Error> {
+        assert!(sets.len() <= MAX_SETS);
+        let weight_of = |c: &char| weights.get(c).copied().unwrap_or(1.0);
+        let full = (1 << sets.len()) - 1;
+        let class_of = |c: &char| classes.iter().position(|s| s.contains(c)).unwrap_or(0);
+        let mask_of = |c: &char| {
+            sets.iter()
+                .enumerate()
+                .filter(|(_, s)| s.contains(c))
+                .fold(0, |m, (i, _)| m | 1 << i)
+        };
+        let by_character = limits.max_repeat > 0 || limits.max_sequence > 0;
+        let by_class = limits.max_class_repeat > 0;
+        // The bytes are tracked only when the longest strings exceed the limit.
+        let longest: usize = alphabets
+            .iter()
+            .map(|a| a.iter().map(|c| c.len_utf8()).max().unwrap_or(0))
+            .sum();
+        let by_bytes = limits.max_bytes > 0 && limits.max_bytes < longest;
+
+        let mut characters: Vec<char> = Vec::new();
+        if by_character {
+            characters = alphabets.iter().flatten().copied().collect();
+            characters.sort_unstable();
+            characters.dedup();
+        }
+        let last_classes: Vec<usize> = if by_character {
+            characters.iter().map(class_of).collect()
+        } else if by_class {
+            (0..classes.len().max(1)).collect()
+        } else {
+            vec![0]
+        };
+
+        let positions: Vec<Vec<Group>> = alphabets
+            .iter()
+            .map(|alphabet| {
+                // Groups are sorted by their last characters to find the neighbors.
+                let mut groups: BTreeMap<(usize, usize, usize, usize, u64), Vec<char>> =
+                    BTreeMap::new();
+                for c in alphabet {
+                    let mask = mask_of(c);
+                    let class = if by_class { class_of(c) } else { 0 };
+                    let last = if by_character {
+                        characters.binary_search(c).unwrap()
+                    } else {
+                        class
+                    };
+                    let width = if by_bytes { c.len_utf8() } else { 0 };
+                    groups
+                        .entry((last, mask, class, width, weight_of(c).to_bits()))
+                        .or_default()
+                        .push(*c);
+                }
+                groups
+                    .into_iter()
+                    .map(|((last, mask, class, width, weight), mut characters)| {
+                        characters.sort_unstable();
+                        characters.dedup();
+                        Group {
+                            mask,
+                            last,
+                            class,
+                            width,
+                            weight: f64::from_bits(weight),
+                            characters,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let lasts = last_classes.len();
+        let mut slots = Vec::new();
+        let mut slot_of = vec![usize::MAX; (full + 1) * lasts];
+        for mask in 0..=full {
+            for last in 0..lasts {
+                let own = if by_character {
+                    mask_of(&characters[last])
+                } else {
+                    0
+                };
+                if mask & own == 0 {
+                    slot_of[mask * lasts + last] = slots.len();
+                    slots.push((mask, last));
+                }
+            }
+        }
+
+        let mut sampler = Sampler {
+            positions,
+            characters,
+            lasts,
+            slots,
+            slot_of,
+            classes: last_classes,
+            limits,
+            runs: 1
+                + limits.max_repeat.saturating_sub(1)
+                + 2 * limits.max_sequence.saturating_sub(1),
+            class_runs: limits.max_class_repeat.max(1),
+            budget: if by_bytes { limits.max_bytes + 1 } else { 1 },
+            table: Vec::new(),
+            ratio: 0.0,
+            log_weight: 0.0,
+            full,
+        };
+        let size = sampler.slots.len() * sampler.runs * sampler.class_runs * sampler.budget;
+        let classes = sampler.classes.iter().max().map_or(1, |c| c + 1);
+        let keys = (full + 1) * classes * sampler.class_runs * sampler.budget;
+        let groups = sampler.positions.iter().map(Vec::len).max().unwrap_or(0);
+        if size.saturating_mul(alphabets.len()) > MAX_TABLE
+            || keys.saturating_mul(groups + 1) > MAX_TABLE
+        {
+            return Err(Error::ComplexityErr(()));
+        }
+
+        let length = sampler.positions.len();
+        let mut table = vec![vec![0f64; size]; length];
+        if let Some(last) = table.last_mut() {
+            for (i, t) in last.iter_mut().enumerate() {
+                if sampler.state(i).mask == 0 {
+                    *t = 1.0;
+                }
+            }
+        }
+        // With weights, `logs[state]` is the sum of log2 of the weights of the ways to fill the
+        // positions after the current one, in the same ratio as `table`.
+        let weighted = sampler.positions.iter().flatten().any(|g| g.weight != 1.0);
+        let mut logs = vec![0f64; if weighted { size } else { 0 }];
+        let by_character = !sampler.characters.is_empty();
+        let budget = sampler.budget;
+        for r in (0..length.saturating_sub(1)).rev() {
+            let groups = &sampler.positions[r + 1];
+            let n: f64 = groups.iter().map(Group::weight_sum).sum();
+            if n == 0.0 {
+                logs.iter_mut().for_each(|l| *l = 0.0);
+                continue;
+            }
+            // Sums of the weights of the groups before and from each group, when their
+            // characters break the runs of the last character but not of its class.
+            let mut before = vec![vec![0f64; groups.len() + 1]; keys];
+            let mut from = before.clone();
+            let mut before_logs = if weighted { before.clone() } else { Vec::new() };
+            let mut from_logs = before_logs.clone();
+            for mask in 0..=full {
+                for class in 0..classes {
+                    for class_run in 1..=sampler.class_runs {
+                        for bytes in 0..budget {
+                            let key = sampler.key(mask, classes, class, class_run, bytes);
+                            let (weights, log_weights): (Vec<f64>, Vec<f64>) = groups
+                                .iter()
+                                .map(|g| {
+                                    let class_run = if sampler.limits.max_class_repeat > 0
+                                        && g.class == class
+                                    {
+                                        class_run + 1
+                                    } else {
+                                        1
+                                    };
+                                    if class_run > sampler.class_runs || bytes + g.width >= budget {
+                                        return (0.0, 0.0);
+                                    }
+                                    let next = State {
+                                        mask: mask & !g.mask,
+                                        last: g.last,
+                                        run: Run::Single,
+                                        class_run,
+                                        bytes: bytes + g.width,
+                                    };
+                                    sampler.terms(g, &table[r + 1], &logs, &next)
+                                })
+                                .unzip();
+                            for (j, w) in weights.iter().enumerate() {
+                                before[key][j + 1] = before[key][j] + w;
+                            }
+                            for (j, w) in weights.iter().enumerate().rev() {
+                                from[key][j] = from[key][j + 1] + w;
+                            }
+                            if weighted {
+                                for (j, w) in log_weights.iter().enumerate() {
+                                    before_logs[key][j + 1] = before_logs[key][j] + w;
+                                }
+                                for (j, w) in log_weights.iter().enumerate().rev() {
+                                    from_logs[key][j] = from_logs[key][j + 1] + w;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            let mut next_logs = vec![0f64; logs.len()];
+            for i in 0..size {
+                let state = sampler.state(i);
+                let class = sampler.classes[state.last];
+                let key = sampler.key(state.mask, classes, class, state.class_run, state.bytes);
+                // Only the neighbors of the last character can continue its runs.
+                let (low, high) = if by_character {
+                    (
+                        groups.partition_point(|g| g.last + 1 < state.last),
+                        groups.partition_point(|g| g.last <= state.last + 1),
+                    )
+                } else {
+                    (0, 0)
+                };
+                let (neighbors, neighbor_logs) = groups[low..high]
+                    .iter()
+                    .filter_map(|g| {
+                        let next = sampler.next(Some(&state), g)?;
+                        Some(sampler.terms(g, &table[r + 1], &logs, &next))
+                    })
+                    .fold((0.0, 0.0), |(w, l), (gw, gl)| (w + gw, l + gl));
+                table[r][i] = (before[key][low] + neighbors + from[key][high]) / n;
+                if weighted {
+                    next_logs[i] =
+                        (before_logs[key][low] + neighbor_logs + from_logs[key][high]) / n;
+                }
+            }
+            logs = next_logs;
+        }
+        if let Some(groups) = sampler.positions.first() {
+            let n: f64 = groups.iter().map(Group::weight_sum).sum();
+            if n > 0.0 {
+                let (ratio, log_weight) = groups
+                    .iter()
+                    .filter_map(|g| {
+                        let next = sampler.next(None, g)?;
+                        Some(sampler.terms(g, &table[0], &logs, &next))
+                    })
+                    .fold((0.0, 0.0), |(w, l), (gw, gl)| (w + gw, l + gl));
+                sampler.ratio = ratio / n;
+                if ratio > 0.0 {
+                    sampler.log_weight = log_weight / ratio;
+                }
+            }
+        }
+        sampler.table = table;
+        Ok(sampler)
+    }
+
+    /// Weight of the ways to continue with the characters of `group` to `next`, and the sum of
+    /// log2 of their weights, in the ratio of `table` and `logs` of the next position.
+    fn terms(&self, group: &Group, table: &[f64], logs: &[f64], next: &State) -> (f64, f64) {
+        let i = self.index(next);
+        let weight = group.weight_sum();
+        let log = logs
+            .get(i)
+            .map_or(0.0, |l| weight * (l + group.weight.log2() * table[i]));
+        (weight * table[i], log)
+    }
+
+    /// Index of the sums of the weights of the groups which break the runs of a character.
+    fn key(
+        &self,
+        mask: usize,
+        classes: usize,
+        class: usize,
+        class_run: usize,
+        bytes: usize,
+    ) -> usize {
+        (((mask * classes + class) * self.class_runs + class_run - 1) * self.budget) + bytes
+    }
+
+    fn index(&self, state: &State) -> usize {
+        let repeats = self.limits.max_repeat.saturating_sub(1);
+        let sequences = self.limits.max_sequence.saturating_sub(1);
+        let run = match state.run {
+            Run::Single => 0,
+            Run::Repeat(k) => k - 1,
+            Run::Up(k) => repeats + k - 1,
+            Run::Down(k) => repeats + sequences + k - 1,
+        };
+        let slot = self.slot_of[state.mask * self.lasts + state.last];
+        ((slot * self.runs + run) * self.class_runs + state.class_run - 1) * self.budget
+            + state.bytes
+    }
+
+    fn state(&self, index: usize) -> State {
+        let repeats = self.limits.max_repeat.saturating_sub(1);
+        let sequences = self.limits.max_sequence.saturating_sub(1);
+        let bytes = index % self.budget;
+        let index = index / self.budget;
+        let class_run = index % self.class_runs + 1;
+        let index = index / self.class_runs;
+        let run = match index % self.runs {
+            0 => Run::Single,
+            i if i <= repeats => Run::Repeat(i + 1),
+            i if i <= repeats + sequences => Run::Up(i - repeats + 1),
+            i => Run::Down(i - repeats - sequences + 1),
+        };
+        let (mask, last) = self.slots[index / self.runs];
+        State {
+            mask,
+            last,
+            run,
+            class_run,
+            bytes,
+        }
+    }
+
+    /// The state after a character of `group` follows `state`, or `None` if the character
+    /// exceeds the limits.
+    fn next(&self, state: Option<&State>, group: &Group) -> Option<State> {
+        let bytes = state.map_or(0, |s| s.bytes) + group.width;
+        if bytes >= self.budget {
+            return None;
+        }
+        let state = match state {
+            Some(s) => s,
+            None => {
+                return Some(State {
+                    mask: self.full & !group.mask,
+                    last: group.last,
+                    run: Run::Single,
+                    class_run: 1,
+                    bytes,
+                })
+            }
+        };
+        let limits = &self.limits;
+        let class_run = if limits.max_class_repeat > 0 && self.classes[state.last] == group.class {
+            state.class_run + 1
+        } else {
+            1
+        };
+        let run = if self.characters.is_empty() {
+            Run::Single
+        } else {
+            let step = self.characters[group.last] as i64 - self.characters[state.last] as i64;
+            match (step, state.run) {
+                (0, Run::Repeat(k)) if limits.max_repeat > 0 => Run::Repeat(k + 1),
+                (0, _) if limits.max_repeat > 0 => Run::Repeat(2),
+                (1, Run::Up(k)) if limits.max_sequence > 0 => Run::Up(k + 1),
+                (1, _) if limits.max_sequence > 0 => Run::Up(2),
+                (-1, Run::Down(k)) if limits.max_sequence > 0 => Run::Down(k + 1),
+                (-1, _) if limits.max_sequence > 0 => Run::Down(2),
+                _ => Run::Single,
+            }
+        };
+        let exceeds = match run {
+            Run::Single => false,
+            Run::Repeat(k) => k > limits.max_repeat,
+            Run::Up(k) | Run::Down(k) => k > limits.max_sequence,
+        };
+        if exceeds || (limits.max_class_repeat > 0 && class_run > limits.max_class_repeat) {
+            return None;
+        }
+        Some(State {
+            mask: state.mask & !group.mask,
+            last: group.last,
+            run,
+            class_run,
+            bytes,
+        })
+    }
+
+    /// Number of the entries of the table.
+    pub(crate) fn size(&self) -> usize {
+        self.table.iter().map(Vec::len).sum()
+    }
+
+    /// Whether no string satisfies the alphabets, the sets and the limits.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.ratio <= 0.0
+    }
+
+    /// log2 of the sum of the products of the weights of the strings.
+    fn log_total(&self) -> f64 {
+        let all: f64 = self
+            .positions
+            .iter()
+            .map(|groups| groups.iter().map(Group::weight_sum).sum::<f64>().log2())
+            .sum();
+        all + self.ratio.log2()
+    }
+
+    /// Bits of entropy of a sampled string, i.e. log2 of the number of the strings without
+    /// weights.
+    /// With weights, it is the Shannon entropy of the strings, which is less than log2 of
+    /// their number.
+    pub(crate) fn entropy(&self) -> f64 {
+        self.log_total() - self.log_weight
+    }
+
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        let mut state: Option<State> = None;
+        let mut s = String::with_capacity(self.positions.len());
+        for (r, groups) in self.positions.iter().enumerate() {
+            let nexts: Vec<Option<State>> = groups
+                .iter()
+                .map(|g| self.next(state.as_ref(), g))
+                .collect();
+            let weights: Vec<f64> = groups
+                .iter()
+                .zip(&nexts)
+                .map(|(g, next)| match next {
+                    Some(n) => g.weight_sum() * self.table[r][self.index(n)],
+                    None => 0.0,
+                })
+                .collect();
+            let mut x = rng.gen_range(0.0, weights.iter().sum::<f64>());
+            let i = weights
+                .iter()
+                .position(|w| {
+                    x -= w;
+                    x < 0.0
+                })
+                // Rounding errors can leave `x` at the last positive weight.
+                .unwrap_or_else(|| weights.iter().rposition(|w| *w > 0.0).unwrap());
+            let group = &groups[i];
+            s.push(group.characters[rng.gen_range(0, group.characters.len())]);
+            state = nexts[i];
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+    use std::iter::FromIterator;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn set(s: &str) -> HashSet<char> {
+        HashSet::from_iter(s.chars())
+    }
+
+    fn plain(alphabets: &[Vec<char>], sets: &[HashSet<char>]) -> Sampler {
+        Sampler::new(alphabets, sets, &[], Limits::default(), &HashMap::new()).unwrap()
+    }
+
+    fn limited(alphabets: &[Vec<char>], classes: &[HashSet<char>], limits: Limits) -> Sampler {
+        Sampler::new(alphabets, &[], classes, limits, &HashMap::new()).unwrap()
+    }
+
+    fn count(alphabets: &[Vec<char>], valid: impl Fn(&[char]) -> bool) -> f64 {
+        let mut strings: Vec<Vec<char>> = vec![Vec::new()];
+        for alphabet in alphabets {
+            strings = strings
+                .iter()
+                .flat_map(|s| {
+                    alphabet.iter().map(move |c| {
+                        let mut s = s.clone();
+                        s.push(*c);
+                        s
+                    })
+                })
+                .collect();
+        }
+        strings.iter().filter(|s| valid(s)).count() as f64
+    }
+
+    #[test]
+    fn test_new() {
+        let sampler = plain(&[chars("ab1")], &[set("ab"), set("1")]);
+        assert_eq!(
+            sampler.positions,
+            vec![vec![
+                Group {
+                    mask: 1,
+                    last: 0,
+                    class: 0,
+                    width: 0,
+                    weight: 1.0,
+                    characters: chars("ab")
+                },
+                Group {
+                    mask: 2,
+                    last: 0,
+                    class: 0,
+                    width: 0,
+                    weight: 1.0,
+                    characters: chars("1")
+                },
+            ]]
+        );
+        assert!(sampler.is_empty());
+
+        let limits = Limits {
+            max_sequence: 100,
+            max_class_repeat: 100,
+            ..Limits::default()
+        };
+        assert_eq!(
+            Sampler::new(&vec![chars("ab"); 255], &[], &[], limits, &HashMap::new()).err(),
+            Some(Error::ComplexityErr(()))
+        );
+        // Larger tables without sets are not sampled either.
+        assert_eq!(
+            Sampling::new(
+                &vec![chars("ab"); 255],
+                &[],
+                &[],
+                limits,
+                &HashMap::new(),
+                None,
+            )
+            .err(),
+            Some(Error::ComplexityErr(()))
+        );
+    }
+
+    #[test]
+    fn test_state() {
+        let limits = Limits {
+            max_repeat: 3,
+            max_sequence: 4,
+            max_class_repeat: 2,
+            unique: false,
+            max_bytes: 0,
+        };
+        let sampler = limited(&[chars("abc")], &[set("ab"), set("c")], limits);
+        for i in 0..sampler.table[0].len() {
+            assert_eq!(sampler.index(&sampler.state(i)), i);
+        }
+    }
+
+    #[test]
+    fn test_entropy() {
+        let sampler = plain(&vec![chars("0123456789"); 8], &[]);
+        assert!((sampler.entropy() - 8.0 * 10f64.log2()).abs() < 1e-9);
+
+        let sampler = plain(&vec![chars("abc123"); 8], &[set("abc"), set("123")]);
+        let count = 6f64.powi(8) - 2.0 * 3f64.powi(8);
+        assert!((sampler.entropy() - count.log2()).abs() < 1e-9);
+
+        // Strings of 3 characters containing a, b and c.
+        let sampler = plain(&vec![chars("abcd"); 3], &[set("a"), set("b"), set("c")]);
+        assert!((sampler.entropy() - 6f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_limits() {
+        let alphabets = vec![chars("abcd12"); 6];
+        let classes = [set("abcd"), set("12")];
+        let runs = |s: &[char], max: usize, follows: &dyn Fn(char, char) -> bool| {
+            let mut run = 1;
+            for w in s.windows(2) {
+                run = if follows(w[0], w[1]) { run + 1 } else { 1 };
+                if run > max {
+                    return false;
+                }
+            }
+            true
+        };
+
+        let limits = Limits {
+            max_repeat: 2,
+            ..Limits::default()
+        };
+        let expected = count(&alphabets, |s| runs(s, 2, &|a, b| a == b));
+        let sampler = limited(&alphabets, &classes, limits);
+        assert!((sampler.entropy() - expected.log2()).abs() < 1e-9);
+
+        let limits = Limits {
+            max_sequence: 2,
+            ..Limits::default()
+        };
+        let expected = count(&alphabets, |s| {
+            runs(s, 2, &|a, b| b as u32 == a as u32 + 1)
+                && runs(s, 2, &|a, b| a as u32 == b as u32 + 1)
+        });
+        let sampler = limited(&alphabets, &classes, limits);
+        assert!((sampler.entropy() - expected.log2()).abs() < 1e-9);
+
+        let limits = Limits {
+            max_class_repeat: 3,
+            ..Limits::default()
+        };
+        let class = |c: char| c.is_ascii_digit();
+        let expected = count(&alphabets, |s| runs(s, 3, &|a, b| class(a) == class(b)));
+        let sampler = limited(&alphabets, &classes, limits);
+        assert!((sampler.entropy() - expected.log2()).abs() < 1e-9);
+
+        let limits = Limits {
+            max_repeat: 1,
+            max_sequence: 3,
+            max_class_repeat: 4,
+            unique: false,
+            max_bytes: 0,
+        };
+        let expected = count(&alphabets, |s| {
+            runs(s, 1, &|a, b| a == b)
+                && runs(s, 3, &|a, b| b as u32 == a as u32 + 1)
+                && runs(s, 3, &|a, b| a as u32 == b as u32 + 1)
+                && runs(s, 4, &|a, b| class(a) == class(b))
+        });
+        let sampler = limited(&alphabets, &classes, limits);
+        assert!((sampler.entropy() - expected.log2()).abs() < 1e-9);
+
+        let limits = Limits {
+            max_class_repeat: 1,
+            ..Limits::default()
+        };
+        assert!(limited(&vec![chars("ab"); 2], &[set("ab")], limits).is_empty());
+    }
+
+    #[test]
+    fn test_bytes() {
+        let alphabets = vec![chars("abαβあ1"); 5];
+        let classes = [set("ab"), set("αβ"), set("あ"), set("1")];
+        for max_bytes in 5..=12 {
+            let limits = Limits {
+                max_bytes,
+                ..Limits::default()
+            };
+            let expected = count(&alphabets, |s| {
+                s.iter().map(|c| c.len_utf8()).sum::<usize>() <= max_bytes
+            });
+            let sampler = limited(&alphabets, &classes, limits);
+            assert!((sampler.entropy() - expected.log2()).abs() < 1e-9);
+
+            let limits = Limits {
+                max_repeat: 1,
+                max_class_repeat: 2,
+                max_bytes,
+                ..Limits::default()
+            };
+            let class = |c: char| classes.iter().position(|s| s.contains(&c));
+            let expected = count(&alphabets, |s| {
+                s.iter().map(|c| c.len_utf8()).sum::<usize>() <= max_bytes
+                    && s.windows(2).all(|w| w[0] != w[1])
+                    && s.windows(3)
+                        .all(|w| !(class(w[0]) == class(w[1]) && class(w[1]) == class(w[2])))
+            });
+            let sampler = limited(&alphabets, &classes, limits);
+            assert!((sampler.entropy() - expected.log2()).abs() < 1e-9);
+        }
+
+        // Every string fits 15 bytes, so that the bytes are not tracked.
+        let limits = Limits {
+            max_bytes: 15,
+            ..Limits::default()
+        };
+        assert_eq!(limited(&alphabets, &classes, limits).budget, 1);
+
+        let limits = Limits {
+            max_bytes: 4,
+            ..Limits::default()
+        };
+        assert!(limited(&alphabets, &classes, limits).is_empty());
+    }
+
+    #[test]
+    fn test_sample_bytes() {
+        let mut rng = rand::thread_rng();
+        let limits = Limits {
+            max_bytes: 4,
+            ..Limits::default()
+        };
+        // Strings of 3 characters of a and α in 4 bytes or less.
+        let sampler = limited(&vec![chars("aα"); 3], &[], limits);
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for _ in 0..4000 {
+            *counts.entry(sampler.sample(&mut rng)).or_default() += 1;
+        }
+        let mut found: Vec<&String> = counts.keys().collect();
+        found.sort();
+        assert_eq!(found, vec!["aaa", "aaα", "aαa", "αaa"]);
+        assert!((sampler.entropy() - 4f64.log2()).abs() < 1e-9);
+        assert!(counts.values().all(|c| 850 < *c && *c < 1150));
+    }
+
+    #[test]
+    fn test_weights() {
+        let alphabets = vec![chars("abAB1"); 4];
+        let sets = [set("ab"), set("AB"), set("1")];
+        let classes = [set("ab"), set("AB"), set("1")];
+        let weights: HashMap<char, f64> = "AB".chars().map(|c| (c, 0.25)).collect();
+        let weight =
+            |s: &[char]| -> f64 { s.iter().map(|c| weights.get(c).unwrap_or(&1.0)).product() };
+        let limits_all = [
+            Limits::default(),
+            Limits {
+                max_repeat: 1,
+                max_class_repeat: 2,
+                ..Limits::default()
+            },
+        ];
+        for limits in limits_all.iter() {
+            let class = |c: char| classes.iter().position(|s| s.contains(&c));
+            let valid = |s: &[char]| {
+                sets.iter().all(|set| s.iter().any(|c| set.contains(c)))
+                    && (limits.max_repeat == 0 || s.windows(2).all(|w| w[0] != w[1]))
+                    && (limits.max_class_repeat == 0
+                        || s.windows(3)
+                            .all(|w| !(class(w[0]) == class(w[1]) && class(w[1]) == class(w[2]))))
+            };
+            // Shannon entropy of the valid strings in proportion to their weights.
+            let mut strings: Vec<Vec<char>> = vec![Vec::new()];
+            for alphabet in &alphabets {
+                strings = strings
+                    .iter()
+                    .flat_map(|s| {
+                        alphabet.iter().map(move |c| {
+                            let mut s = s.clone();
+                            s.push(*c);
+                            s
+                        })
+                    })
+                    .collect();
+            }
+            let strings: Vec<&Vec<char>> = strings.iter().filter(|s| valid(s)).collect();
+            let total: f64 = strings.iter().map(|s| weight(s)).sum();
+            let expected: f64 = strings
+                .iter()
+                .map(|s| {
+                    let p = weight(s) / total;
+                    -p * p.log2()
+                })
+                .sum();
+            let sampler = Sampler::new(&alphabets, &sets, &classes, *limits, &weights).unwrap();
+            assert!((sampler.entropy() - expected).abs() < 1e-9);
+            assert!(sampler.entropy() < (strings.len() as f64).log2());
+        }
+
+        let sampling = Sampling::new(
+            &alphabets,
+            &sets,
+            &classes,
+            Limits {
+                unique: true,
+                ..Limits::default()
+            },
+            &weights,
+            None,
+        );
+        assert_eq!(sampling, Err(Error::UniqueConflictErr(())));
+        let unique = |max_sequence| Limits {
+            max_sequence,
+            unique: true,
+            ..Limits::default()
+        };
+        assert_eq!(
+            Sampling::new(
+                &alphabets,
+                &sets,
+                &classes,
+                unique(3),
+                &HashMap::new(),
+                None
+            ),
+            Err(Error::UniqueConflictErr(()))
+        );
+        // No sequence of 4 characters exceeds 4.
+        let sampling = Sampling::new(
+            &alphabets,
+            &sets,
+            &classes,
+            unique(4),
+            &HashMap::new(),
+            None,
+        );
+        assert!(matches!(sampling, Ok(Sampling::Unique(_))));
+        let pages = [set("1")];
+        assert_eq!(
+            Sampling::new(
+                &alphabets,
+                &sets,
+                &classes,
+                Limits::default(),
+                &weights,
+                Some(&pages)
+            ),
+            Err(Error::PagesConflictErr(()))
+        );
+        assert_eq!(
+            Sampling::new(
+                &alphabets,
+                &sets,
+                &classes,
+                Limits {
+                    max_repeat: 2,
+                    ..Limits::default()
+                },
+                &HashMap::new(),
+                Some(&pages)
+            ),
+            Err(Error::PagesConflictErr(()))
+        );
+    }
+
+    #[test]
+    fn test_sample_weights() {
+        let mut rng = rand::thread_rng();
+        // Strings of 2 characters of a and B, where B weighs a third of a.
+        let weights: HashMap<char, f64> = std::iter::once(('B', 1.0 / 3.0)).collect();
+        let sampler =
+            Sampler::new(&vec![chars("aB"); 2], &[], &[], Limits::default(), &weights).unwrap();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for _ in 0..16000 {
+            *counts.entry(sampler.sample(&mut rng)).or_default() += 1;
+        }
+        // Probabilities are 9/16, 3/16, 3/16 and 1/16.
+        assert!((8500..9500).contains(&counts["aa"]));
+        assert!((2700..3300).contains(&counts["aB"]));
+        assert!((2700..3300).contains(&counts["Ba"]));
+        assert!((800..1200).contains(&counts["BB"]));
+        let p = [9.0, 3.0, 3.0, 1.0].iter().map(|n: &f64| n / 16.0);
+        let expected: f64 = p.map(|p| -p * p.log2()).sum();
+        assert!((sampler.entropy() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut rng = rand::thread_rng();
+        let sampler = plain(
+            &[chars("ab"), chars("abc"), chars("abc")],
+            &[set("a"), set("c")],
+        );
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for _ in 0..7000 {
+            *counts.entry(sampler.sample(&mut rng)).or_default() += 1;
+        }
+        let mut found: Vec<&String> = counts.keys().collect();
+        found.sort();
+        assert_eq!(found, vec!["aac", "abc", "aca", "acb", "acc", "bac", "bca"]);
+        assert!((sampler.entropy() - 7f64.log2()).abs() < 1e-9);
+        assert!(counts.values().all(|c| 850 < *c && *c < 1150));
+    }
+
+    #[test]
+    fn test_sample_limits() {
+        let mut rng = rand::thread_rng();
+        let limits = Limits {
+            max_repeat: 1,
+            max_sequence: 1,
+            ..Limits::default()
+        };
+        // Strings of 3 characters of a, b and c without repeats nor sequences.
+        let sampler = limited(&vec![chars("abc"); 3], &[], limits);
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for _ in 0..4000 {
+            *counts.entry(sampler.sample(&mut rng)).or_default() += 1;
+        }
+        let mut found: Vec<&String> = counts.keys().collect();
+        found.sort();
+        assert_eq!(found, vec!["aca", "cac"]);
+        assert!((sampler.entropy() - 2f64.log2()).abs() < 1e-9);
+        assert!(counts.values().all(|c| 1800 < *c && *c < 2200));
+    }
+}
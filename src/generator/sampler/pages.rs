@@ -0,0 +1,319 @@
+use super::{MAX_SETS, MAX_TABLE};
+use crate::generator::error::Error;
+use rand::Rng;
+use std::collections::{BTreeMap, HashSet};
+
+/// Maximum number of keyboard pages, including the first page.
+const MAX_PAGES: usize = 8;
+
+/// Characters of a position on the same page which intersect the same sets.
+#[derive(Debug, PartialEq)]
+struct Group {
+    page: usize,
+    mask: usize,
+    characters: Vec<char>,
+}
+
+/// What the rest of a string depends on after a character.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct State {
+    /// Sets which are not intersected yet.
+    mask: usize,
+    /// Pages which have been typed on.
+    visited: usize,
+    /// Page of the last character.
+    page: usize,
+}
+
+/// Samples strings uniformly from the strings which take every character from the alphabet
+/// of its position, which intersect every set, and which type the characters of each page
+/// in one run, so that a keyboard switches to each page only once.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Pages {
+    positions: Vec<Vec<Group>>,
+    pages: usize,
+    /// `table[r][state]` is the ratio of the ways to fill the positions after `r` from `state`
+    /// to all the ways to fill them.
+    table: Vec<Vec<f64>>,
+    /// The same ratio for all the positions.
+    ratio: f64,
+    full: usize,
+}
+
+impl Pages {
+    /// `pages` are disjoint sets of characters, and the characters in none of them are on the
+    /// first page. `sets` must be `MAX_SETS` or less.
+    pub(crate) fn new(
+        alphabets: &[Vec<char>],
+        sets: &[HashSet<char>],
+        pages: &[HashSet<char>],
+    ) -> Result<Self, Error> {
+        assert!(sets.len() <= MAX_SETS);
+        if pages.len() >= MAX_PAGES {
+            return Err(Error::ComplexityErr(()));
+        }
+        let full = (1 << sets.len()) - 1;
+        let positions: Vec<Vec<Group>> = alphabets
+            .iter()
+            .map(|alphabet| {
+                let mut groups: BTreeMap<(usize, usize), Vec<char>> = BTreeMap::new();
+                for c in alphabet {
+                    let mask = sets
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, s)| s.contains(c))
+                        .fold(0, |m, (i, _)| m | 1 << i);
+                    let page = pages
+                        .iter()
+                        .position(|p| p.contains(c))
+                        .map_or(0, |p| p + 1);
+                    groups.entry((page, mask)).or_default().push(*c);
+                }
+                groups
+                    .into_iter()
+                    .map(|((page, mask), mut characters)| {
+                        characters.sort_unstable();
+                        characters.dedup();
+                        Group {
+                            page,
+                            mask,
+                            characters,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut sampler = Pages {
+            positions,
+            pages: pages.len() + 1,
+            table: Vec::new(),
+            ratio: 0.0,
+            full,
+        };
+        let size = (full + 1) * (1 << sampler.pages) * sampler.pages;
+        if size.saturating_mul(alphabets.len()) > MAX_TABLE {
+            return Err(Error::ComplexityErr(()));
+        }
+
+        let length = sampler.positions.len();
+        let mut table = vec![vec![0f64; size]; length];
+        if let Some(last) = table.last_mut() {
+            for (i, t) in last.iter_mut().enumerate() {
+                if sampler.state(i).mask == 0 {
+                    *t = 1.0;
+                }
+            }
+        }
+        for r in (0..length.saturating_sub(1)).rev() {
+            let groups = &sampler.positions[r + 1];
+            let n: usize = groups.iter().map(|g| g.characters.len()).sum();
+            if n == 0 {
+                continue;
+            }
+            for i in 0..size {
+                let state = sampler.state(i);
+                table[r][i] = groups
+                    .iter()
+                    .filter_map(|g| {
+                        let next = sampler.next(Some(&state), g)?;
+                        Some(g.characters.len() as f64 * table[r + 1][sampler.index(&next)])
+                    })
+                    .sum::<f64>()
+                    / n as f64;
+            }
+        }
+        if let Some(groups) = sampler.positions.first() {
+            let n: usize = groups.iter().map(|g| g.characters.len()).sum();
+            if n > 0 {
+                sampler.ratio = groups
+                    .iter()
+                    .filter_map(|g| {
+                        let next = sampler.next(None, g)?;
+                        Some(g.characters.len() as f64 * table[0][sampler.index(&next)])
+                    })
+                    .sum::<f64>()
+                    / n as f64;
+            }
+        }
+        sampler.table = table;
+        Ok(sampler)
+    }
+
+    fn index(&self, state: &State) -> usize {
+        ((state.mask << self.pages) + state.visited) * self.pages + state.page
+    }
+
+    fn state(&self, index: usize) -> State {
+        let page = index % self.pages;
+        let index = index / self.pages;
+        State {
+            mask: index >> self.pages,
+            visited: index & ((1 << self.pages) - 1),
+            page,
+        }
+    }
+
+    /// The state after a character of `group` follows `state`, or `None` if the character
+    /// returns to a page left before.
+    fn next(&self, state: Option<&State>, group: &Group) -> Option<State> {
+        let (mask, visited) = match state {
+            Some(s) if s.page != group.page && s.visited & 1 << group.page != 0 => return None,
+            Some(s) => (s.mask, s.visited),
+            None => (self.full, 0),
+        };
+        Some(State {
+            mask: mask & !group.mask,
+            visited: visited | 1 << group.page,
+            page: group.page,
+        })
+    }
+
+    /// Whether no string satisfies the alphabets, the sets and the pages.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.ratio <= 0.0
+    }
+
+    /// Bits of entropy of a sampled string, i.e. log2 of the number of the strings.
+    pub(crate) fn entropy(&self) -> f64 {
+        let all: f64 = self
+            .positions
+            .iter()
+            .map(|groups| {
+                let n: usize = groups.iter().map(|g| g.characters.len()).sum();
+                (n as f64).log2()
+            })
+            .sum();
+        all + self.ratio.log2()
+    }
+
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        let mut state: Option<State> = None;
+        let mut s = String::with_capacity(self.positions.len());
+        for (r, groups) in self.positions.iter().enumerate() {
+            let nexts: Vec<Option<State>> = groups
+                .iter()
+                .map(|g| self.next(state.as_ref(), g))
+                .collect();
+            let weights: Vec<f64> = groups
+                .iter()
+                .zip(&nexts)
+                .map(|(g, next)| match next {
+                    Some(n) => g.characters.len() as f64 * self.table[r][self.index(n)],
+                    None => 0.0,
+                })
+                .collect();
+            let mut x = rng.gen_range(0.0, weights.iter().sum::<f64>());
+            let i = weights
+                .iter()
+                .position(|w| {
+                    x -= w;
+                    x < 0.0
+                })
+                // Rounding errors can leave `x` at the last positive weight.
+                .unwrap_or_else(|| weights.iter().rposition(|w| *w > 0.0).unwrap());
+            let group = &groups[i];
+            s.push(group.characters[rng.gen_range(0, group.characters.len())]);
+            state = nexts[i];
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+    use std::iter::FromIterator;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn set(s: &str) -> HashSet<char> {
+        HashSet::from_iter(s.chars())
+    }
+
+    /// Number of the strings of `alphabets` intersecting `sets` with one run of each page.
+    fn count(alphabets: &[Vec<char>], sets: &[HashSet<char>], pages: &[HashSet<char>]) -> f64 {
+        let page = |c: &char| {
+            pages
+                .iter()
+                .position(|p| p.contains(c))
+                .map_or(0, |p| p + 1)
+        };
+        let mut strings: Vec<Vec<char>> = vec![Vec::new()];
+        for alphabet in alphabets {
+            strings = strings
+                .iter()
+                .flat_map(|s| {
+                    alphabet.iter().map(move |c| {
+                        let mut s = s.clone();
+                        s.push(*c);
+                        s
+                    })
+                })
+                .collect();
+        }
+        strings
+            .iter()
+            .filter(|s| {
+                let mut runs: Vec<usize> = s.iter().map(page).collect();
+                runs.dedup();
+                let distinct: HashSet<&usize> = runs.iter().collect();
+                distinct.len() == runs.len()
+                    && sets.iter().all(|set| s.iter().any(|c| set.contains(c)))
+            })
+            .count() as f64
+    }
+
+    #[test]
+    fn test_entropy() {
+        let pages = [set("12-"), set("#+")];
+        let sets = [set("ab"), set("AB"), set("12"), set("-#+")];
+        for length in 4..=6 {
+            let alphabets = vec![chars("abAB12-#+"); length];
+            let sampler = Pages::new(&alphabets, &sets, &pages).unwrap();
+            let expected = count(&alphabets, &sets, &pages);
+            assert!((sampler.entropy() - expected.log2()).abs() < 1e-9);
+        }
+
+        // The first character is a letter.
+        let mut alphabets = vec![chars("abAB12-#+"); 5];
+        alphabets[0] = chars("abAB");
+        let sampler = Pages::new(&alphabets, &sets, &pages).unwrap();
+        let expected = count(&alphabets, &sets, &pages);
+        assert!((sampler.entropy() - expected.log2()).abs() < 1e-9);
+
+        // 4 pages need 4 characters or more.
+        let pages = [set("a"), set("1"), set("#"), set("あ")];
+        let sets = pages.clone();
+        let sampler = Pages::new(&vec![chars("a1#あ"); 4], &sets, &pages).unwrap();
+        assert!((sampler.entropy() - 24f64.log2()).abs() < 1e-9);
+        assert!(Pages::new(&vec![chars("a1#あ"); 3], &sets, &pages)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut rng = rand::thread_rng();
+        let pages = [set("1")];
+        // Strings of 3 characters of a or b and 1, in one run of each page.
+        let sampler = Pages::new(&vec![chars("ab1"); 3], &[set("ab"), set("1")], &pages).unwrap();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for _ in 0..12000 {
+            *counts.entry(sampler.sample(&mut rng)).or_default() += 1;
+        }
+        let mut found: Vec<&String> = counts.keys().collect();
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                "11a", "11b", "1aa", "1ab", "1ba", "1bb", "a11", "aa1", "ab1", "b11", "ba1", "bb1"
+            ]
+        );
+        assert!((sampler.entropy() - 12f64.log2()).abs() < 1e-9);
+        assert!(counts.values().all(|c| 850 < *c && *c < 1150));
+    }
+}
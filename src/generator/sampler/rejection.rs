@@ -0,0 +1,333 @@
+use super::{Limits, Sampler, Unique};
+use crate::generator::error::Error;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+/// Least ratio of the proposed strings which are accepted, so that a string takes less than
+/// 16 proposals on average.
+const MIN_ACCEPTANCE: f64 = 1.0 / 16.0;
+/// Ratio under which no string is accepted, left by the rounding errors of the counts.
+const NO_ACCEPTANCE: f64 = 1e-9;
+/// Maximum number of entries of the tables of the samplers counting the accepted strings.
+const MAX_COUNTED: usize = 1 << 25;
+
+/// Sampler of the strings without the rejected sets.
+#[derive(Debug, PartialEq)]
+enum Proposal {
+    Table(Sampler),
+    Unique(Unique),
+}
+
+impl Proposal {
+    /// `sets` are the kept sets, and the limits are for a table unless `unique`.
+    fn new(
+        alphabets: &[Vec<char>],
+        sets: &[HashSet<char>],
+        classes: &[HashSet<char>],
+        limits: Limits,
+        weights: &HashMap<char, f64>,
+    ) -> Result<Self, Error> {
+        if limits.unique {
+            Unique::new(alphabets, sets).map(Proposal::Unique)
+        } else {
+            Sampler::new(alphabets, sets, classes, limits, weights).map(Proposal::Table)
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Proposal::Table(s) => s.size(),
+            Proposal::Unique(s) => s.size(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Proposal::Table(s) => s.is_empty(),
+            Proposal::Unique(s) => s.is_empty(),
+        }
+    }
+
+    /// log2 of the sum of the weights of the strings, and the expected log2 of the weight of
+    /// a sampled string.
+    fn logs(&self) -> (f64, f64) {
+        match self {
+            Proposal::Table(s) => (s.log_total(), s.log_weight),
+            Proposal::Unique(s) => (s.entropy(), 0.0),
+        }
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        match self {
+            Proposal::Table(s) => s.sample(rng),
+            Proposal::Unique(s) => s.sample(rng),
+        }
+    }
+}
+
+/// Samples strings when the sets make the table of the other samplers too large.
+///
+/// The smallest sets are kept in the table of the proposed strings, and the proposed strings
+/// which miss any of the other sets are rejected, so that the accepted strings are sampled
+/// exactly as by the table with every set.
+/// The accepted strings are counted by inclusion and exclusion of the rejected sets, so that
+/// constraints which reject more than `1 - MIN_ACCEPTANCE` of the proposals are errors.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Rejection {
+    proposal: Proposal,
+    /// Sets which every accepted string intersects.
+    rejected: Vec<HashSet<char>>,
+    /// Ratio of the accepted strings to the proposed strings, in their weights.
+    acceptance: f64,
+    entropy: f64,
+}
+
+impl Rejection {
+    /// `classes` are the classes of characters for `max_class_repeat`, and `weights` are the
+    /// weights of the characters other than 1.
+    /// With `unique`, the other limits and the weights must be left out.
+    pub(crate) fn new(
+        alphabets: &[Vec<char>],
+        sets: &[HashSet<char>],
+        classes: &[HashSet<char>],
+        limits: Limits,
+        weights: &HashMap<char, f64>,
+    ) -> Result<Self, Error> {
+        // Strings miss small sets more often than large ones.
+        let mut sets = sets.to_vec();
+        sets.sort_by_key(HashSet::len);
+        let (proposal, kept) = (0..sets.len())
+            .rev()
+            .find_map(|kept| {
+                let proposal = Proposal::new(alphabets, &sets[..kept], classes, limits, weights);
+                proposal.ok().map(|p| (p, kept))
+            })
+            .ok_or(Error::ComplexityErr(()))?;
+        let (kept, rejected) = sets.split_at(kept);
+        if proposal.size().saturating_mul(1 << rejected.len()) > MAX_COUNTED {
+            return Err(Error::ComplexityErr(()));
+        }
+
+        // The strings which miss every set of a subset of the rejected sets are counted
+        // without the characters of those sets, relative to all the proposed strings.
+        let (log_total, log_weight) = proposal.logs();
+        let mut acceptance = 0.0;
+        let mut accepted_log_weight = 0.0;
+        let subsets = if proposal.is_empty() {
+            0
+        } else {
+            1usize << rejected.len()
+        };
+        for subset in 0..subsets {
+            let missed: Vec<&HashSet<char>> = (0..rejected.len())
+                .filter(|i| subset & 1 << i != 0)
+                .map(|i| &rejected[i])
+                .collect();
+            let (ratio, log_weight) = if subset == 0 {
+                (1.0, log_weight)
+            } else {
+                let alphabets: Vec<Vec<char>> = alphabets
+                    .iter()
+                    .map(|a| {
+                        let mut a = a.clone();
+                        a.retain(|c| !missed.iter().any(|s| s.contains(c)));
+                        a
+                    })
+                    .collect();
+                if alphabets.iter().any(Vec::is_empty) {
+                    continue;
+                }
+                let (total, log_weight) =
+                    Proposal::new(&alphabets, kept, classes, limits, weights)?.logs();
+                ((total - log_total).exp2(), log_weight)
+            };
+            if ratio > 0.0 {
+                let sign = if missed.len() % 2 == 0 { 1.0 } else { -1.0 };
+                acceptance += sign * ratio;
+                accepted_log_weight += sign * ratio * log_weight;
+            }
+        }
+
+        if acceptance < NO_ACCEPTANCE {
+            acceptance = 0.0;
+        } else if acceptance < MIN_ACCEPTANCE {
+            return Err(Error::ComplexityErr(()));
+        }
+        let entropy = if acceptance > 0.0 {
+            log_total + acceptance.log2() - accepted_log_weight / acceptance
+        } else {
+            0.0
+        };
+        Ok(Rejection {
+            proposal,
+            rejected: rejected.to_vec(),
+            acceptance,
+            entropy,
+        })
+    }
+
+    /// Whether no string satisfies the alphabets, the sets and the limits.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.acceptance <= 0.0
+    }
+
+    /// Bits of entropy of a sampled string, as of the table with every set.
+    pub(crate) fn entropy(&self) -> f64 {
+        self.entropy
+    }
+
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        loop {
+            let s = self.proposal.sample(rng);
+            if self
+                .rejected
+                .iter()
+                .all(|set| s.chars().any(|c| set.contains(&c)))
+            {
+                return s;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generator::sampler::Sampling;
+    use std::iter::FromIterator;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn set(s: &str) -> HashSet<char> {
+        HashSet::from_iter(s.chars())
+    }
+
+    #[test]
+    fn test_entropy() {
+        let alphabets = vec![chars("abcAB12-"); 6];
+        let sets = [set("abc"), set("AB"), set("12"), set("-")];
+        let classes = [set("abc"), set("AB"), set("12-")];
+        let limits = Limits {
+            max_repeat: 1,
+            max_sequence: 2,
+            max_class_repeat: 2,
+            ..Limits::default()
+        };
+        let weights: HashMap<char, f64> = "AB-".chars().map(|c| (c, 0.25)).collect();
+        for weights in [HashMap::new(), weights].iter() {
+            let rejection = Rejection::new(&alphabets, &sets, &classes, limits, weights).unwrap();
+            let sampler = Sampler::new(&alphabets, &sets, &classes, limits, weights).unwrap();
+            assert_eq!(rejection.rejected.len(), 1);
+            assert!((rejection.entropy() - sampler.entropy()).abs() < 1e-9);
+        }
+
+        let unique = Limits {
+            unique: true,
+            ..Limits::default()
+        };
+        let rejection = Rejection::new(&alphabets, &sets, &[], unique, &HashMap::new()).unwrap();
+        let expected = Unique::new(&alphabets, &sets).unwrap().entropy();
+        assert!((rejection.entropy() - expected).abs() < 1e-9);
+
+        // Strings of 2 characters cannot intersect 4 sets.
+        let rejection = Rejection::new(&vec![chars("ab1"); 2], &sets, &[], limits, &HashMap::new());
+        assert!(rejection.unwrap().is_empty());
+        // 1 in 25 strings has W.
+        let alphabets = [chars("a"), chars("bcdefghijklmnopqrstuvwxyW")];
+        assert_eq!(
+            Rejection::new(
+                &alphabets,
+                &[set("a"), set("WX")],
+                &[],
+                limits,
+                &HashMap::new()
+            ),
+            Err(Error::ComplexityErr(()))
+        );
+    }
+
+    #[test]
+    fn test_new() {
+        // 12 sets and 200 weights of characters make the table too large.
+        // Both characters of a string are in a set unless they are equal modulo 12.
+        let characters: Vec<char> = (0..200u32)
+            .map(|i| std::char::from_u32(0x100 + i).unwrap())
+            .collect();
+        let sets: Vec<HashSet<char>> = (0..12)
+            .map(|i| {
+                characters
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| j % 12 != i)
+                    .map(|(_, c)| *c)
+                    .collect()
+            })
+            .collect();
+        let weights: HashMap<char, f64> = characters
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (*c, 1.0 + i as f64 / 256.0))
+            .collect();
+        let limits = Limits {
+            max_class_repeat: 11,
+            ..Limits::default()
+        };
+        let alphabets = vec![characters; 2];
+        assert_eq!(
+            Sampler::new(&alphabets, &sets, &[], limits, &weights).err(),
+            Some(Error::ComplexityErr(()))
+        );
+        let sampling = Sampling::new(&alphabets, &sets, &[], limits, &weights, None);
+        let rejection = match sampling {
+            Ok(Sampling::Rejection(r)) => r,
+            sampling => panic!("{:?}", sampling.err()),
+        };
+        assert_eq!(rejection.rejected.len(), 1);
+        let pairs: Vec<f64> = (0..200)
+            .flat_map(|i| {
+                (0..200)
+                    .filter(move |j| i % 12 != j % 12)
+                    .map(move |j| (i, j))
+            })
+            .map(|(i, j)| weights[&alphabets[0][i]] * weights[&alphabets[1][j]])
+            .collect();
+        let total: f64 = pairs.iter().sum();
+        let expected: f64 = pairs.iter().map(|w| -w / total * (w / total).log2()).sum();
+        assert!((rejection.entropy() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut rng = rand::thread_rng();
+        let limits = Limits {
+            max_repeat: 1,
+            ..Limits::default()
+        };
+        // Strings of 3 characters of abc1 without repeats, with an a and a digit.
+        let rejection = Rejection::new(
+            &vec![chars("abc1"); 3],
+            &[set("a"), set("1")],
+            &[],
+            limits,
+            &HashMap::new(),
+        )
+        .unwrap();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for _ in 0..14000 {
+            *counts.entry(rejection.sample(&mut rng)).or_default() += 1;
+        }
+        let mut found: Vec<&String> = counts.keys().collect();
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                "1a1", "1ab", "1ac", "1ba", "1ca", "a1a", "a1b", "a1c", "ab1", "ac1", "b1a", "ba1",
+                "c1a", "ca1"
+            ]
+        );
+        assert!((rejection.entropy() - 14f64.log2()).abs() < 1e-9);
+        assert!(counts.values().all(|c| 850 < *c && *c < 1150));
+    }
+}
@@ -0,0 +1,267 @@
+use super::{MAX_SETS, MAX_TABLE};
+use crate::generator::error::Error;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{BTreeMap, HashSet};
+
+/// Characters which intersect the same sets and are allowed at the same edges.
+#[derive(Debug, PartialEq)]
+struct Group {
+    mask: usize,
+    first: bool,
+    middle: bool,
+    last: bool,
+    characters: Vec<char>,
+}
+
+/// Samples strings of distinct characters uniformly from the strings which take every
+/// character from the alphabet of its position, and which intersect every set.
+///
+/// The positions between the first and the last must share the same alphabet, so that the
+/// characters between them can be chosen as a set and shuffled.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Unique {
+    groups: Vec<Group>,
+    /// Number of the characters between the first and the last.
+    middle: usize,
+    /// `table[index(i, k, a, b, mask)]` is the number of ways for the groups from `i` to give
+    /// `k` middle characters, `a` first and `b` last characters intersecting every set in `mask`.
+    table: Vec<f64>,
+    full: usize,
+}
+
+impl Unique {
+    /// `alphabets` must be 2 or more and `sets` must be `MAX_SETS` or less.
+    pub(crate) fn new(alphabets: &[Vec<char>], sets: &[HashSet<char>]) -> Result<Self, Error> {
+        assert!(alphabets.len() >= 2 && sets.len() <= MAX_SETS);
+        let full = (1 << sets.len()) - 1;
+        let first = &alphabets[0];
+        let last = &alphabets[alphabets.len() - 1];
+        let middle = alphabets.len() - 2;
+        let between = &alphabets[1..alphabets.len() - 1];
+        let all: HashSet<char> = alphabets.iter().flatten().copied().collect();
+
+        let mut groups: BTreeMap<(usize, bool, bool, bool), Vec<char>> = BTreeMap::new();
+        for c in all {
+            let mask = sets
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| s.contains(&c))
+                .fold(0, |m, (i, _)| m | 1 << i);
+            let key = (
+                mask,
+                first.contains(&c),
+                between.iter().all(|a| a.contains(&c)),
+                last.contains(&c),
+            );
+            groups.entry(key).or_default().push(c);
+        }
+        let groups: Vec<Group> = groups
+            .into_iter()
+            .map(|((mask, first, middle, last), mut characters)| {
+                characters.sort_unstable();
+                Group {
+                    mask,
+                    first,
+                    middle,
+                    last,
+                    characters,
+                }
+            })
+            .collect();
+
+        let size = (groups.len() + 1) * (middle + 1) * 4 * (full + 1);
+        if size > MAX_TABLE {
+            return Err(Error::ComplexityErr(()));
+        }
+        let mut unique = Unique {
+            groups,
+            middle,
+            table: vec![0f64; size],
+            full,
+        };
+        let end = unique.index(unique.groups.len(), 0, 0, 0, 0);
+        unique.table[end] = 1.0;
+        for i in (0..unique.groups.len()).rev() {
+            for k in 0..=middle {
+                for a in 0..2 {
+                    for b in 0..2 {
+                        for mask in 0..=full {
+                            let ways: f64 = unique
+                                .choices(i, k, a, b)
+                                .iter()
+                                .map(|(ai, bi, ki, w)| {
+                                    w * unique.rest(i, k - ki, a - ai, b - bi, mask, ai + bi + ki)
+                                })
+                                .sum();
+                            let index = unique.index(i, k, a, b, mask);
+                            unique.table[index] = ways;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(unique)
+    }
+
+    fn index(&self, i: usize, k: usize, a: usize, b: usize, mask: usize) -> usize {
+        (((i * (self.middle + 1) + k) * 2 + a) * 2 + b) * (self.full + 1) + mask
+    }
+
+    /// Ways of the groups after `i` when group `i` gives `n` characters.
+    fn rest(&self, i: usize, k: usize, a: usize, b: usize, mask: usize, n: usize) -> f64 {
+        let mask = if n > 0 {
+            mask & !self.groups[i].mask
+        } else {
+            mask
+        };
+        self.table[self.index(i + 1, k, a, b, mask)]
+    }
+
+    /// Numbers of the first, last and middle characters which group `i` can give, with the
+    /// number of ways to choose them.
+    fn choices(&self, i: usize, k: usize, a: usize, b: usize) -> Vec<(usize, usize, usize, f64)> {
+        let group = &self.groups[i];
+        let n = group.characters.len();
+        let mut choices = Vec::new();
+        for ai in 0..=(a.min(group.first as usize)) {
+            for bi in 0..=(b.min(group.last as usize)) {
+                if ai + bi > n {
+                    continue;
+                }
+                // Ordered choices of the first and the last characters.
+                let edges = (0..ai + bi).fold(1.0, |e, j| e * (n - j) as f64);
+                let most = if group.middle { k.min(n - ai - bi) } else { 0 };
+                for ki in 0..=most {
+                    choices.push((ai, bi, ki, edges * binomial(n - ai - bi, ki)));
+                }
+            }
+        }
+        choices
+    }
+
+    /// Number of the entries of the table.
+    pub(crate) fn size(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Whether no string satisfies the alphabets and the sets.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.table[self.index(0, self.middle, 1, 1, self.full)] <= 0.0
+    }
+
+    /// Bits of entropy of a sampled string, i.e. log2 of the number of the strings.
+    pub(crate) fn entropy(&self) -> f64 {
+        let orders: f64 = (1..=self.middle).map(|n| (n as f64).log2()).sum();
+        self.table[self.index(0, self.middle, 1, 1, self.full)].log2() + orders
+    }
+
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        let (mut k, mut a, mut b, mut mask) = (self.middle, 1, 1, self.full);
+        let mut first = None;
+        let mut last = None;
+        let mut middle: Vec<char> = Vec::with_capacity(self.middle);
+        for (i, group) in self.groups.iter().enumerate() {
+            let choices = self.choices(i, k, a, b);
+            let weights: Vec<f64> = choices
+                .iter()
+                .map(|(ai, bi, ki, w)| w * self.rest(i, k - ki, a - ai, b - bi, mask, ai + bi + ki))
+                .collect();
+            let mut x = rng.gen_range(0.0, weights.iter().sum::<f64>());
+            let j = weights
+                .iter()
+                .position(|w| {
+                    x -= w;
+                    x < 0.0
+                })
+                // Rounding errors can leave `x` at the last positive weight.
+                .unwrap_or_else(|| weights.iter().rposition(|w| *w > 0.0).unwrap());
+            let (ai, bi, ki, _) = choices[j];
+
+            let mut characters = group.characters.clone();
+            characters.shuffle(rng);
+            let mut characters = characters.into_iter();
+            if ai == 1 {
+                first = characters.next();
+            }
+            if bi == 1 {
+                last = characters.next();
+            }
+            middle.extend(characters.take(ki));
+            if ai + bi + ki > 0 {
+                mask &= !group.mask;
+            }
+            k -= ki;
+            a -= ai;
+            b -= bi;
+        }
+        middle.shuffle(rng);
+        first.into_iter().chain(middle).chain(last).collect()
+    }
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |b, i| b * (n - i) as f64 / (i + 1) as f64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+    use std::iter::FromIterator;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn set(s: &str) -> HashSet<char> {
+        HashSet::from_iter(s.chars())
+    }
+
+    #[test]
+    fn test_binomial() {
+        assert_eq!(binomial(5, 0), 1.0);
+        assert_eq!(binomial(5, 2), 10.0);
+        assert_eq!(binomial(10, 5), 252.0);
+        assert_eq!(binomial(3, 4), 0.0);
+    }
+
+    #[test]
+    fn test_entropy() {
+        // Permutations of 8 of 10 digits.
+        let unique = Unique::new(&vec![chars("0123456789"); 8], &[]).unwrap();
+        assert!((unique.entropy() - 1814400f64.log2()).abs() < 1e-9);
+
+        // Strings of 4 distinct characters of abc123, with a letter and a digit.
+        let unique = Unique::new(&vec![chars("abc123"); 4], &[set("abc"), set("123")]).unwrap();
+        assert!((unique.entropy() - 360f64.log2()).abs() < 1e-9);
+
+        // First a letter and last a digit: 3 * 3 * 4 * 3.
+        let alphabets = [chars("abc"), chars("abc123"), chars("abc123"), chars("123")];
+        let unique = Unique::new(&alphabets, &[]).unwrap();
+        assert!((unique.entropy() - 108f64.log2()).abs() < 1e-9);
+
+        assert!(Unique::new(&vec![chars("abc"); 4], &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut rng = rand::thread_rng();
+        let alphabets = [chars("abc"), chars("abc1"), chars("abc1")];
+        let unique = Unique::new(&alphabets, &[set("1")]).unwrap();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for _ in 0..6000 {
+            *counts.entry(unique.sample(&mut rng)).or_default() += 1;
+        }
+        let mut found: Vec<&String> = counts.keys().collect();
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                "a1b", "a1c", "ab1", "ac1", "b1a", "b1c", "ba1", "bc1", "c1a", "c1b", "ca1", "cb1"
+            ]
+        );
+        assert!((unique.entropy() - 12f64.log2()).abs() < 1e-9);
+        assert!(counts.values().all(|c| 400 < *c && *c < 600));
+    }
+}
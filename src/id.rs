@@ -0,0 +1,324 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::Rng;
+
+use crate::generator::character::Category;
+use crate::generator::error::Error;
+
+pub const KINDS: [&str; 4] = ["uuid4", "uuid7", "ulid", "nanoid"];
+/// Alphabet of Nano ID, which is Base64 for URLs.
+pub const NANOID_ALPHABET: &str =
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789_-";
+pub const DEFAULT_NANOID_SIZE: usize = 21;
+pub const MAX_NANOID_SIZE: usize = 255;
+pub const MIN_COUNT: usize = 1;
+pub const MAX_COUNT: usize = 10000;
+
+/// Alphabet of ULID, which is Crockford's Base32.
+const ULID_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+/// Random bits of UUID version 7 after the timestamp, which are rand_a and rand_b.
+const UUID7_RANDOM_BITS: u32 = 74;
+/// Random bits of ULID after the timestamp.
+const ULID_RANDOM_BITS: u32 = 80;
+
+/// Kind of identifiers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Kind {
+    /// UUID of 122 random bits.
+    Uuid4,
+    /// UUID of a timestamp in milliseconds and 74 random bits, which sorts by time.
+    Uuid7,
+    /// ULID of a timestamp in milliseconds and 80 random bits in Crockford's Base32.
+    Ulid,
+    /// Nano ID of `size` characters of `alphabet`.
+    Nanoid { alphabet: Vec<char>, size: usize },
+}
+
+impl Kind {
+    /// Kind of `name`, whose Nano ID is 21 characters of `NANOID_ALPHABET`.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "uuid4" => Ok(Kind::Uuid4),
+            "uuid7" => Ok(Kind::Uuid7),
+            "ulid" => Ok(Kind::Ulid),
+            "nanoid" => Ok(Kind::Nanoid {
+                alphabet: NANOID_ALPHABET.chars().collect(),
+                size: DEFAULT_NANOID_SIZE,
+            }),
+            _ => Err(format!("unknown kind: {}", name)),
+        }
+    }
+
+    /// Nano ID of `size` characters of `alphabet`, leaving out the characters repeated in it.
+    pub fn nanoid(alphabet: &str, size: usize) -> Result<Self, String> {
+        let mut characters: Vec<char> = Vec::with_capacity(alphabet.len());
+        for c in alphabet.chars() {
+            if !characters.contains(&c) {
+                characters.push(c);
+            }
+        }
+        if characters.len() < 2 {
+            return Err(format!(
+                "Needs 2 or more characters in the alphabet: {}",
+                characters.len()
+            ));
+        }
+        Self::validate_size(size)?;
+        Ok(Kind::Nanoid {
+            alphabet: characters,
+            size,
+        })
+    }
+
+    /// Nano ID of the characters of the categories of `usable` flags.
+    pub fn nanoid_of_categories(usable: &str, is_easy: bool, size: usize) -> Result<Self, String> {
+        let alphabet = Category::alphabet(usable, is_easy).map_err(|e| match e {
+            Error::CategoryFlagErr(flags) => format!("unknown usable flags: {}", flags),
+            _ => String::from("Needs 1 or more usable flags"),
+        })?;
+        Self::nanoid(&alphabet.into_iter().collect::<String>(), size)
+    }
+
+    pub fn validate_size(size: usize) -> Result<(), String> {
+        if (1..=MAX_NANOID_SIZE).contains(&size) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Needs 1 to {} characters: {}",
+                MAX_NANOID_SIZE, size
+            ))
+        }
+    }
+
+    /// Entropy of the random bits in bits, without the timestamp.
+    pub fn entropy(&self) -> f64 {
+        match self {
+            Kind::Uuid4 => 122.0,
+            Kind::Uuid7 => f64::from(UUID7_RANDOM_BITS),
+            Kind::Ulid => f64::from(ULID_RANDOM_BITS),
+            Kind::Nanoid { alphabet, size } => *size as f64 * (alphabet.len() as f64).log2(),
+        }
+    }
+}
+
+/// Identifiers of a kind.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ids {
+    kind: Kind,
+    count: usize,
+}
+
+impl Ids {
+    pub fn new(kind: Kind, count: usize) -> Result<Self, String> {
+        Self::validate_count(count)?;
+        Ok(Ids { kind, count })
+    }
+
+    pub fn validate_count(count: usize) -> Result<(), String> {
+        if (MIN_COUNT..=MAX_COUNT).contains(&count) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Needs {} to {} identifiers: {}",
+                MIN_COUNT, MAX_COUNT, count
+            ))
+        }
+    }
+
+    /// Entropy of an identifier in bits.
+    pub fn entropy(&self) -> f64 {
+        self.kind.entropy()
+    }
+
+    pub fn generate(&self) -> Vec<String> {
+        // Clocks before the Unix epoch are taken as the epoch.
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        self.sample(&mut rand::thread_rng(), millis)
+    }
+
+    /// Identifiers at `millis` since the Unix epoch, sorted in the order they are generated.
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R, millis: u64) -> Vec<String> {
+        let mut previous: Option<u128> = None;
+        let mut ids = Vec::with_capacity(self.count);
+        for _ in 0..self.count {
+            let id = match &self.kind {
+                Kind::Uuid4 => uuid(rng.gen::<u128>(), 4),
+                Kind::Uuid7 => {
+                    let bits = monotonic(rng, millis, UUID7_RANDOM_BITS, &mut previous);
+                    // 12 bits of rand_a after the version and 62 bits of rand_b after the variant.
+                    let (time, random) =
+                        (bits >> UUID7_RANDOM_BITS, bits & mask(UUID7_RANDOM_BITS));
+                    uuid(time << 80 | (random >> 62) << 64 | random & mask(62), 7)
+                }
+                Kind::Ulid => ulid(monotonic(rng, millis, ULID_RANDOM_BITS, &mut previous)),
+                Kind::Nanoid { alphabet, size } => (0..*size)
+                    .map(|_| alphabet[rng.gen_range(0, alphabet.len())])
+                    .collect(),
+            };
+            ids.push(id);
+        }
+        ids
+    }
+}
+
+fn mask(bits: u32) -> u128 {
+    (1 << bits) - 1
+}
+
+/// 48 bits of `millis` followed by random bits, incrementing `previous` in the same millisecond.
+fn monotonic<R: Rng + ?Sized>(
+    rng: &mut R,
+    millis: u64,
+    random_bits: u32,
+    previous: &mut Option<u128>,
+) -> u128 {
+    let now = u128::from(millis) & mask(48);
+    let bits = match previous {
+        // Overflowing random bits carry into the timestamp.
+        Some(p) if *p >> random_bits >= now => *p + 1,
+        _ => now << random_bits | rng.gen::<u128>() & mask(random_bits),
+    };
+    *previous = Some(bits);
+    bits
+}
+
+/// UUID of `bits` with the version and the variant of RFC 9562 set, in lowercase hex.
+fn uuid(bits: u128, version: u8) -> String {
+    let bits = bits & !(0xf << 76 | 0x3 << 62) | u128::from(version) << 76 | 0x2 << 62;
+    let hex = format!("{:032x}", bits);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// ULID of 128 `bits` in 26 characters of Crockford's Base32, starting from the highest bits.
+fn ulid(bits: u128) -> String {
+    (0..26)
+        .rev()
+        .map(|i| ULID_ALPHABET[(bits >> (i * 5)) as usize & 31] as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn test_uuid() {
+        // Examples of RFC 9562.
+        assert_eq!(
+            uuid(0x919108f7_52d1_3320_5bac_f847db4148a8, 4),
+            "919108f7-52d1-4320-9bac-f847db4148a8"
+        );
+        assert_eq!(
+            uuid(0x017f22e2_79b0_0cc3_18c4_dc0c0c07398f, 7),
+            "017f22e2-79b0-7cc3-98c4-dc0c0c07398f"
+        );
+        let ids = Ids::new(Kind::Uuid4, 10).unwrap().generate();
+        for id in &ids {
+            assert_eq!(id.len(), 36);
+            assert_eq!(&id[14..15], "4");
+            assert!("89ab".contains(&id[19..20]));
+        }
+        assert_ne!(ids[0], ids[1]);
+    }
+
+    #[test]
+    fn test_uuid7() {
+        let ids = Ids::new(Kind::Uuid7, 3).unwrap();
+        let generated = ids.sample(&mut StepRng::new(0, 0), 0x017f_22e2_79b0);
+        assert_eq!(
+            generated,
+            vec![
+                "017f22e2-79b0-7000-8000-000000000000",
+                "017f22e2-79b0-7000-8000-000000000001",
+                "017f22e2-79b0-7000-8000-000000000002",
+            ]
+        );
+        let generated = ids.sample(&mut StepRng::new(u64::MAX, 0), 0x017f_22e2_79b0);
+        assert_eq!(
+            generated,
+            vec![
+                "017f22e2-79b0-7fff-bfff-ffffffffffff",
+                "017f22e2-79b1-7000-8000-000000000000",
+                "017f22e2-79b1-7000-8000-000000000001",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ulid() {
+        // Example of the ULID specification.
+        assert_eq!(
+            ulid(1469922850259 << 80 | 0xd6764c61efb99302bd5b),
+            "01ARZ3NDEKTSV4RRFFQ69G5FAV"
+        );
+        let ids = Ids::new(Kind::Ulid, 2).unwrap();
+        let generated = ids.sample(&mut StepRng::new(0, 0), 1469922850259);
+        assert_eq!(
+            generated,
+            vec!["01ARZ3NDEK0000000000000000", "01ARZ3NDEK0000000000000001"]
+        );
+        let generated = ids.generate();
+        assert!(generated[0] < generated[1]);
+    }
+
+    #[test]
+    fn test_nanoid() {
+        let ids = Ids::new(Kind::from_name("nanoid").unwrap(), 10).unwrap();
+        for id in ids.generate() {
+            assert_eq!(id.len(), 21);
+            assert!(id.chars().all(|c| NANOID_ALPHABET.contains(c)));
+        }
+        let kind = Kind::nanoid_of_categories("ln", true, 12).unwrap();
+        for id in Ids::new(kind.clone(), 10).unwrap().generate() {
+            assert_eq!(id.len(), 12);
+            assert!(id
+                .chars()
+                .all(|c| "abcdefghijkmnpqrstuvwxyz23456789".contains(c)));
+        }
+        assert_eq!(kind.entropy(), 60.0);
+        assert_eq!(
+            Kind::nanoid_of_categories("lx", true, 12),
+            Err(String::from("unknown usable flags: x"))
+        );
+        assert_eq!(
+            Kind::nanoid("aaa", 12),
+            Err(String::from(
+                "Needs 2 or more characters in the alphabet: 1"
+            ))
+        );
+        assert_eq!(
+            Kind::nanoid("abca", 12),
+            Ok(Kind::Nanoid {
+                alphabet: vec!['a', 'b', 'c'],
+                size: 12
+            })
+        );
+        assert_eq!(
+            Kind::nanoid("ab", 0),
+            Err(String::from("Needs 1 to 255 characters: 0"))
+        );
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Kind::from_name("uuid7"), Ok(Kind::Uuid7));
+        assert_eq!(
+            Kind::from_name("uuid1"),
+            Err(String::from("unknown kind: uuid1"))
+        );
+        assert_eq!(
+            Ids::new(Kind::Ulid, 0),
+            Err(String::from("Needs 1 to 10000 identifiers: 0"))
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use rand::Rng;
+use unicode_normalization::{is_nfc, is_nfkc};
+
+/// Bundled wordlist of common hiragana words, one word in a line.
+const WORDS: &str = include_str!("kana/words.txt");
+
+pub const DEFAULT_WORDS: usize = 6;
+pub const MIN_WORDS: usize = 4;
+pub const MAX_WORDS: usize = 32;
+pub const DEFAULT_SEPARATOR: &str = " ";
+
+/// Romaji of hiragana from ぁ to ゖ, as typed into an IME.
+const ROMAJI: [&str; 86] = [
+    "xa", "a", "xi", "i", "xu", "u", "xe", "e", "xo", "o", "ka", "ga", "ki", "gi", "ku", "gu",
+    "ke", "ge", "ko", "go", "sa", "za", "shi", "ji", "su", "zu", "se", "ze", "so", "zo", "ta",
+    "da", "chi", "di", "xtu", "tsu", "du", "te", "de", "to", "do", "na", "ni", "nu", "ne", "no",
+    "ha", "ba", "pa", "hi", "bi", "pi", "fu", "bu", "pu", "he", "be", "pe", "ho", "bo", "po", "ma",
+    "mi", "mu", "me", "mo", "xya", "ya", "xyu", "yu", "xyo", "yo", "ra", "ri", "ru", "re", "ro",
+    "xwa", "wa", "wyi", "wye", "wo", "nn", "vu", "xka", "xke",
+];
+
+const SOKUON: char = 'っ';
+
+/// Passphrase of words drawn uniformly from the bundled wordlist.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Passphrase {
+    words: Vec<&'static str>,
+    count: usize,
+    separator: String,
+}
+
+impl Passphrase {
+    /// Passphrase of `count` words joined by `separator`, which must not contain kana.
+    pub fn new(count: usize, separator: &str) -> Result<Self, String> {
+        if count < MIN_WORDS {
+            return Err(format!("Needs {} or more words: {}", MIN_WORDS, count));
+        }
+        if count > MAX_WORDS {
+            return Err(format!("Needs {} or less words: {}", MAX_WORDS, count));
+        }
+        if separator.is_empty() || separator.chars().any(is_kana) {
+            return Err(format!(
+                "separator must not be empty nor kana: {}",
+                separator
+            ));
+        }
+        if !is_nfc(separator) || !is_nfkc(separator) {
+            return Err(format!(
+                "separator changes under NFC or NFKC normalization: {}",
+                separator
+            ));
+        }
+        Ok(Passphrase {
+            words: WORDS.lines().collect(),
+            count,
+            separator: String::from(separator),
+        })
+    }
+
+    pub fn generate(&self) -> String {
+        let mut rng = rand::thread_rng();
+        (0..self.count)
+            .map(|_| self.words[rng.gen_range(0, self.words.len())])
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
+
+    /// Entropy in bits, which is exact because every passphrase splits into words in one way.
+    pub fn entropy(&self) -> f64 {
+        self.count as f64 * (self.words.len() as f64).log2()
+    }
+}
+
+fn is_kana(c: char) -> bool {
+    ('\u{3041}'..='\u{309f}').contains(&c) || ('\u{30a0}'..='\u{30ff}').contains(&c)
+}
+
+/// Syllable of kana in romaji, with whether it is katakana.
+struct Syllable {
+    romaji: String,
+    sokuon: bool,
+    katakana: bool,
+}
+
+/// Romaji which an IME turns back into `kana`, with katakana in upper case.
+pub fn romaji(kana: &str) -> String {
+    let mut syllables: Vec<Syllable> = Vec::new();
+    for c in kana.chars() {
+        let (hiragana, katakana) = match c {
+            '\u{3041}'..='\u{3096}' => (c, false),
+            '\u{30a1}'..='\u{30f6}' => (std::char::from_u32(c as u32 - 0x60).unwrap(), true),
+            'ー' => {
+                syllables.push(Syllable {
+                    romaji: String::from("-"),
+                    sokuon: false,
+                    katakana: true,
+                });
+                continue;
+            }
+            _ => {
+                syllables.push(Syllable {
+                    romaji: c.to_string(),
+                    sokuon: false,
+                    katakana: false,
+                });
+                continue;
+            }
+        };
+        let romaji = ROMAJI[hiragana as usize - 0x3041];
+        if let Some(previous) = syllables.last_mut() {
+            if let Some(yoon) = yoon(&previous.romaji, hiragana) {
+                if previous.katakana == katakana && !previous.sokuon {
+                    previous.romaji = yoon;
+                    continue;
+                }
+            }
+        }
+        syllables.push(Syllable {
+            romaji: String::from(romaji),
+            sokuon: hiragana == SOKUON,
+            katakana,
+        });
+    }
+
+    let mut romaji = String::new();
+    for (i, syllable) in syllables.iter().enumerate() {
+        let text = if syllable.sokuon {
+            match syllables.get(i + 1).and_then(|s| s.romaji.chars().next()) {
+                Some(c) if c.is_ascii_lowercase() && !"aeiouny".contains(c) => c.to_string(),
+                _ => syllable.romaji.clone(),
+            }
+        } else {
+            syllable.romaji.clone()
+        };
+        if syllable.katakana {
+            romaji.push_str(&text.to_ascii_uppercase());
+        } else {
+            romaji.push_str(&text);
+        }
+    }
+    romaji
+}
+
+/// Contracted syllable of `previous`, such as `kya` of き and ゃ.
+fn yoon(previous: &str, small: char) -> Option<String> {
+    let vowel = match small {
+        'ゃ' => 'a',
+        'ゅ' => 'u',
+        'ょ' => 'o',
+        _ => return None,
+    };
+    match previous.strip_suffix('i')? {
+        stem @ ("sh" | "ch" | "j") => Some(format!("{}{}", stem, vowel)),
+        stem @ ("k" | "g" | "n" | "h" | "b" | "p" | "m" | "r" | "d") => {
+            Some(format!("{}y{}", stem, vowel))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+    use unicode_normalization::UnicodeNormalization;
+
+    /// Kana which an IME types for `romaji`, matching the longest syllable first.
+    fn ime(romaji: &str) -> String {
+        let mut table: Vec<(String, String)> = Vec::new();
+        for (i, r) in ROMAJI.iter().enumerate() {
+            let hiragana = std::char::from_u32(0x3041 + i as u32).unwrap();
+            table.push((String::from(*r), hiragana.to_string()));
+            for small in "ゃゅょ".chars() {
+                if let Some(yoon) = yoon(r, small) {
+                    table.push((yoon, format!("{}{}", hiragana, small)));
+                }
+            }
+        }
+        let katakana: Vec<(String, String)> = table
+            .iter()
+            .map(|(r, k)| {
+                let k = k
+                    .chars()
+                    .map(|c| std::char::from_u32(c as u32 + 0x60).unwrap());
+                (r.to_ascii_uppercase(), k.collect())
+            })
+            .collect();
+        table.extend(katakana);
+        table.sort_by_key(|(r, _)| std::cmp::Reverse(r.len()));
+
+        let mut kana = String::new();
+        let mut rest = romaji;
+        while let Some(first) = rest.chars().next() {
+            if rest[first.len_utf8()..].starts_with(first)
+                && first.is_ascii_alphabetic()
+                && !"aeiouynAEIOUYN".contains(first)
+            {
+                kana.push(if first.is_ascii_lowercase() {
+                    'っ'
+                } else {
+                    'ッ'
+                });
+                rest = &rest[1..];
+            } else if let Some((r, k)) = table.iter().find(|(r, _)| rest.starts_with(r.as_str())) {
+                kana.push_str(k);
+                rest = &rest[r.len()..];
+            } else if first == '-' {
+                kana.push('ー');
+                rest = &rest[1..];
+            } else {
+                kana.push(first);
+                rest = &rest[first.len_utf8()..];
+            }
+        }
+        kana
+    }
+
+    #[test]
+    fn test_romaji() {
+        assert_eq!(romaji("しんぶん"), "shinnbunn");
+        assert_eq!(romaji("きっぷ"), "kippu");
+        assert_eq!(romaji("じてんしゃ"), "jitennsha");
+        assert_eq!(romaji("ちゃわん"), "chawann");
+        assert_eq!(romaji("ぢづを"), "diduwo");
+        assert_eq!(romaji("キャンプ"), "KYANNPU");
+        assert_eq!(romaji("コーヒー"), "KO-HI-");
+        assert_eq!(romaji("あっ"), "axtu");
+        assert_eq!(romaji("a1ゃ"), "a1xya");
+        // The small kana of katakana are not contracted with hiragana.
+        assert_eq!(romaji("きャ"), "kiXYA");
+    }
+
+    #[test]
+    fn test_romaji_ime() {
+        for word in WORDS.lines() {
+            assert_eq!(ime(&romaji(word)), word, "{}", romaji(word));
+        }
+        let kana: String = (0x3041..=0x3096)
+            .chain(0x30a1..=0x30f6)
+            .filter_map(std::char::from_u32)
+            .filter(|c| !"っッ".contains(*c))
+            .collect();
+        for (a, b) in kana.chars().zip(kana.chars().rev()) {
+            let pair = format!("{}{}", a, b);
+            assert_eq!(ime(&romaji(&pair)), pair, "{}", romaji(&pair));
+        }
+    }
+
+    #[test]
+    fn test_words() {
+        let words: Vec<&str> = WORDS.lines().collect();
+        let unique: HashSet<&str> = words.iter().cloned().collect();
+        assert_eq!(unique.len(), words.len());
+        assert!(words.len() >= 512);
+        for word in words {
+            assert!(!word.is_empty() && word.chars().all(is_kana));
+            // IME input in NFC, NFKC or decomposed form is the same word.
+            assert_eq!(word.nfc().collect::<String>(), word);
+            assert_eq!(word.nfkc().collect::<String>(), word);
+            assert_eq!(word.nfd().nfc().collect::<String>(), word);
+        }
+    }
+
+    #[test]
+    fn test_passphrase() {
+        let passphrase = Passphrase::new(6, DEFAULT_SEPARATOR).unwrap();
+        let words: HashSet<&str> = WORDS.lines().collect();
+        for _ in 0..100 {
+            let generated = passphrase.generate();
+            let split: Vec<&str> = generated.split(' ').collect();
+            assert_eq!(split.len(), 6);
+            assert!(split.iter().all(|w| words.contains(w)));
+            // An IME types the separator as an ideographic space.
+            let typed = generated.replace(' ', "\u{3000}");
+            assert_eq!(typed.nfkc().collect::<String>(), generated);
+        }
+        let entropy = 6.0 * (words.len() as f64).log2();
+        assert!((passphrase.entropy() - entropy).abs() < 1e-9);
+
+        assert_eq!(
+            Passphrase::new(3, "-"),
+            Err(String::from("Needs 4 or more words: 3"))
+        );
+        assert_eq!(
+            Passphrase::new(33, "-"),
+            Err(String::from("Needs 32 or less words: 33"))
+        );
+        assert_eq!(
+            Passphrase::new(6, "の"),
+            Err(String::from("separator must not be empty nor kana: の"))
+        );
+        assert_eq!(
+            Passphrase::new(6, "\u{3000}"),
+            Err(String::from(
+                "separator changes under NFC or NFKC normalization: \u{3000}"
+            ))
+        );
+    }
+}
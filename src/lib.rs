@@ -1,11 +1,47 @@
 mod generator;
+pub use generator::character::CustomCategory;
 pub use generator::cli;
+pub use generator::policy::Policy;
 pub use generator::Generator;
 
+pub mod apikey;
+pub mod config;
+pub mod encoding;
+pub mod external;
+pub mod format;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod id;
+pub mod kana;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod mnemonic;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod otp;
+pub mod pin;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod qr;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod recovery;
+pub mod render;
+pub mod rpc;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod server;
+mod service;
+pub mod spell;
+pub mod token;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod wifi;
+
+#[cfg(feature = "wasm")]
+mod wasm;
+
 use std::ffi::CString;
 use std::os::raw::c_char;
 
-pub extern "C" fn generate(length: usize, usable_code: usize, is_easy_code: usize) -> *mut c_char {
+pub extern "C-unwind" fn generate(
+    length: usize,
+    usable_code: usize,
+    is_easy_code: usize,
+) -> *mut c_char {
     let is_easy = is_easy_code & (1 as usize) == 1;
     let generator = match Generator::from_code(length, usable_code, is_easy, String::default()) {
         Ok(gen) => gen,
@@ -1,11 +1,393 @@
-use pswdgn::{cli, Generator};
+// The command line uses the native modules, which wasm builds leave out.
+#![cfg_attr(target_arch = "wasm32", no_main)]
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use pswdgn::{cli, config::Config, id::Ids, otp::Otp, qr, token::Token, wifi::Network, Policy};
 
 fn main() {
-    let cli = cli::CommandLine::parse(cli::build());
-    let generator = match Generator::from_cli(cli.length, cli.flags, cli.is_easy, cli.symbols) {
+    let arg_matches = cli::build().get_matches();
+    let config = match Config::load(arg_matches.value_of(cli::OPTION_CONFIG).map(Path::new)) {
+        Ok(config) => config,
+        Err(e) => fail(e),
+    };
+    match arg_matches.subcommand() {
+        (cli::apikey::SUBCOMMAND, Some(m)) => match m.subcommand() {
+            (cli::apikey::SUBCOMMAND_VERIFY, Some(m)) => {
+                apikey_verify(cli::apikey::VerifyCommand::from_matches(m))
+            }
+            _ => apikey(cli::apikey::ApiKeyCommand::from_matches(m)),
+        },
+        (cli::easy_profiles::SUBCOMMAND, Some(_)) => easy_profiles(&config),
+        (cli::external::SUBCOMMAND, Some(_)) => external(&config),
+        (cli::id::SUBCOMMAND, Some(m)) => id(cli::id::IdCommand::from_matches(m)),
+        (cli::kana_passphrase::SUBCOMMAND, Some(m)) => {
+            kana_passphrase(cli::kana_passphrase::KanaPassphraseCommand::from_matches(m))
+        }
+        (cli::mnemonic::SUBCOMMAND, Some(m)) => match m.subcommand() {
+            (cli::mnemonic::SUBCOMMAND_VERIFY, Some(m)) => {
+                mnemonic_verify(cli::mnemonic::VerifyCommand::from_matches(m))
+            }
+            _ => mnemonic(cli::mnemonic::MnemonicCommand::from_matches(m)),
+        },
+        (cli::otp::SUBCOMMAND, Some(m)) => match m.subcommand() {
+            (cli::otp::SUBCOMMAND_CODE, Some(m)) => {
+                otp_code(cli::otp::CodeCommand::from_matches(m))
+            }
+            _ => otp(cli::otp::OtpCommand::from_matches(m)),
+        },
+        (cli::pin::SUBCOMMAND, Some(m)) => pin(cli::pin::PinCommand::from_matches(m)),
+        (cli::recovery_codes::SUBCOMMAND, Some(m)) => {
+            recovery_codes(cli::recovery_codes::RecoveryCodesCommand::from_matches(m))
+        }
+        (cli::render::SUBCOMMAND, Some(m)) => {
+            render(cli::render::RenderCommand::from_matches(m), &config)
+        }
+        (cli::rpc::SUBCOMMAND, Some(_)) => rpc(&config),
+        (cli::serve::SUBCOMMAND, Some(m)) => serve(cli::serve::parse(m), config),
+        (cli::token::SUBCOMMAND, Some(m)) => token(cli::token::TokenCommand::from_matches(m)),
+        (cli::wifi::SUBCOMMAND, Some(m)) => wifi(cli::wifi::WifiCommand::from_matches(m)),
+        _ => generate(cli::CommandLine::from_matches(&arg_matches), &config),
+    }
+}
+
+fn generate(cli: cli::CommandLine, config: &Config) {
+    let romaji = cli.romaji;
+    let mobile = cli.mobile;
+    let grouping = cli.grouping.clone();
+    let (spell, color) = (cli.spell, cli.color);
+    let qr = QrOutput {
+        terminal: cli.qr || (cli.qr_wifi.is_some() && cli.qr_output.is_none()),
+        image: cli.qr_output.clone(),
+    };
+    let qr_wifi = cli.qr_wifi.clone();
+    let mut policy = Policy::from(cli);
+    config.resolve(&mut policy);
+    let generator = match policy.generator() {
         Ok(gen) => gen,
-        Err(e) => panic!("{}", e),
+        Err(e) => fail(e),
     };
     let generated = generator.generate();
+    let shown = |s: &str| {
+        if color {
+            generator.colorize(s)
+        } else {
+            String::from(s)
+        }
+    };
+    match &grouping {
+        Some(g) => {
+            let groups: Vec<String> = g.groups(&generated).iter().map(|s| shown(s)).collect();
+            println!("{}", groups.join(g.separator()));
+        }
+        None => println!("{}", shown(&generated)),
+    }
+    if romaji {
+        println!("{}", pswdgn::kana::romaji(&generated));
+    }
+    if let Some(language) = spell {
+        println!("{}", language.spell(&generated));
+    }
+    let payload = match &qr_wifi {
+        Some(ssid) => qr::wifi_payload(ssid, &generated, "WPA", false),
+        None => generated.clone(),
+    };
+    if let Err(e) = show_qr(&qr, &payload) {
+        fail(e);
+    }
+    if mobile {
+        let entropy = generator.entropy();
+        match generator.uniform_entropy() {
+            Ok(uniform) => eprintln!(
+                "entropy: {:.2} bits, {:.2} bits less than uniform",
+                entropy,
+                uniform - entropy
+            ),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+fn read_stdin() -> Result<String, String> {
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|e| e.to_string())?;
+    Ok(input)
+}
+
+fn fail(e: impl std::fmt::Display) -> ! {
+    eprintln!("error: {}", e);
+    std::process::exit(1)
+}
+
+struct QrOutput {
+    terminal: bool,
+    image: Option<PathBuf>,
+}
+
+fn show_qr(output: &QrOutput, payload: &str) -> Result<(), String> {
+    if !output.terminal && output.image.is_none() {
+        return Ok(());
+    }
+    let code = qr::Qr::new(payload)?;
+    if output.terminal {
+        println!("{}", code.to_terminal());
+    }
+    if let Some(path) = &output.image {
+        let written = match cli::QrFormat::from_path(&path.to_string_lossy()) {
+            Some(cli::QrFormat::Svg) => pswdgn::render::write_private(path, code.to_svg()),
+            _ => pswdgn::render::write_private(path, code.to_png()),
+        };
+        written.map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+fn kana_passphrase(command: cli::kana_passphrase::KanaPassphraseCommand) {
+    let generated = command.passphrase.generate();
     println!("{}", generated);
+    if command.romaji {
+        println!("{}", pswdgn::kana::romaji(&generated));
+    }
+}
+
+fn wifi(command: cli::wifi::WifiCommand) {
+    let network = match Network::generate(&command.ssid, command.length, command.security) {
+        Ok(network) => network,
+        Err(e) => fail(e),
+    };
+    println!("{}", network.passphrase());
+    if command.psk {
+        println!("{}", network.psk());
+    }
+    if command.conf {
+        println!("{}", network.supplicant_conf());
+    }
+    if command.uri {
+        println!("{}", network.uri());
+    }
+    let qr = QrOutput {
+        terminal: command.qr,
+        image: command.qr_output,
+    };
+    if let Err(e) = show_qr(&qr, &network.uri()) {
+        fail(e);
+    }
+}
+
+fn id(command: cli::id::IdCommand) {
+    let ids = match command
+        .kind()
+        .and_then(|kind| Ids::new(kind, command.count))
+    {
+        Ok(ids) => ids,
+        Err(e) => fail(e),
+    };
+    for id in ids.generate() {
+        println!("{}", id);
+    }
+    eprintln!("entropy: {:.2} bits per identifier", ids.entropy());
+}
+
+fn mnemonic(command: cli::mnemonic::MnemonicCommand) {
+    println!("{}", command.mnemonic.generate());
+    eprintln!("entropy: {} bits", command.mnemonic.entropy());
+}
+
+fn mnemonic_verify(command: cli::mnemonic::VerifyCommand) {
+    if let Err(e) = try_mnemonic_verify(command) {
+        fail(e);
+    }
+}
+
+fn try_mnemonic_verify(command: cli::mnemonic::VerifyCommand) -> Result<(), String> {
+    let phrase = match command.phrase {
+        Some(phrase) => phrase,
+        None => read_stdin()?,
+    };
+    pswdgn::mnemonic::verify(&phrase)?;
+    println!("valid");
+    Ok(())
+}
+
+fn otp(command: cli::otp::OtpCommand) {
+    let otp = match Otp::generate(
+        command.bytes,
+        command.kind,
+        command.digits,
+        command.algorithm,
+    ) {
+        Ok(otp) => otp,
+        Err(e) => fail(e),
+    };
+    let uri = otp.uri(&command.issuer, &command.account);
+    println!("{}", otp.secret());
+    println!("{}", uri);
+    let qr = QrOutput {
+        terminal: command.qr,
+        image: command.qr_output,
+    };
+    if let Err(e) = show_qr(&qr, &uri) {
+        fail(e);
+    }
+}
+
+fn otp_code(command: cli::otp::CodeCommand) {
+    if let Err(e) = try_otp_code(command) {
+        fail(e);
+    }
+}
+
+fn try_otp_code(command: cli::otp::CodeCommand) -> Result<(), String> {
+    let secret = match command.secret {
+        Some(secret) => secret,
+        None => String::from(read_stdin()?.trim()),
+    };
+    let otp = Otp::from_base32(&secret, command.kind, command.digits, command.algorithm)?;
+    let time = match command.time {
+        Some(time) => time,
+        None => std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_secs(),
+    };
+    println!("{}", otp.code(time));
+    Ok(())
+}
+
+fn pin(command: cli::pin::PinCommand) {
+    let pin = match pswdgn::pin::Pin::new(command.length) {
+        Ok(pin) => pin,
+        Err(e) => fail(e),
+    };
+    println!("{}", pin.generate());
+    eprintln!(
+        "entropy: {:.2} bits, {:.2} bits less than uniform, leaving out {} weak PINs",
+        pin.entropy(),
+        pin.uniform_entropy() - pin.entropy(),
+        pin.rejected()
+    );
+}
+
+fn recovery_codes(command: cli::recovery_codes::RecoveryCodesCommand) {
+    let recovery = match pswdgn::recovery::RecoveryCodes::new(
+        command.count,
+        command.groups,
+        command.group_length,
+        &command.usable,
+        &command.separator,
+    ) {
+        Ok(recovery) => recovery,
+        Err(e) => fail(e),
+    };
+    for code in recovery.generate() {
+        if command.hash {
+            println!("{} {}", code, recovery.hash(&code));
+        } else {
+            println!("{}", code);
+        }
+    }
+    eprintln!("entropy: {:.2} bits per code", recovery.entropy());
+}
+
+fn token(command: cli::token::TokenCommand) {
+    let token = match Token::new(command.bytes, command.encoding, command.padding) {
+        Ok(token) => token,
+        Err(e) => fail(e),
+    };
+    println!("{}", token.generate());
+    eprintln!("entropy: {} bits", token.entropy());
+}
+
+fn apikey(command: cli::apikey::ApiKeyCommand) {
+    println!("{}", command.api_key.generate());
+    if command.regex {
+        println!("{}", command.api_key.regex());
+    }
+    eprintln!("entropy: {:.2} bits", command.api_key.entropy());
+}
+
+fn apikey_verify(command: cli::apikey::VerifyCommand) {
+    if let Err(e) = try_apikey_verify(command) {
+        fail(e);
+    }
+}
+
+fn try_apikey_verify(command: cli::apikey::VerifyCommand) -> Result<(), String> {
+    let key = match command.key {
+        Some(key) => key,
+        None => String::from(read_stdin()?.trim()),
+    };
+    pswdgn::apikey::verify(&key)?;
+    println!("valid");
+    Ok(())
+}
+
+fn easy_profiles(config: &Config) {
+    for (name, removed) in config.removed() {
+        println!("{}: {}", name, removed);
+    }
+}
+
+fn external(config: &Config) {
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    if let Err(e) = pswdgn::external::run(stdin.lock(), stdout.lock(), config) {
+        fail(e);
+    }
+}
+
+fn render(command: cli::render::RenderCommand, config: &Config) {
+    if let Err(e) = try_render(command, config) {
+        fail(e);
+    }
+}
+
+fn try_render(command: cli::render::RenderCommand, config: &Config) -> Result<(), String> {
+    let template = match &command.template {
+        Some(path) => {
+            std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?
+        }
+        None => read_stdin()?,
+    };
+    let existing = match &command.output {
+        Some(path) if command.keep && path.exists() => {
+            Some(std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?)
+        }
+        _ => None,
+    };
+
+    let rendered = pswdgn::render::render(&template, existing.as_deref(), config)?;
+    match &command.output {
+        Some(path) => pswdgn::render::write_private(path, &rendered)
+            .map_err(|e| format!("{}: {}", path.display(), e)),
+        None => {
+            if pswdgn::render::stdout_is_shared_file() {
+                eprintln!(
+                    "warning: standard output is a file readable by others; -o FILE writes it readable only by the owner"
+                );
+            }
+            print!("{}", rendered);
+            Ok(())
+        }
+    }
+}
+
+fn rpc(config: &Config) {
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    if let Err(e) = pswdgn::rpc::serve(stdin.lock(), stdout.lock(), config) {
+        fail(e);
+    }
+}
+
+fn serve(server_config: pswdgn::server::Config, config: Config) {
+    let server = match pswdgn::server::Server::bind(server_config) {
+        Ok(server) => server.with_settings(config),
+        Err(e) => fail(e),
+    };
+    server.run();
 }
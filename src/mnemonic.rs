@@ -0,0 +1,213 @@
+use pbkdf2::pbkdf2_hmac;
+use rand::Rng;
+use sha2::{Digest, Sha256, Sha512};
+use unicode_normalization::UnicodeNormalization;
+
+/// Bundled English wordlist of BIP39, one word in a line in the order of their indices.
+const WORDS: &str = include_str!("mnemonic/english.txt");
+
+/// Numbers of words of BIP39, for 128 to 256 bits of entropy.
+pub const WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+pub const DEFAULT_WORDS: usize = 12;
+
+/// Bits a word encodes.
+const BITS_PER_WORD: usize = 11;
+/// Iterations of PBKDF2 in seeds.
+const SEED_ITERATIONS: u32 = 2048;
+
+/// Mnemonic of `words` words.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mnemonic {
+    words: usize,
+}
+
+impl Mnemonic {
+    pub fn new(words: usize) -> Result<Self, String> {
+        if WORD_COUNTS.contains(&words) {
+            Ok(Mnemonic { words })
+        } else {
+            Err(format!("Needs 12, 15, 18, 21 or 24 words: {}", words))
+        }
+    }
+
+    /// Entropy in bits, which leaves out the checksum.
+    pub fn entropy(&self) -> f64 {
+        (self.words * BITS_PER_WORD * 32 / 33) as f64
+    }
+
+    pub fn generate(&self) -> String {
+        self.sample(&mut rand::thread_rng())
+    }
+
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        let mut entropy = vec![0u8; self.entropy() as usize / 8];
+        rng.fill_bytes(&mut entropy);
+        // The length of the entropy is always valid.
+        from_entropy(&entropy).unwrap()
+    }
+}
+
+fn wordlist() -> Vec<&'static str> {
+    WORDS.lines().collect()
+}
+
+/// Mnemonic of `entropy` of 16 to 32 bytes in steps of 4.
+pub fn from_entropy(entropy: &[u8]) -> Result<String, String> {
+    if !(16..=32).contains(&entropy.len()) || entropy.len() % 4 != 0 {
+        return Err(format!(
+            "Needs 16, 20, 24, 28 or 32 bytes of entropy: {}",
+            entropy.len()
+        ));
+    }
+    let checksum = Sha256::digest(entropy)[0];
+    let bits = entropy.len() * 8;
+    let bit = |i: usize| {
+        let byte = if i < bits { entropy[i / 8] } else { checksum };
+        usize::from(byte >> (7 - i % 8) & 1)
+    };
+    let words = wordlist();
+    let count = (bits + bits / 32) / BITS_PER_WORD;
+    Ok((0..count)
+        .map(|w| {
+            let index =
+                (0..BITS_PER_WORD).fold(0, |index, b| (index << 1) | bit(w * BITS_PER_WORD + b));
+            words[index]
+        })
+        .collect::<Vec<&str>>()
+        .join(" "))
+}
+
+/// Entropy of `phrase`, whose words are separated by white space, if its checksum is valid.
+pub fn verify(phrase: &str) -> Result<Vec<u8>, String> {
+    let words = wordlist();
+    let indices = phrase
+        .split_whitespace()
+        .map(|word| {
+            words
+                .binary_search(&word)
+                .map_err(|_| format!("unknown word: {}", word))
+        })
+        .collect::<Result<Vec<usize>, String>>()?;
+    if !WORD_COUNTS.contains(&indices.len()) {
+        return Err(format!(
+            "Needs 12, 15, 18, 21 or 24 words: {}",
+            indices.len()
+        ));
+    }
+    let bits: Vec<u8> = indices
+        .iter()
+        .flat_map(|index| {
+            (0..BITS_PER_WORD)
+                .rev()
+                .map(move |b| (index >> b & 1) as u8)
+        })
+        .collect();
+    let entropy_bits = bits.len() * 32 / 33;
+    let entropy: Vec<u8> = bits[..entropy_bits]
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0, |b, bit| (b << 1) | bit))
+        .collect();
+    let checksum = Sha256::digest(&entropy)[0];
+    let expected = bits[entropy_bits..]
+        .iter()
+        .fold(0u8, |b, bit| (b << 1) | bit);
+    let length = bits.len() - entropy_bits;
+    if checksum >> (8 - length) == expected {
+        Ok(entropy)
+    } else {
+        Err(String::from("checksum mismatch"))
+    }
+}
+
+/// Seed of 64 bytes of `phrase` and `passphrase`, derived by PBKDF2-HMAC-SHA512 of their NFKD.
+pub fn seed(phrase: &str, passphrase: &str) -> [u8; 64] {
+    let phrase: String = phrase.nfkd().collect();
+    let salt: String = format!("mnemonic{}", passphrase).nfkd().collect();
+    let mut seed = [0u8; 64];
+    pbkdf2_hmac::<Sha512>(
+        phrase.as_bytes(),
+        salt.as_bytes(),
+        SEED_ITERATIONS,
+        &mut seed,
+    );
+    seed
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_vectors() {
+        // Test vectors of BIP39 by Trezor, whose passphrase is TREZOR.
+        let vectors: serde_json::Value =
+            serde_json::from_str(include_str!("mnemonic/vectors.json")).unwrap();
+        for vector in vectors["english"].as_array().unwrap() {
+            let entropy = vector[0].as_str().unwrap();
+            let phrase = vector[1].as_str().unwrap();
+            let bytes: Vec<u8> = (0..entropy.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&entropy[i..i + 2], 16).unwrap())
+                .collect();
+            assert_eq!(from_entropy(&bytes).unwrap(), phrase);
+            assert_eq!(verify(phrase), Ok(bytes));
+            assert_eq!(hex(&seed(phrase, "TREZOR")), vector[2].as_str().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_wordlist() {
+        let words = wordlist();
+        assert_eq!(words.len(), 2048);
+        assert!(words.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(words[0], "abandon");
+        assert_eq!(words[2047], "zoo");
+    }
+
+    #[test]
+    fn test_generate() {
+        for words in WORD_COUNTS.iter() {
+            let mnemonic = Mnemonic::new(*words).unwrap();
+            let phrase = mnemonic.generate();
+            assert_eq!(phrase.split(' ').count(), *words);
+            assert_eq!(
+                verify(&phrase).unwrap().len() * 8,
+                mnemonic.entropy() as usize
+            );
+        }
+        assert_eq!(Mnemonic::new(12).unwrap().entropy(), 128.0);
+        assert_eq!(Mnemonic::new(24).unwrap().entropy(), 256.0);
+        assert_eq!(
+            Mnemonic::new(13),
+            Err(String::from("Needs 12, 15, 18, 21 or 24 words: 13"))
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let phrase = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        assert!(verify(&phrase.replace(' ', "\n  ")).is_ok());
+        assert_eq!(
+            verify(&phrase.replace("yellow", "year")),
+            Err(String::from("checksum mismatch"))
+        );
+        assert_eq!(
+            verify(&phrase.replace("yellow", "yelow")),
+            Err(String::from("unknown word: yelow"))
+        );
+        assert_eq!(
+            verify("legal winner thank"),
+            Err(String::from("Needs 12, 15, 18, 21 or 24 words: 3"))
+        );
+        assert_eq!(
+            from_entropy(&[0; 15]),
+            Err(String::from(
+                "Needs 16, 20, 24, 28 or 32 bytes of entropy: 15"
+            ))
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+
+use crate::encoding::{base32, decode_base32};
+
+/// RFC 4226 requires 128 bits of secret and recommends 160 bits.
+pub const MIN_BYTES: usize = 16;
+pub const MAX_BYTES: usize = 64;
+pub const DEFAULT_BYTES: usize = 20;
+pub const MIN_DIGITS: u32 = 6;
+pub const MAX_DIGITS: u32 = 8;
+pub const DEFAULT_DIGITS: u32 = 6;
+pub const DEFAULT_PERIOD: u64 = 30;
+
+/// Hash function of HMAC.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Algorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl Algorithm {
+    /// Algorithm of `name` as in otpauth URIs, such as `SHA1`, in either case.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_uppercase().as_str() {
+            "SHA1" => Ok(Algorithm::Sha1),
+            "SHA256" => Ok(Algorithm::Sha256),
+            "SHA512" => Ok(Algorithm::Sha512),
+            _ => Err(format!("unknown algorithm: {}", name)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Sha1 => "SHA1",
+            Algorithm::Sha256 => "SHA256",
+            Algorithm::Sha512 => "SHA512",
+        }
+    }
+
+    fn hmac(self, key: &[u8], message: &[u8]) -> Vec<u8> {
+        fn mac<M: Mac + KeyInit>(key: &[u8], message: &[u8]) -> Vec<u8> {
+            // HMAC takes keys of any length.
+            let mut mac = <M as Mac>::new_from_slice(key).unwrap();
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+        match self {
+            Algorithm::Sha1 => mac::<Hmac<Sha1>>(key, message),
+            Algorithm::Sha256 => mac::<Hmac<Sha256>>(key, message),
+            Algorithm::Sha512 => mac::<Hmac<Sha512>>(key, message),
+        }
+    }
+}
+
+/// Kind of one-time passwords.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    /// Time-based, changing every period of seconds.
+    Totp { period: u64 },
+    /// Counter-based, starting from the counter.
+    Hotp { counter: u64 },
+}
+
+/// Shared secret with the parameters of its codes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Otp {
+    secret: Vec<u8>,
+    kind: Kind,
+    digits: u32,
+    algorithm: Algorithm,
+}
+
+impl Otp {
+    pub fn new(
+        secret: Vec<u8>,
+        kind: Kind,
+        digits: u32,
+        algorithm: Algorithm,
+    ) -> Result<Self, String> {
+        Self::validate_digits(digits)?;
+        if let Kind::Totp { period: 0 } = kind {
+            return Err(String::from("period must be 1 second or more"));
+        }
+        if secret.is_empty() {
+            return Err(String::from("secret must not be empty"));
+        }
+        Ok(Otp {
+            secret,
+            kind,
+            digits,
+            algorithm,
+        })
+    }
+
+    /// Random secret of `bytes` bytes.
+    pub fn generate(
+        bytes: usize,
+        kind: Kind,
+        digits: u32,
+        algorithm: Algorithm,
+    ) -> Result<Self, String> {
+        Self::validate_bytes(bytes)?;
+        let mut secret = vec![0u8; bytes];
+        rand::thread_rng().fill_bytes(&mut secret);
+        Self::new(secret, kind, digits, algorithm)
+    }
+
+    pub fn validate_bytes(bytes: usize) -> Result<(), String> {
+        if (MIN_BYTES..=MAX_BYTES).contains(&bytes) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Needs {} to {} bytes: {}",
+                MIN_BYTES, MAX_BYTES, bytes
+            ))
+        }
+    }
+
+    pub fn validate_digits(digits: u32) -> Result<(), String> {
+        if (MIN_DIGITS..=MAX_DIGITS).contains(&digits) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Needs {} to {} digits: {}",
+                MIN_DIGITS, MAX_DIGITS, digits
+            ))
+        }
+    }
+
+    /// Secret of Base32 `text`, as shown by services or in otpauth URIs.
+    pub fn from_base32(
+        text: &str,
+        kind: Kind,
+        digits: u32,
+        algorithm: Algorithm,
+    ) -> Result<Self, String> {
+        Self::new(decode_base32(text)?, kind, digits, algorithm)
+    }
+
+    /// Base32 of the secret without padding.
+    pub fn secret(&self) -> String {
+        base32(&self.secret)
+    }
+
+    /// `otpauth://` URI, which authenticator apps scan as a QR code.
+    pub fn uri(&self, issuer: &str, account: &str) -> String {
+        let (kind, parameter) = match self.kind {
+            Kind::Totp { period } => ("totp", format!("period={}", period)),
+            Kind::Hotp { counter } => ("hotp", format!("counter={}", counter)),
+        };
+        let label = if issuer.is_empty() {
+            percent_encode(account)
+        } else {
+            format!("{}:{}", percent_encode(issuer), percent_encode(account))
+        };
+        let mut uri = format!("otpauth://{}/{}?secret={}", kind, label, self.secret());
+        if !issuer.is_empty() {
+            uri.push_str(&format!("&issuer={}", percent_encode(issuer)));
+        }
+        uri.push_str(&format!(
+            "&algorithm={}&digits={}&{}",
+            self.algorithm.name(),
+            self.digits,
+            parameter
+        ));
+        uri
+    }
+
+    /// Code of RFC 4226 at `counter`.
+    pub fn hotp(&self, counter: u64) -> String {
+        let hash = self.algorithm.hmac(&self.secret, &counter.to_be_bytes());
+        let offset = (hash[hash.len() - 1] & 0xf) as usize;
+        let binary = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+        format!(
+            "{:0width$}",
+            binary % 10u32.pow(self.digits),
+            width = self.digits as usize
+        )
+    }
+
+    /// Code of RFC 6238 at `time` in seconds since the Unix epoch, or of the counter of HOTP.
+    pub fn code(&self, time: u64) -> String {
+        match self.kind {
+            Kind::Totp { period } => self.hotp(time / period),
+            Kind::Hotp { counter } => self.hotp(counter),
+        }
+    }
+}
+
+/// `text` with the characters other than the unreserved characters of RFC 3986 encoded.
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_totp() {
+        // Test vectors of RFC 6238.
+        let times = [
+            59,
+            1111111109,
+            1111111111,
+            1234567890,
+            2000000000,
+            20000000000,
+        ];
+        let vectors = [
+            (
+                Algorithm::Sha1,
+                "12345678901234567890",
+                [
+                    "94287082", "07081804", "14050471", "89005924", "69279037", "65353130",
+                ],
+            ),
+            (
+                Algorithm::Sha256,
+                "12345678901234567890123456789012",
+                [
+                    "46119246", "68084774", "67062674", "91819424", "90698825", "77737706",
+                ],
+            ),
+            (
+                Algorithm::Sha512,
+                "1234567890123456789012345678901234567890123456789012345678901234",
+                [
+                    "90693936", "25091201", "99943326", "93441116", "38618901", "47863826",
+                ],
+            ),
+        ];
+        for (algorithm, secret, codes) in vectors.iter() {
+            let otp = Otp::new(
+                secret.as_bytes().to_vec(),
+                Kind::Totp { period: 30 },
+                8,
+                *algorithm,
+            )
+            .unwrap();
+            for (time, code) in times.iter().zip(codes.iter()) {
+                assert_eq!(otp.code(*time), *code, "{:?} {}", algorithm, time);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hotp() {
+        // Test vectors of RFC 4226.
+        let codes = [
+            "755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583",
+            "399871", "520489",
+        ];
+        let secret = b"12345678901234567890".to_vec();
+        for (counter, code) in codes.iter().enumerate() {
+            let otp = Otp::new(
+                secret.clone(),
+                Kind::Hotp {
+                    counter: counter as u64,
+                },
+                6,
+                Algorithm::Sha1,
+            )
+            .unwrap();
+            assert_eq!(otp.code(0), *code);
+        }
+    }
+
+    #[test]
+    fn test_uri() {
+        let otp = Otp::from_base32(
+            "JBSWY3DPEHPK3PXP",
+            Kind::Totp { period: 30 },
+            6,
+            Algorithm::Sha1,
+        )
+        .unwrap();
+        assert_eq!(
+            otp.uri("Example Co", "alice@example.com"),
+            "otpauth://totp/Example%20Co:alice%40example.com?secret=JBSWY3DPEHPK3PXP\
+             &issuer=Example%20Co&algorithm=SHA1&digits=6&period=30"
+        );
+        let otp = Otp::new(
+            otp.secret.clone(),
+            Kind::Hotp { counter: 5 },
+            8,
+            Algorithm::Sha256,
+        )
+        .unwrap();
+        assert_eq!(
+            otp.uri("", "ci"),
+            "otpauth://hotp/ci?secret=JBSWY3DPEHPK3PXP&algorithm=SHA256&digits=8&counter=5"
+        );
+    }
+
+    #[test]
+    fn test_generate() {
+        let otp = Otp::generate(20, Kind::Totp { period: 30 }, 6, Algorithm::Sha1).unwrap();
+        assert_eq!(otp.secret().len(), 32);
+        assert_eq!(decode_base32(&otp.secret()), Ok(otp.secret.clone()));
+        assert_ne!(
+            Otp::generate(20, Kind::Totp { period: 30 }, 6, Algorithm::Sha1).unwrap(),
+            otp
+        );
+        assert_eq!(
+            Otp::generate(8, Kind::Totp { period: 30 }, 6, Algorithm::Sha1),
+            Err(String::from("Needs 16 to 64 bytes: 8"))
+        );
+        assert_eq!(
+            Otp::generate(20, Kind::Totp { period: 30 }, 9, Algorithm::Sha1),
+            Err(String::from("Needs 6 to 8 digits: 9"))
+        );
+        assert_eq!(
+            Otp::generate(20, Kind::Totp { period: 0 }, 6, Algorithm::Sha1),
+            Err(String::from("period must be 1 second or more"))
+        );
+        assert_eq!(
+            Algorithm::from_name("md5"),
+            Err(String::from("unknown algorithm: md5"))
+        );
+        assert_eq!(Algorithm::from_name("sha256"), Ok(Algorithm::Sha256));
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::HashSet;
+
+use rand::Rng;
+
+/// Bundled list of the most common PINs, one PIN in a line.
+const COMMON: &str = include_str!("pin/common.txt");
+
+pub const MIN_LENGTH: usize = 4;
+pub const MAX_LENGTH: usize = 10;
+pub const DEFAULT_LENGTH: usize = 6;
+
+/// Rows and columns of the keys of a phone keypad, from 1 at the top left to 0 below 8.
+const KEYPAD: [(i32, i32); 10] = [
+    (3, 1),
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (2, 0),
+    (2, 1),
+    (2, 2),
+];
+
+/// Days of the months, with February 29.
+const DAYS: [u32; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+/// Years of four digits which are plausible birth years and anniversaries.
+const YEARS: std::ops::RangeInclusive<u32> = 1900..=2099;
+
+/// PIN of `length` digits drawn uniformly from those which are not weak.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pin {
+    length: usize,
+    weak: HashSet<String>,
+}
+
+impl Pin {
+    pub fn new(length: usize) -> Result<Self, String> {
+        Self::validate_length(length)?;
+        let mut weak = HashSet::new();
+        weak.extend(repeated(length));
+        weak.extend(progressions(length));
+        weak.extend(dates(length));
+        weak.extend(keypad_paths(length));
+        weak.extend(
+            COMMON
+                .lines()
+                .filter(|pin| pin.len() == length)
+                .map(String::from),
+        );
+        Ok(Pin { length, weak })
+    }
+
+    pub fn validate_length(length: usize) -> Result<(), String> {
+        if (MIN_LENGTH..=MAX_LENGTH).contains(&length) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Needs {} to {} digits: {}",
+                MIN_LENGTH, MAX_LENGTH, length
+            ))
+        }
+    }
+
+    pub fn is_weak(&self, pin: &str) -> bool {
+        self.weak.contains(pin)
+    }
+
+    /// Number of the weak PINs, which are left out.
+    pub fn rejected(&self) -> usize {
+        self.weak.len()
+    }
+
+    /// Exact entropy in bits of the PINs which are not weak.
+    pub fn entropy(&self) -> f64 {
+        (10f64.powi(self.length as i32) - self.weak.len() as f64).log2()
+    }
+
+    /// Entropy in bits of all PINs of the length.
+    pub fn uniform_entropy(&self) -> f64 {
+        self.length as f64 * 10f64.log2()
+    }
+
+    pub fn generate(&self) -> String {
+        self.sample(&mut rand::thread_rng())
+    }
+
+    /// Draws PINs uniformly until one is not weak, which keeps the rest uniform.
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        loop {
+            let pin: String = (0..self.length)
+                .map(|_| char::from(b'0' + rng.gen_range(0, 10)))
+                .collect();
+            if !self.is_weak(&pin) {
+                return pin;
+            }
+        }
+    }
+}
+
+/// PINs of a block of digits repeated twice or more.
+fn repeated(length: usize) -> Vec<String> {
+    let mut pins = Vec::new();
+    for block in (1..length).filter(|b| length % b == 0) {
+        for n in 0..10u64.pow(block as u32) {
+            pins.push(format!("{:0width$}", n, width = block).repeat(length / block));
+        }
+    }
+    pins
+}
+
+/// PINs whose digits go up or down by the same difference, wrapping from 9 to 0.
+fn progressions(length: usize) -> Vec<String> {
+    let mut pins = Vec::new();
+    for first in 0..10 {
+        for difference in 1..10 {
+            pins.push(
+                (0..length)
+                    .map(|i| char::from(b'0' + ((first + difference * i) % 10) as u8))
+                    .collect(),
+            );
+        }
+    }
+    pins
+}
+
+/// PINs of dates in the orders of the length.
+fn dates(length: usize) -> Vec<String> {
+    let days: Vec<(u32, u32)> = DAYS
+        .iter()
+        .enumerate()
+        .flat_map(|(m, days)| (1..=*days).map(move |d| (d, m as u32 + 1)))
+        .collect();
+    let mut pins = Vec::new();
+    match length {
+        4 => {
+            for (d, m) in &days {
+                pins.push(format!("{:02}{:02}", d, m));
+                pins.push(format!("{:02}{:02}", m, d));
+            }
+            pins.extend(YEARS.map(|y| y.to_string()));
+        }
+        6 | 8 => {
+            let years: Vec<String> = if length == 6 {
+                (0..100).map(|y| format!("{:02}", y)).collect()
+            } else {
+                YEARS.map(|y| y.to_string()).collect()
+            };
+            for (d, m) in &days {
+                for y in &years {
+                    pins.push(format!("{:02}{:02}{}", d, m, y));
+                    pins.push(format!("{:02}{:02}{}", m, d, y));
+                    pins.push(format!("{}{:02}{:02}", y, m, d));
+                }
+            }
+        }
+        _ => (),
+    }
+    pins
+}
+
+/// PINs of paths on a phone keypad which move to a neighbouring key and never come back.
+fn keypad_paths(length: usize) -> Vec<String> {
+    fn extend(path: &mut Vec<usize>, length: usize, pins: &mut Vec<String>) {
+        if path.len() == length {
+            pins.push(path.iter().map(|d| char::from(b'0' + *d as u8)).collect());
+            return;
+        }
+        let (row, column) = KEYPAD[path[path.len() - 1]];
+        for (next, (r, c)) in KEYPAD.iter().enumerate() {
+            let neighbour = (r - row).abs() <= 1 && (c - column).abs() <= 1;
+            if neighbour && !path.contains(&next) {
+                path.push(next);
+                extend(path, length, pins);
+                path.pop();
+            }
+        }
+    }
+    let mut pins = Vec::new();
+    for first in 0..10 {
+        extend(&mut vec![first], length, &mut pins);
+    }
+    pins
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_weak() {
+        let pin = Pin::new(4).unwrap();
+        for weak in &[
+            "0000", "7777", "1212", "1234", "8901", "4321", "2468", "3108", "1231", "1987", "2580",
+            "1478", "1379", "6969",
+        ] {
+            assert!(pin.is_weak(weak), "{}", weak);
+        }
+        for strong in &["3861", "0473", "5027", "1854"] {
+            assert!(!pin.is_weak(strong), "{}", strong);
+        }
+        let pin = Pin::new(6).unwrap();
+        for weak in &["123123", "310899", "123199", "991231", "147896", "159753"] {
+            assert!(pin.is_weak(weak), "{}", weak);
+        }
+        assert!(!pin.is_weak("305172"));
+        let pin = Pin::new(8).unwrap();
+        for weak in &["31121999", "12311999", "19991231", "12341234", "12345678"] {
+            assert!(pin.is_weak(weak), "{}", weak);
+        }
+    }
+
+    #[test]
+    fn test_rejected() {
+        // 100 repeated pairs, which include the 10 repeated digits.
+        assert_eq!(repeated(4).iter().collect::<HashSet<_>>().len(), 100);
+        assert_eq!(progressions(4).iter().collect::<HashSet<_>>().len(), 90);
+        // 366 DDMM and 366 MMDD, of which 144 are both, and 200 years, of which 1901 to 1912
+        // and 2001 to 2012 are DDMM.
+        assert_eq!(
+            dates(4).iter().collect::<HashSet<_>>().len(),
+            366 * 2 - 144 + 200 - 24
+        );
+        assert!(dates(5).is_empty());
+        // A 3x3 keypad and 0 below 8 has no path of 11 keys.
+        assert!(keypad_paths(11).is_empty());
+        // 20 neighbours among the 3x3 keys and 3 of 0, in both directions.
+        assert_eq!(keypad_paths(2).len(), 2 * (20 + 3));
+        let pin = Pin::new(4).unwrap();
+        assert_eq!(pin.entropy(), (10000.0 - pin.rejected() as f64).log2());
+        assert!(pin.entropy() < pin.uniform_entropy());
+    }
+
+    #[test]
+    fn test_generate() {
+        for length in MIN_LENGTH..=MAX_LENGTH {
+            let pin = Pin::new(length).unwrap();
+            for _ in 0..100 {
+                let generated = pin.generate();
+                assert_eq!(generated.len(), length);
+                assert!(generated.chars().all(|c| c.is_ascii_digit()));
+                assert!(!pin.is_weak(&generated));
+            }
+        }
+        assert_eq!(Pin::new(3), Err(String::from("Needs 4 to 10 digits: 3")));
+    }
+
+    #[test]
+    fn test_common() {
+        for pin in COMMON.lines() {
+            assert!(pin.chars().all(|c| c.is_ascii_digit()), "{}", pin);
+            assert!((MIN_LENGTH..=MAX_LENGTH).contains(&pin.len()), "{}", pin);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use qrcode::render::svg;
+use qrcode::{Color, QrCode};
+
+/// Light modules around the code in the terminal.
+const TERMINAL_QUIET_ZONE: usize = 2;
+/// Light modules around the code in images.
+const QUIET_ZONE: usize = 4;
+/// Pixels of a module in images.
+const SCALE: usize = 8;
+
+/// Payload of a Wi-Fi network of `security` `WPA`, `SAE`, `WEP` or `nopass`.
+pub fn wifi_payload(ssid: &str, password: &str, security: &str, hidden: bool) -> String {
+    let escape = |s: &str| -> String {
+        s.chars()
+            .flat_map(|c| {
+                let escaped = if "\\;,:\"".contains(c) {
+                    Some('\\')
+                } else {
+                    None
+                };
+                escaped.into_iter().chain(std::iter::once(c))
+            })
+            .collect()
+    };
+    let mut payload = match security {
+        "SAE" => format!("WIFI:T:WPA;R:1;S:{};", escape(ssid)),
+        _ => format!("WIFI:T:{};S:{};", security, escape(ssid)),
+    };
+    if security != "nopass" {
+        payload.push_str(&format!("P:{};", escape(password)));
+    }
+    if hidden {
+        payload.push_str("H:true;");
+    }
+    payload.push(';');
+    payload
+}
+
+/// QR code of a text, rendered in the terminal, PNG or SVG.
+pub struct Qr {
+    code: QrCode,
+}
+
+impl Qr {
+    pub fn new(text: &str) -> Result<Self, String> {
+        QrCode::new(text.as_bytes())
+            .map(|code| Qr { code })
+            .map_err(|e| format!("cannot encode in a QR code: {}", e))
+    }
+
+    /// Modules with `quiet_zone` light modules around the code, `true` for dark.
+    fn modules(&self, quiet_zone: usize) -> Vec<Vec<bool>> {
+        let width = self.code.width();
+        let colors = self.code.to_colors();
+        let size = width + 2 * quiet_zone;
+        (0..size)
+            .map(|y| {
+                (0..size)
+                    .map(|x| {
+                        x >= quiet_zone
+                            && y >= quiet_zone
+                            && x < width + quiet_zone
+                            && y < width + quiet_zone
+                            && colors[(y - quiet_zone) * width + x - quiet_zone] == Color::Dark
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Lines of Unicode half blocks, two rows of modules in a line.
+    pub fn to_terminal(&self) -> String {
+        let modules = self.modules(TERMINAL_QUIET_ZONE);
+        // The last line of an odd number of rows has nothing below.
+        let blank = vec![true; modules.len()];
+        modules
+            .chunks(2)
+            .map(|rows| {
+                let bottom = rows.get(1).unwrap_or(&blank);
+                rows[0]
+                    .iter()
+                    .zip(bottom)
+                    .map(|(top, bottom)| match (*top, *bottom) {
+                        (false, false) => '█',
+                        (false, true) => '▀',
+                        (true, false) => '▄',
+                        (true, true) => ' ',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn to_svg(&self) -> String {
+        self.code
+            .render::<svg::Color>()
+            .quiet_zone(true)
+            .module_dimensions(SCALE as u32, SCALE as u32)
+            .build()
+    }
+
+    /// Grayscale PNG image.
+    pub fn to_png(&self) -> Vec<u8> {
+        let modules = self.modules(QUIET_ZONE);
+        let size = modules.len() * SCALE;
+        let mut pixels = Vec::with_capacity(size * size);
+        for row in &modules {
+            let line: Vec<u8> = row
+                .iter()
+                .flat_map(|dark| std::iter::repeat(if *dark { 0 } else { 255 }).take(SCALE))
+                .collect();
+            for _ in 0..SCALE {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, size as u32, size as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        // Writing to a vector fails only for a wrong size of the pixels.
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&pixels).unwrap();
+        writer.finish().unwrap();
+        png
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_wifi_payload() {
+        assert_eq!(
+            wifi_payload("home", "abcd1234", "WPA", false),
+            "WIFI:T:WPA;S:home;P:abcd1234;;"
+        );
+        assert_eq!(
+            wifi_payload("a;b", r#"p\:,"q"#, "WPA", true),
+            r#"WIFI:T:WPA;S:a\;b;P:p\\\:\,\"q;H:true;;"#
+        );
+        assert_eq!(
+            wifi_payload("home", "abcd1234", "SAE", false),
+            "WIFI:T:WPA;R:1;S:home;P:abcd1234;;"
+        );
+        assert_eq!(
+            wifi_payload("guest", "", "nopass", false),
+            "WIFI:T:nopass;S:guest;;"
+        );
+    }
+
+    #[test]
+    fn test_to_terminal() {
+        let qr = Qr::new("abcd1234").unwrap();
+        let modules = qr.modules(TERMINAL_QUIET_ZONE);
+        let size = qr.code.width() + 2 * TERMINAL_QUIET_ZONE;
+        assert_eq!(modules.len(), size);
+        let terminal = qr.to_terminal();
+        let lines: Vec<&str> = terminal.lines().collect();
+        assert_eq!(lines.len(), size.div_ceil(2));
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let top = modules[2 * y][x];
+                let bottom = modules.get(2 * y + 1).map_or(true, |row| row[x]);
+                assert_eq!(c == '█' || c == '▀', !top);
+                assert_eq!(c == '█' || c == '▄', !bottom);
+            }
+        }
+        // The quiet zone is light, and the finder pattern starts with a dark module.
+        assert!(lines[0].chars().all(|c| c == '█'));
+        assert!(modules[TERMINAL_QUIET_ZONE][TERMINAL_QUIET_ZONE]);
+    }
+
+    #[test]
+    fn test_to_png() {
+        let qr = Qr::new("WIFI:T:WPA;S:home;P:abcd1234;;").unwrap();
+        let png = qr.to_png();
+        let decoder = png::Decoder::new(png.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        let modules = qr.modules(QUIET_ZONE);
+        assert_eq!(info.width as usize, modules.len() * SCALE);
+        for (y, row) in modules.iter().enumerate() {
+            for (x, dark) in row.iter().enumerate() {
+                let pixel = pixels[(y * SCALE) * info.width as usize + x * SCALE];
+                assert_eq!(pixel == 0, *dark);
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let svg = Qr::new("abcd1234").unwrap().to_svg();
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains("<svg") && svg.ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_new() {
+        assert!(Qr::new(&"a".repeat(8000)).is_err());
+    }
+}
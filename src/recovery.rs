@@ -0,0 +1,234 @@
+use std::collections::HashSet;
+
+use pbkdf2::pbkdf2_hmac;
+use rand::Rng;
+use sha2::Sha256;
+
+use crate::format::Grouping;
+use crate::generator::character::Category;
+
+pub const MIN_COUNT: usize = 1;
+pub const MAX_COUNT: usize = 100;
+pub const DEFAULT_COUNT: usize = 10;
+pub const DEFAULT_GROUPS: usize = 2;
+pub const DEFAULT_GROUP_LENGTH: usize = 4;
+/// Characters of a code without the separators.
+pub const MIN_CHARACTERS: usize = 8;
+pub const MAX_CHARACTERS: usize = 64;
+pub const DEFAULT_USABLE: &str = "ln";
+/// Rounds of PBKDF2-HMAC-SHA256 of a hashed code.
+pub const HASH_ROUNDS: u32 = 600_000;
+/// Bytes of the random salt of a hashed code.
+const SALT_BYTES: usize = 16;
+
+/// Set of distinct codes of `groups` groups of easy characters, as `4k7p-m2xq`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecoveryCodes {
+    count: usize,
+    length: usize,
+    grouping: Grouping,
+    alphabet: Vec<char>,
+}
+
+impl RecoveryCodes {
+    /// Codes of the easy characters of the categories of `usable` flags.
+    pub fn new(
+        count: usize,
+        groups: usize,
+        group_length: usize,
+        usable: &str,
+        separator: &str,
+    ) -> Result<Self, String> {
+        Self::validate_count(count)?;
+        let length = groups.saturating_mul(group_length);
+        Self::validate_characters(length)?;
+        Category::validate_flag(usable).map_err(|e| format!("unknown usable flags: {}", e))?;
+        let categories = Category::from_cli(String::from(usable))
+            .map_err(|_| String::from("Needs 1 or more usable flags"))?;
+        let mut alphabet: Vec<char> = categories
+            .iter()
+            .flat_map(|c| c.char_set(true, ""))
+            .collect();
+        alphabet.sort_unstable();
+        let overlap: String = separator.chars().filter(|c| alphabet.contains(c)).collect();
+        if !overlap.is_empty() {
+            return Err(format!(
+                "separator must not contain the characters of the codes: {}",
+                overlap
+            ));
+        }
+        Ok(RecoveryCodes {
+            count,
+            length,
+            grouping: Grouping::new(group_length, separator)?,
+            alphabet,
+        })
+    }
+
+    pub fn validate_count(count: usize) -> Result<(), String> {
+        if (MIN_COUNT..=MAX_COUNT).contains(&count) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Needs {} to {} codes: {}",
+                MIN_COUNT, MAX_COUNT, count
+            ))
+        }
+    }
+
+    /// Validates the characters of a code, which are the groups times the group length.
+    pub fn validate_characters(characters: usize) -> Result<(), String> {
+        if (MIN_CHARACTERS..=MAX_CHARACTERS).contains(&characters) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Needs {} to {} characters in a code: {}",
+                MIN_CHARACTERS, MAX_CHARACTERS, characters
+            ))
+        }
+    }
+
+    /// Entropy of a code in bits, which the uniqueness within the set lowers negligibly.
+    pub fn entropy(&self) -> f64 {
+        self.length as f64 * (self.alphabet.len() as f64).log2()
+    }
+
+    pub fn generate(&self) -> Vec<String> {
+        self.sample(&mut rand::thread_rng())
+    }
+
+    /// Draws codes until `count` of them are distinct, keeping the order they are drawn in.
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<String> {
+        let mut seen = HashSet::with_capacity(self.count);
+        let mut codes = Vec::with_capacity(self.count);
+        while codes.len() < self.count {
+            let code: String = (0..self.length)
+                .map(|_| self.alphabet[rng.gen_range(0, self.alphabet.len())])
+                .collect();
+            if seen.insert(code.clone()) {
+                codes.push(self.grouping.format(&code));
+            }
+        }
+        codes
+    }
+
+    /// `salt$hash` in hex of PBKDF2-HMAC-SHA256 of `code` without the separators.
+    pub fn hash(&self, code: &str) -> String {
+        let mut salt = [0u8; SALT_BYTES];
+        rand::thread_rng().fill(&mut salt);
+        self.hash_with_salt(code, &salt, HASH_ROUNDS)
+    }
+
+    pub(crate) fn hash_with_salt(&self, code: &str, salt: &[u8], rounds: u32) -> String {
+        let code = code.replace(self.grouping.separator(), "");
+        let mut hash = [0u8; 32];
+        pbkdf2_hmac::<Sha256>(code.as_bytes(), salt, rounds, &mut hash);
+        let hex = |bytes: &[u8]| -> String { bytes.iter().map(|b| format!("{:02x}", b)).collect() };
+        format!("{}${}", hex(salt), hex(&hash))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let recovery = RecoveryCodes::new(10, 2, 4, DEFAULT_USABLE, "-").unwrap();
+        let codes = recovery.generate();
+        assert_eq!(codes.len(), 10);
+        for code in &codes {
+            assert_eq!(code.len(), 9);
+            assert_eq!(&code[4..5], "-");
+            assert!(code
+                .chars()
+                .all(|c| c == '-' || "abcdefghijkmnpqrstuvwxyz23456789".contains(c)));
+        }
+        assert_eq!(recovery.entropy(), 40.0);
+    }
+
+    /// Generator of 64-bit words which replays `words`.
+    struct Replay {
+        words: Vec<u64>,
+        next: usize,
+    }
+
+    impl rand::RngCore for Replay {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let word = self.words[self.next % self.words.len()];
+            self.next += 1;
+            word
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest.iter_mut() {
+                *byte = self.next_u64() as u8;
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_sample() {
+        // Words of k << 61 draw the k-th of the 8 easy numbers.
+        let mut words = vec![0; 16];
+        words.extend(vec![1 << 61; 8]);
+        let mut rng = Replay { words, next: 0 };
+        let recovery = RecoveryCodes::new(2, 2, 4, "n", "-").unwrap();
+        assert_eq!(recovery.sample(&mut rng), vec!["2222-2222", "3333-3333"]);
+        assert_eq!(rng.next, 24);
+    }
+
+    #[test]
+    fn test_hash() {
+        let recovery = RecoveryCodes::new(10, 2, 4, DEFAULT_USABLE, "-").unwrap();
+        // PBKDF2-HMAC-SHA256 of "abcdefgh" with the salt "salt" in 1000 rounds.
+        let hash = "73616c74$c37e8ca7a284c7be764cb922b11a1415cf9b916c7579096c58024b958636397c";
+        assert_eq!(recovery.hash_with_salt("abcd-efgh", b"salt", 1000), hash);
+        assert_eq!(recovery.hash_with_salt("abcdefgh", b"salt", 1000), hash);
+        assert_ne!(recovery.hash_with_salt("abcdefgh", b"pepper", 1000), hash);
+
+        let hash = recovery.hash("abcd-efgh");
+        assert_eq!(hash.len(), 2 * SALT_BYTES + 1 + 64);
+        assert_eq!(&hash[2 * SALT_BYTES..2 * SALT_BYTES + 1], "$");
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            RecoveryCodes::new(0, 2, 4, DEFAULT_USABLE, "-"),
+            Err(String::from("Needs 1 to 100 codes: 0"))
+        );
+        assert_eq!(
+            RecoveryCodes::new(10, 2, 3, DEFAULT_USABLE, "-"),
+            Err(String::from("Needs 8 to 64 characters in a code: 6"))
+        );
+        assert_eq!(
+            RecoveryCodes::new(10, 2, 4, "lx", "-"),
+            Err(String::from("unknown usable flags: x"))
+        );
+        assert_eq!(
+            RecoveryCodes::new(10, 2, 4, "", "-"),
+            Err(String::from("Needs 1 or more usable flags"))
+        );
+        assert_eq!(
+            RecoveryCodes::new(10, 2, 4, DEFAULT_USABLE, ""),
+            Err(String::from("separator must not be empty"))
+        );
+        assert_eq!(
+            RecoveryCodes::new(10, 2, 4, DEFAULT_USABLE, "a-z"),
+            Err(String::from(
+                "separator must not contain the characters of the codes: az"
+            ))
+        );
+        assert!(RecoveryCodes::new(10, 2, 4, "n", "A").is_ok());
+    }
+}
@@ -0,0 +1,485 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::config::Config;
+use crate::generator::policy::Policy;
+use crate::generator::Generator;
+
+const OPEN: &str = "{{";
+const CLOSE: &str = "}}";
+const KEYWORD: &str = "pswdgn";
+
+/// Placeholder of a generated password in a template, as `{{ pswdgn:NAME length=24 }}`.
+#[derive(Debug, PartialEq)]
+struct Marker {
+    name: Option<String>,
+    /// `None` when the marker has no options.
+    policy: Option<Box<Policy>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Part<'a> {
+    Text(&'a str),
+    Marker(Marker),
+}
+
+/// Replaces the markers in `template` with generated passwords, keeping those in `existing`.
+/// The policies of the markers take the custom categories and easy profiles of `config`.
+pub fn render(template: &str, existing: Option<&str>, config: &Config) -> Result<String, String> {
+    let lines = template
+        .split('\n')
+        .enumerate()
+        .map(|(i, l)| parse_line(l).map_err(|e| format!("line {}: {}", i + 1, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let at_line = |i: usize| move |e: String| format!("line {}: {}", i + 1, e);
+    // Every generator is built before any password is generated.
+    let mut named: HashMap<String, Generator> = HashMap::new();
+    for (name, (i, policy)) in named_policies(&lines)? {
+        named.insert(name, generator(policy, config).map_err(at_line(i))?);
+    }
+    let mut unnamed: HashMap<(usize, usize), Generator> = HashMap::new();
+    for (i, line) in lines.iter().enumerate() {
+        for (k, part) in line.iter().enumerate() {
+            if let Part::Marker(Marker { name: None, policy }) = part {
+                let policy = policy.as_deref().cloned().unwrap_or_default();
+                unnamed.insert((i, k), generator(policy, config).map_err(at_line(i))?);
+            }
+        }
+    }
+    let generator_of = |i: usize, k: usize, m: &Marker| -> &Generator {
+        match &m.name {
+            Some(n) => &named[n],
+            None => &unnamed[&(i, k)],
+        }
+    };
+
+    let mut kept: HashMap<(usize, usize), String> = HashMap::new();
+    let mut values: HashMap<String, String> = HashMap::new();
+    if let Some(existing) = existing {
+        let mut candidates: Vec<&str> = existing.split('\n').collect();
+        for (i, line) in lines.iter().enumerate() {
+            if !line.iter().any(|p| matches!(p, Part::Marker(_))) {
+                continue;
+            }
+            let found = candidates
+                .iter()
+                .enumerate()
+                .find_map(|(j, c)| match_line(line, c).map(|v| (j, v)));
+            if let Some((j, found)) = found {
+                candidates.remove(j);
+                let markers = line.iter().enumerate().filter_map(|(k, p)| match p {
+                    Part::Marker(m) => Some((k, m)),
+                    Part::Text(_) => None,
+                });
+                for ((k, marker), value) in markers.zip(found) {
+                    if value.is_empty() || generator_of(i, k, marker).check(value).is_err() {
+                        continue;
+                    }
+                    match &marker.name {
+                        Some(n) => {
+                            values.entry(n.clone()).or_insert_with(|| value.to_string());
+                        }
+                        None => {
+                            kept.insert((i, k), value.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut rendered = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        let mut s = String::new();
+        for (k, part) in line.iter().enumerate() {
+            match part {
+                Part::Text(t) => s.push_str(t),
+                Part::Marker(m) => {
+                    let value = match (&m.name, kept.remove(&(i, k))) {
+                        (_, Some(v)) => v,
+                        (Some(n), None) => values
+                            .entry(n.clone())
+                            .or_insert_with(|| named[n].generate())
+                            .clone(),
+                        (None, None) => generator_of(i, k, m).generate(),
+                    };
+                    s.push_str(&value);
+                }
+            }
+        }
+        rendered.push(s);
+    }
+    Ok(rendered.join("\n"))
+}
+
+/// Writes `contents` to `path` readable and writable only by the owner.
+pub fn write_private<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let file = options.open(&path)?;
+        // The mode is applied only when the file is created.
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+        write_all(file, contents.as_ref())
+    }
+    #[cfg(not(unix))]
+    write_all(options.open(&path)?, contents.as_ref())
+}
+
+/// Whether the standard output is a regular file which others can read.
+pub fn stdout_is_shared_file() -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        match fs::metadata("/dev/stdout") {
+            Ok(m) => m.is_file() && m.permissions().mode() & 0o077 != 0,
+            Err(_) => false,
+        }
+    }
+    #[cfg(not(unix))]
+    false
+}
+
+fn write_all(mut file: fs::File, contents: &[u8]) -> io::Result<()> {
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// The generator of `policy` with the custom categories and easy profiles of `config`.
+fn generator(mut policy: Policy, config: &Config) -> Result<Generator, String> {
+    config.resolve(&mut policy);
+    policy.generator()
+}
+
+fn parse_line(line: &str) -> Result<Vec<Part<'_>>, String> {
+    let mut parts = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find(OPEN) {
+        let end = match rest[start..].find(CLOSE) {
+            Some(e) => start + e,
+            None => break,
+        };
+        match parse_marker(&rest[start + OPEN.len()..end])? {
+            Some(marker) => {
+                parts.push(Part::Text(&rest[..start]));
+                parts.push(Part::Marker(marker));
+            }
+            None => parts.push(Part::Text(&rest[..end + CLOSE.len()])),
+        }
+        rest = &rest[end + CLOSE.len()..];
+    }
+    parts.push(Part::Text(rest));
+    parts.retain(|p| p != &Part::Text(""));
+    Ok(parts)
+}
+
+/// Returns `None` when `content` is not a marker of pswdgn.
+fn parse_marker(content: &str) -> Result<Option<Marker>, String> {
+    let mut words = content.split_whitespace();
+    let head = match words.next() {
+        Some(h) => h,
+        None => return Ok(None),
+    };
+    let name = match head.strip_prefix(KEYWORD) {
+        Some("") => None,
+        Some(n) => match n.strip_prefix(':') {
+            Some("") => return Err(String::from("marker name is empty")),
+            Some(n) => Some(String::from(n)),
+            None => return Ok(None),
+        },
+        None => return Ok(None),
+    };
+
+    let mut policy: Option<Box<Policy>> = None;
+    for word in words {
+        let p = policy.get_or_insert_with(Box::default);
+        let (key, value) = match word.find('=') {
+            Some(i) => (&word[..i], &word[i + 1..]),
+            None => return Err(format!("option needs a value: {}", word)),
+        };
+        p.set_option(key, value)?;
+    }
+    Ok(Some(Marker { name, policy }))
+}
+
+/// Options of named markers, which are given at most once or identically, with the index of
+/// the line which gives them or else of the first line of the marker.
+fn named_policies(lines: &[Vec<Part>]) -> Result<HashMap<String, (usize, Policy)>, String> {
+    let mut named: HashMap<String, (usize, Option<Policy>)> = HashMap::new();
+    for (i, line) in lines.iter().enumerate() {
+        for part in line {
+            let (name, policy) = match part {
+                Part::Marker(Marker {
+                    name: Some(n),
+                    policy,
+                }) => (n, policy),
+                _ => continue,
+            };
+            let entry = named.entry(name.clone()).or_insert((i, None));
+            match (entry.1.as_ref(), policy.as_deref()) {
+                (Some(a), Some(b)) if a != b => {
+                    return Err(format!(
+                        "line {}: conflicting options of marker: {}",
+                        i + 1,
+                        name
+                    ))
+                }
+                (None, Some(b)) => *entry = (i, Some(b.clone())),
+                _ => (),
+            }
+        }
+    }
+    Ok(named
+        .into_iter()
+        .map(|(n, (i, p))| (n, (i, p.unwrap_or_default())))
+        .collect())
+}
+
+/// Values of the markers in `line` when `rendered` is a result of it.
+fn match_line<'a>(line: &[Part], rendered: &'a str) -> Option<Vec<&'a str>> {
+    let mut values = Vec::new();
+    let mut rest = rendered;
+    let mut parts = line.iter().peekable();
+    while let Some(part) = parts.next() {
+        match part {
+            Part::Text(t) => rest = rest.strip_prefix(t)?,
+            Part::Marker(_) => match parts.peek() {
+                None => {
+                    values.push(rest);
+                    rest = "";
+                }
+                Some(Part::Text(t)) => {
+                    // The last text must match the end of the line.
+                    let i = if parts.len() == 1 {
+                        rest.len().checked_sub(t.len())?
+                    } else {
+                        rest.find(t)?
+                    };
+                    values.push(&rest[..i]);
+                    rest = &rest[i..];
+                }
+                // Adjacent markers cannot be told apart.
+                Some(Part::Marker(_)) => return None,
+            },
+        }
+    }
+    if rest.is_empty() {
+        Some(values)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn policy(length: usize, usable: &str) -> Policy {
+        Policy {
+            length,
+            usable: String::from(usable),
+            ..Policy::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_marker() {
+        assert_eq!(
+            parse_marker(" pswdgn "),
+            Ok(Some(Marker {
+                name: None,
+                policy: None
+            }))
+        );
+        assert_eq!(
+            parse_marker(" pswdgn:db_pass length=24 usable=lun easy=true symbols=!@ "),
+            Ok(Some(Marker {
+                name: Some(String::from("db_pass")),
+                policy: Some(Box::new(Policy {
+                    length: 24,
+                    usable: String::from("lun"),
+                    is_easy: true,
+                    symbols: String::from("!@"),
+                    ..Policy::default()
+                })),
+            }))
+        );
+        assert_eq!(parse_marker(" name "), Ok(None));
+        assert_eq!(parse_marker(" pswdgnx "), Ok(None));
+        assert_eq!(parse_marker(""), Ok(None));
+        assert_eq!(
+            parse_marker(" pswdgn: "),
+            Err(String::from("marker name is empty"))
+        );
+        assert_eq!(
+            parse_marker(" pswdgn length "),
+            Err(String::from("option needs a value: length"))
+        );
+        assert_eq!(
+            parse_marker(" pswdgn size=8 "),
+            Err(String::from("unknown option: size"))
+        );
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("A={{ pswdgn:a }} {{ other }}{{pswdgn}}"),
+            Ok(vec![
+                Part::Text("A="),
+                Part::Marker(Marker {
+                    name: Some(String::from("a")),
+                    policy: None
+                }),
+                Part::Text(" {{ other }}"),
+                Part::Marker(Marker {
+                    name: None,
+                    policy: None
+                }),
+            ])
+        );
+        assert_eq!(parse_line("{{ pswdgn"), Ok(vec![Part::Text("{{ pswdgn")]));
+    }
+
+    #[test]
+    fn test_match_line() {
+        let line = parse_line("url=db://{{ pswdgn }}@{{ pswdgn }}/x").unwrap();
+        assert_eq!(
+            match_line(&line, "url=db://ab@cd@ef/x"),
+            Some(vec!["ab", "cd@ef"])
+        );
+        assert_eq!(match_line(&line, "url=db://ab@cd/y"), None);
+        assert_eq!(match_line(&line, "uri=db://ab@cd/x"), None);
+
+        let line = parse_line("A={{ pswdgn }}").unwrap();
+        assert_eq!(match_line(&line, "A=secret"), Some(vec!["secret"]));
+
+        let line = parse_line("{{ pswdgn }}{{ pswdgn }}").unwrap();
+        assert_eq!(match_line(&line, "ab"), None);
+    }
+
+    #[test]
+    fn test_render() {
+        let template = "\
+# database
+DB_PASS={{ pswdgn:db length=24 usable=lun }}
+DB_URL=postgres://app:{{ pswdgn:db }}@localhost/app
+TOKEN={{ pswdgn length=16 usable=n }}
+OTHER={{ other }}";
+        let rendered = render(template, None, &Config::default()).unwrap();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "# database");
+        let db = lines[1].strip_prefix("DB_PASS=").unwrap();
+        assert_eq!(policy(24, "lun").check(db), Ok(()));
+        assert_eq!(
+            lines[2],
+            format!("DB_URL=postgres://app:{}@localhost/app", db)
+        );
+        let token = lines[3].strip_prefix("TOKEN=").unwrap();
+        assert_eq!(policy(16, "n").check(token), Ok(()));
+        assert_eq!(lines[4], "OTHER={{ other }}");
+
+        assert_ne!(
+            render(template, None, &Config::default()).unwrap(),
+            rendered
+        );
+    }
+
+    #[test]
+    fn test_render_keep() {
+        let template = "\
+A={{ pswdgn:a length=10 usable=l }}
+B={{ pswdgn length=10 usable=n }}
+C={{ pswdgn length=12 usable=n }}
+D={{ pswdgn:a }}";
+        let existing = "\
+B=0123456789
+A=abcdefghij
+C=0123456789
+";
+        let rendered = render(template, Some(existing), &Config::default()).unwrap();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "A=abcdefghij");
+        assert_eq!(lines[1], "B=0123456789");
+        // Too short for the marker.
+        assert_ne!(lines[2], "C=0123456789");
+        assert_eq!(policy(12, "n").check(&lines[2][2..]), Ok(()));
+        assert_eq!(lines[3], "D=abcdefghij");
+    }
+
+    #[test]
+    fn test_render_config() {
+        let config = Config::parse(
+            r#"{"categories": [{"name": "greek", "flag": "g", "characters": "αβγ"}]}"#,
+        )
+        .unwrap();
+        let rendered = render("A={{ pswdgn length=8 usable=g }}", None, &config).unwrap();
+        assert!(rendered[2..].chars().all(|c| "αβγ".contains(c)));
+    }
+
+    #[test]
+    fn test_render_errors() {
+        let config = Config::default();
+        assert_eq!(
+            render("A={{ pswdgn length=7 }}", None, &config),
+            Err(String::from("line 1: Needs 8 or more: 7"))
+        );
+        assert_eq!(
+            render("\nA={{ pswdgn length=x }}", None, &config),
+            Err(String::from("line 2: Not number value: x"))
+        );
+        assert_eq!(
+            render(
+                "A={{ pswdgn:a length=10 }}\nB={{ pswdgn:a length=12 }}",
+                None,
+                &config
+            ),
+            Err(String::from("line 2: conflicting options of marker: a"))
+        );
+        // Policies which pass validation but have no generator.
+        assert_eq!(
+            render(
+                "\n\nA={{ pswdgn usable=n exclude=0123456789 }}",
+                None,
+                &config
+            ),
+            Err(String::from(
+                "line 3: all characters are excluded from usable flags: n"
+            ))
+        );
+        assert_eq!(
+            render(
+                "A={{ pswdgn:a }}\nB={{ pswdgn:a usable=ln require=abcdefgh }}",
+                None,
+                &config
+            ),
+            Err(String::from(
+                "line 2: No password satisfies the constraints"
+            ))
+        );
+        // The category is defined only in the config file.
+        assert!(render("A={{ pswdgn usable=g }}", None, &config).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("pswdgn-render-{}", std::process::id()));
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        write_private(&path, "new").unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(contents, "new");
+    }
+}
@@ -0,0 +1,257 @@
+use std::io::{self, BufRead, Write};
+
+use serde_json::{json, Map, Value};
+
+use crate::config::Config;
+use crate::service::{self, Operation};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Serves line-delimited JSON-RPC 2.0 until `input` is closed.
+///
+/// Methods take named parameters:
+///
+/// * `generate` : `Policy` → `{"password": ...}`
+/// * `batch` : `{"count": ..., "policy": Policy}` → `{"passwords": [...]}`
+/// * `check` : `{"password": ..., "policy": Policy}` → `{"valid": ..., "error": ...}`
+/// * `entropy` : `Policy` → `{"entropy": ..., "uniform_entropy": ...}`
+/// * `validate-policy` : `Policy` → `{"policy": Policy, "entropy": ..., "uniform_entropy": ...}`
+/// * `list-categories` : → `[{"flag": ..., ...}]`
+///
+/// Policies take the custom categories and easy profiles of `config`.
+/// Invalid options are reported as `-32602` errors with the same message as the command line.
+pub fn serve<R: BufRead, W: Write>(input: R, mut output: W, config: &Config) -> io::Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle(&line, config) {
+            writeln!(output, "{}", response)?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Handles a request or a batch of requests, and returns nothing for notifications.
+pub fn handle(line: &str, config: &Config) -> Option<Value> {
+    match serde_json::from_str::<Value>(line) {
+        Err(e) => Some(error(Value::Null, PARSE_ERROR, e.to_string())),
+        Ok(Value::Array(requests)) if requests.is_empty() => Some(error(
+            Value::Null,
+            INVALID_REQUEST,
+            String::from("Empty batch"),
+        )),
+        Ok(Value::Array(requests)) => {
+            let responses: Vec<Value> = requests
+                .into_iter()
+                .filter_map(|r| call(r, config))
+                .collect();
+            if responses.is_empty() {
+                None
+            } else {
+                Some(Value::from(responses))
+            }
+        }
+        Ok(request) => call(request, config),
+    }
+}
+
+fn call(request: Value, config: &Config) -> Option<Value> {
+    let mut request = match request {
+        Value::Object(r) => r,
+        _ => {
+            return Some(error(
+                Value::Null,
+                INVALID_REQUEST,
+                String::from("Not object"),
+            ))
+        }
+    };
+    let id = request.remove("id");
+    let response = match parse(&mut request) {
+        // Invalid requests are not notifications, and are answered with null id.
+        Err((INVALID_REQUEST, message)) => {
+            return Some(error(id.unwrap_or(Value::Null), INVALID_REQUEST, message))
+        }
+        Err((code, message)) => error(id.clone().unwrap_or(Value::Null), code, message),
+        Ok((method, params)) => match method(params, config) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => error(id.clone().unwrap_or(Value::Null), INVALID_PARAMS, e),
+        },
+    };
+    // Requests without id are notifications.
+    id.map(|_| response)
+}
+
+fn parse(request: &mut Map<String, Value>) -> Result<(Operation, Value), (i64, String)> {
+    if request.get("jsonrpc") != Some(&json!("2.0")) {
+        return Err((INVALID_REQUEST, String::from("Needs \"jsonrpc\": \"2.0\"")));
+    }
+    let method: Operation = match request.get("method") {
+        Some(Value::String(m)) => match m.as_str() {
+            "generate" => service::generate,
+            "batch" => service::batch,
+            "check" => service::check,
+            "entropy" => service::entropy,
+            "validate-policy" => service::validate_policy,
+            "list-categories" => service::list_categories,
+            _ => return Err((METHOD_NOT_FOUND, format!("Method not found: {}", m))),
+        },
+        _ => return Err((INVALID_REQUEST, String::from("Needs method name"))),
+    };
+    match request.remove("params") {
+        None => Ok((method, Value::Null)),
+        Some(params @ Value::Object(_)) => Ok((method, params)),
+        Some(_) => Err((INVALID_PARAMS, String::from("Needs named parameters"))),
+    }
+}
+
+fn error(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_serve() {
+        let input = concat!(
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "generate", "params": {"length": 12}}"#,
+            "\n\n",
+            r#"{"jsonrpc": "2.0", "method": "generate"}"#,
+            "\n",
+            r#"{"jsonrpc": "2.0", "id": "b", "method": "list-categories"}"#,
+            "\n",
+        );
+        let mut output = Vec::new();
+        serve(input.as_bytes(), &mut output, &Config::default()).unwrap();
+        let responses: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], json!(1));
+        assert_eq!(
+            responses[0]["result"]["password"].as_str().unwrap().len(),
+            12
+        );
+        assert_eq!(responses[1]["id"], json!("b"));
+        assert_eq!(responses[1]["result"][0]["flag"], json!("l"));
+    }
+
+    #[test]
+    fn test_handle_validations() {
+        assert_eq!(
+            handle(
+                r#"{"jsonrpc": "2.0", "id": 1, "method": "generate", "params": {"length": 256}}"#,
+                &Config::default()
+            ),
+            Some(error(
+                json!(1),
+                INVALID_PARAMS,
+                String::from("Needs 255 or less: 256")
+            ))
+        );
+        assert_eq!(
+            handle(
+                r#"{"jsonrpc": "2.0", "id": 2, "method": "validate-policy", "params": {"usable": "lx"}}"#,
+                &Config::default()
+            ),
+            Some(error(
+                json!(2),
+                INVALID_PARAMS,
+                String::from("unknown usable flags: x")
+            ))
+        );
+        assert_eq!(
+            handle(
+                r#"{"jsonrpc": "2.0", "id": 3, "method": "check", "params": {"password": "abcdefgh", "policy": {"symbols": "!a"}}}"#,
+                &Config::default()
+            ),
+            Some(error(
+                json!(3),
+                INVALID_PARAMS,
+                String::from("unknown symbol character: a")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_handle_errors() {
+        let response = handle("{", &Config::default()).unwrap();
+        assert_eq!(response["id"], Value::Null);
+        assert_eq!(response["error"]["code"], json!(PARSE_ERROR));
+
+        let response = handle(r#"{"id": 1, "method": "generate"}"#, &Config::default()).unwrap();
+        assert_eq!(response["error"]["code"], json!(INVALID_REQUEST));
+
+        // Invalid requests without id are not notifications.
+        assert_eq!(
+            handle(r#"{"method": "generate"}"#, &Config::default()),
+            Some(error(
+                Value::Null,
+                INVALID_REQUEST,
+                String::from("Needs \"jsonrpc\": \"2.0\"")
+            ))
+        );
+        assert_eq!(
+            handle(
+                r#"{"jsonrpc": "2.0", "method": "unknown"}"#,
+                &Config::default()
+            ),
+            None
+        );
+
+        let response = handle(
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "unknown"}"#,
+            &Config::default(),
+        )
+        .unwrap();
+        assert_eq!(response["error"]["code"], json!(METHOD_NOT_FOUND));
+
+        let response = handle(
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "generate", "params": [8]}"#,
+            &Config::default(),
+        )
+        .unwrap();
+        assert_eq!(response["error"]["code"], json!(INVALID_PARAMS));
+
+        let response = handle("[]", &Config::default()).unwrap();
+        assert_eq!(response["error"]["code"], json!(INVALID_REQUEST));
+    }
+
+    #[test]
+    fn test_handle_batch() {
+        let response = handle(concat!(
+            r#"[{"jsonrpc": "2.0", "id": 1, "method": "entropy", "params": {"usable": "n"}},"#,
+            r#"{"jsonrpc": "2.0", "method": "generate"},"#,
+            r#"{"jsonrpc": "2.0", "id": 2, "method": "check", "params": {"password": "12345678", "policy": {"usable": "n"}}}]"#,
+        ), &Config::default())
+        .unwrap();
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 2);
+        assert!(
+            (responses[0]["result"]["entropy"].as_f64().unwrap() - 8.0 * 10f64.log2()).abs() < 1e-9
+        );
+        assert_eq!(responses[1]["result"], json!({"valid": true}));
+
+        assert_eq!(
+            handle(
+                r#"[{"jsonrpc": "2.0", "method": "generate"}]"#,
+                &Config::default()
+            ),
+            None
+        );
+    }
+}
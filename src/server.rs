@@ -0,0 +1,220 @@
+use std::io::Read;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::service::{self, Operation};
+
+/// Default limit of the size of request bodies in bytes.
+pub const DEFAULT_MAX_BODY: usize = 16 * 1024;
+/// Default number of the threads which handle requests.
+pub const DEFAULT_WORKERS: usize = 4;
+
+/// Address the service listens on.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Listen {
+    Tcp(String),
+    Unix(PathBuf),
+}
+
+impl FromStr for Listen {
+    type Err = String;
+
+    /// `unix:PATH` is a Unix domain socket, and others are TCP addresses.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("unix:") {
+            Some("") => Err(String::from("Socket path is empty")),
+            Some(path) => Ok(Listen::Unix(PathBuf::from(path))),
+            None => match s.parse::<SocketAddr>() {
+                Ok(_) => Ok(Listen::Tcp(String::from(s))),
+                Err(_) => Err(format!("Not socket address: {}", s)),
+            },
+        }
+    }
+}
+
+pub struct Config {
+    pub listen: Listen,
+    /// Bearer token required by every request.
+    pub token: Option<String>,
+    pub max_body: usize,
+    /// Number of the threads which handle requests.
+    pub workers: usize,
+}
+
+/// JSON service over HTTP.
+///
+/// Every endpoint takes a `POST` request:
+///
+/// * `/generate` : `Policy` → `{"password": ...}`
+/// * `/batch` : `{"count": ..., "policy": Policy}` → `{"passwords": [...]}`
+/// * `/passphrase` : `{"words": ..., "separator": ..., "policy": Policy, "kana": ...}` →
+///   `{"passphrase": ...}`
+/// * `/check` : `{"password": ..., "policy": Policy}` → `{"valid": ..., "error": ...}`
+/// * `/entropy` : `Policy` → `{"entropy": ..., "uniform_entropy": ...}`
+pub struct Server {
+    http: tiny_http::Server,
+    token: Option<String>,
+    max_body: usize,
+    workers: usize,
+    settings: crate::config::Config,
+}
+
+impl Server {
+    pub fn bind(config: Config) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let http = match &config.listen {
+            Listen::Tcp(addr) => tiny_http::Server::http(addr)?,
+            #[cfg(unix)]
+            Listen::Unix(path) => tiny_http::Server::http_unix(path)?,
+            #[cfg(not(unix))]
+            Listen::Unix(_) => return Err("Unix domain socket is not supported".into()),
+        };
+        Ok(Server {
+            http,
+            token: config.token,
+            max_body: config.max_body,
+            workers: config.workers.max(1),
+            settings: crate::config::Config::default(),
+        })
+    }
+
+    /// Applies the custom categories and easy profiles of the config file to every policy.
+    pub fn with_settings(mut self, settings: crate::config::Config) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// TCP address the service listens on.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// Handles requests on the worker threads until `unblock` is called, so that a slow
+    /// request does not hold up the others.
+    pub fn run(&self) {
+        std::thread::scope(|scope| {
+            for _ in 0..self.workers {
+                scope.spawn(|| {
+                    for request in self.http.incoming_requests() {
+                        self.handle(request);
+                    }
+                });
+            }
+        });
+    }
+
+    pub fn unblock(&self) {
+        // Each call stops one worker.
+        for _ in 0..self.workers {
+            self.http.unblock();
+        }
+    }
+
+    fn handle(&self, mut request: Request) {
+        let (status, body) = match self.route(&mut request) {
+            Ok(body) => (200, body),
+            Err((status, message)) => (status, json!({ "error": message })),
+        };
+        let mut response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(header("Content-Type", "application/json"))
+            .with_header(header("Cache-Control", "no-store"));
+        if status == 401 {
+            response.add_header(header("WWW-Authenticate", "Bearer"));
+        }
+        let _ = request.respond(response);
+    }
+
+    fn route(&self, request: &mut Request) -> Result<Value, (u16, String)> {
+        if !self.authorized(request) {
+            return Err((401, String::from("Unauthorized")));
+        }
+        let endpoint: Operation = match request.url() {
+            "/generate" => service::generate,
+            "/batch" => service::batch,
+            "/passphrase" => service::passphrase,
+            "/check" => service::check,
+            "/entropy" => service::entropy,
+            url => return Err((404, format!("Not found: {}", url))),
+        };
+        if *request.method() != Method::Post {
+            return Err((405, format!("Method not allowed: {}", request.method())));
+        }
+        let body = self.read_body(request)?;
+        // Empty body is an empty JSON object.
+        let params = if body.trim().is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_str(&body).map_err(|e| (400, e.to_string()))?
+        };
+        endpoint(params, &self.settings).map_err(|e| (400, e))
+    }
+
+    fn authorized(&self, request: &Request) -> bool {
+        let token = match &self.token {
+            Some(t) => t,
+            None => return true,
+        };
+        request
+            .headers()
+            .iter()
+            .filter(|h| h.field.equiv("Authorization"))
+            .filter_map(|h| h.value.as_str().strip_prefix("Bearer "))
+            .any(|t| constant_time_eq(t.as_bytes(), token.as_bytes()))
+    }
+
+    fn read_body(&self, request: &mut Request) -> Result<String, (u16, String)> {
+        let too_large = || (413, format!("Needs {} bytes or less", self.max_body));
+        if request.body_length().is_some_and(|l| l > self.max_body) {
+            return Err(too_large());
+        }
+        let mut body = String::new();
+        request
+            .as_reader()
+            .take(self.max_body as u64 + 1)
+            .read_to_string(&mut body)
+            .map_err(|e| (400, e.to_string()))?;
+        if body.len() > self.max_body {
+            Err(too_large())
+        } else {
+            Ok(body)
+        }
+    }
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field.as_bytes(), value.as_bytes()).unwrap()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_listen_from_str() {
+        assert_eq!(
+            "127.0.0.1:8080".parse(),
+            Ok(Listen::Tcp(String::from("127.0.0.1:8080")))
+        );
+        assert_eq!(
+            "unix:/tmp/pswdgn.sock".parse(),
+            Ok(Listen::Unix(PathBuf::from("/tmp/pswdgn.sock")))
+        );
+        assert!("unix:".parse::<Listen>().is_err());
+        assert!("localhost".parse::<Listen>().is_err());
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret!"));
+    }
+}
@@ -0,0 +1,337 @@
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::config::Config;
+use crate::generator::character::{with_presets, CharSets, ALL_CHARACTERS};
+use crate::generator::policy::Policy;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::kana::{self, Passphrase};
+
+/// Maximum number of passwords generated by one batch request.
+pub const MAX_BATCH: usize = 1000;
+/// Default number of words of passphrases.
+#[cfg(not(target_arch = "wasm32"))]
+pub const DEFAULT_WORDS: usize = 4;
+/// Default separator of the words of passphrases.
+#[cfg(not(target_arch = "wasm32"))]
+pub const DEFAULT_SEPARATOR: &str = "-";
+
+/// JSON operations shared by the HTTP service and JSON-RPC, with the config file.
+pub(crate) type Operation = fn(Value, &Config) -> Result<Value, String>;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchParams {
+    count: usize,
+    #[serde(default)]
+    policy: Policy,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PassphraseParams {
+    words: usize,
+    separator: String,
+    policy: Policy,
+    kana: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for PassphraseParams {
+    /// Words of 8 easy lower case letters.
+    fn default() -> Self {
+        PassphraseParams {
+            words: DEFAULT_WORDS,
+            separator: String::from(DEFAULT_SEPARATOR),
+            policy: Policy {
+                usable: String::from("l"),
+                is_easy: true,
+                ..Policy::default()
+            },
+            kana: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CheckParams {
+    password: String,
+    #[serde(default)]
+    policy: Policy,
+}
+
+/// `null` is an empty JSON object.
+fn parse<T: DeserializeOwned>(params: Value) -> Result<T, String> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| e.to_string())
+}
+
+/// `policy` with the custom categories and easy profiles of `config`, as on the command line.
+fn resolved(mut policy: Policy, config: &Config) -> Policy {
+    config.resolve(&mut policy);
+    policy
+}
+
+/// `Policy` → `{"password": ...}`
+pub(crate) fn generate(params: Value, config: &Config) -> Result<Value, String> {
+    let generator = resolved(parse(params)?, config).generator()?;
+    Ok(json!({ "password": generator.generate() }))
+}
+
+/// `{"count": ..., "policy": Policy}` → `{"passwords": [...]}`
+pub(crate) fn batch(params: Value, config: &Config) -> Result<Value, String> {
+    let params: BatchParams = parse(params)?;
+    if params.count == 0 || MAX_BATCH < params.count {
+        return Err(format!(
+            "Needs 1 to {} passwords: {}",
+            MAX_BATCH, params.count
+        ));
+    }
+    let generator = resolved(params.policy, config).generator()?;
+    let passwords: Vec<String> = (0..params.count).map(|_| generator.generate()).collect();
+    Ok(json!({ "passwords": passwords }))
+}
+
+/// `{"words": ..., "separator": ..., "policy": Policy}` → `{"passphrase": ..., "entropy": ...}`
+/// of words generated by the policy, or of hiragana words with their romaji if `kana`.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn passphrase(params: Value, config: &Config) -> Result<Value, String> {
+    let params: PassphraseParams = parse(params)?;
+    if params.kana {
+        let passphrase = Passphrase::new(params.words, &params.separator)?;
+        let generated = passphrase.generate();
+        return Ok(json!({
+            "passphrase": generated,
+            "romaji": kana::romaji(&generated),
+            "entropy": passphrase.entropy(),
+        }));
+    }
+    if params.words == 0 || MAX_BATCH < params.words {
+        return Err(format!("Needs 1 to {} words: {}", MAX_BATCH, params.words));
+    }
+    let generator = resolved(params.policy, config).generator()?;
+    let words: Vec<String> = (0..params.words).map(|_| generator.generate()).collect();
+    Ok(json!({
+        "passphrase": words.join(&params.separator),
+        "entropy": params.words as f64 * generator.entropy(),
+    }))
+}
+
+/// `{"password": ..., "policy": Policy}` → `{"valid": ..., "error": ...}`
+pub(crate) fn check(params: Value, config: &Config) -> Result<Value, String> {
+    let params: CheckParams = parse(params)?;
+    let policy = resolved(params.policy, config);
+    policy.validate()?;
+    Ok(match policy.check(&params.password) {
+        Ok(_) => json!({ "valid": true }),
+        Err(e) => json!({ "valid": false, "error": e }),
+    })
+}
+
+/// `Policy` → `{"entropy": ..., "uniform_entropy": ...}`
+pub(crate) fn entropy(params: Value, config: &Config) -> Result<Value, String> {
+    let generator = resolved(parse(params)?, config).generator()?;
+    let uniform = generator.uniform_entropy().map_err(|e| e.to_string())?;
+    Ok(json!({ "entropy": generator.entropy(), "uniform_entropy": uniform }))
+}
+
+/// `Policy` → `{"policy": Policy, "entropy": ..., "uniform_entropy": ...}` with the defaults
+/// filled in.
+pub(crate) fn validate_policy(params: Value, config: &Config) -> Result<Value, String> {
+    let policy = resolved(parse(params)?, config);
+    let generator = policy.generator()?;
+    let uniform = generator.uniform_entropy().map_err(|e| e.to_string())?;
+    Ok(json!({
+        "policy": policy,
+        "entropy": generator.entropy(),
+        "uniform_entropy": uniform,
+    }))
+}
+
+/// → `[{"flag": ..., "description": ..., "characters": ..., "easy_characters": ...}]` of the
+/// built-in categories, followed by the custom categories of `config` and the kana presets.
+/// Easy characters leave out those which look the same as the characters of any category.
+pub(crate) fn list_categories(_: Value, config: &Config) -> Result<Value, String> {
+    let custom = with_presets(&config.categories);
+    let builtin = ALL_CHARACTERS.iter().map(|c| {
+        (
+            c.flag,
+            String::from(c.description),
+            String::from(c.chars_all),
+        )
+    });
+    let categories: Vec<(char, String, String)> = builtin
+        .chain(
+            custom
+                .iter()
+                .map(|c| (c.flag, c.name.clone(), c.characters.nfc().collect())),
+        )
+        .collect();
+    let flags: String = categories.iter().map(|(flag, _, _)| flag).collect();
+    let char_set = |is_easy| {
+        CharSets::from_custom(&flags, is_easy, String::default(), &custom)
+            .map(|c| c.characters())
+            .map_err(|e| e.to_string())
+    };
+    let (all, easy) = (char_set(false)?, char_set(true)?);
+    let listed: Vec<Value> = categories
+        .iter()
+        .map(|(flag, description, characters)| {
+            json!({
+                "flag": flag.to_string(),
+                "description": description,
+                "characters": characters.chars().filter(|c| all.contains(c)).collect::<String>(),
+                "easy_characters": characters.chars().filter(|c| easy.contains(c)).collect::<String>(),
+            })
+        })
+        .collect();
+    Ok(Value::from(listed))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let value = generate(json!({"length": 12, "usable": "n"}), &Config::default()).unwrap();
+        let password = value["password"].as_str().unwrap();
+        assert_eq!(password.len(), 12);
+        assert!(password.chars().all(|c| c.is_ascii_digit()));
+
+        assert!(generate(Value::Null, &Config::default()).is_ok());
+        assert_eq!(
+            generate(json!({"length": 7}), &Config::default()),
+            Err(String::from("Needs 8 or more: 7"))
+        );
+    }
+
+    #[test]
+    fn test_batch() {
+        let value = batch(
+            json!({"count": 3, "policy": {"length": 10}}),
+            &Config::default(),
+        )
+        .unwrap();
+        let passwords = value["passwords"].as_array().unwrap();
+        assert_eq!(passwords.len(), 3);
+        assert!(passwords.iter().all(|p| p.as_str().unwrap().len() == 10));
+
+        assert_eq!(
+            batch(json!({"count": 0}), &Config::default()),
+            Err(String::from("Needs 1 to 1000 passwords: 0"))
+        );
+    }
+
+    #[test]
+    fn test_passphrase() {
+        let value = passphrase(
+            json!({"words": 3, "separator": " ", "policy": {"length": 10}}),
+            &Config::default(),
+        )
+        .unwrap();
+        let words: Vec<&str> = value["passphrase"].as_str().unwrap().split(' ').collect();
+        assert_eq!(words.len(), 3);
+        assert!(words.iter().all(|w| w.len() == 10));
+
+        let value = passphrase(Value::Null, &Config::default()).unwrap();
+        let words: Vec<&str> = value["passphrase"].as_str().unwrap().split('-').collect();
+        assert_eq!(words.len(), DEFAULT_WORDS);
+        assert!(words
+            .iter()
+            .all(|w| w.len() == 8 && w.chars().all(|c| c.is_ascii_lowercase())));
+
+        assert_eq!(
+            passphrase(json!({"words": 0}), &Config::default()),
+            Err(String::from("Needs 1 to 1000 words: 0"))
+        );
+
+        let value = passphrase(
+            json!({"kana": true, "words": 5, "separator": " "}),
+            &Config::default(),
+        )
+        .unwrap();
+        let generated = value["passphrase"].as_str().unwrap();
+        assert_eq!(generated.split(' ').count(), 5);
+        assert_eq!(value["romaji"], json!(kana::romaji(generated)));
+        assert_eq!(
+            passphrase(json!({"kana": true, "words": 3}), &Config::default()),
+            Err(String::from("Needs 4 or more words: 3"))
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(
+            check(
+                json!({"password": "abcd1234", "policy": {"usable": "ln"}}),
+                &Config::default()
+            ),
+            Ok(json!({"valid": true}))
+        );
+        assert_eq!(
+            check(
+                json!({"password": "abcd!234", "policy": {"usable": "ln"}}),
+                &Config::default()
+            ),
+            Ok(json!({"valid": false, "error": "unusable character: !"}))
+        );
+        assert_eq!(
+            check(
+                json!({"password": "abcd1234", "policy": {"usable": "lx"}}),
+                &Config::default()
+            ),
+            Err(String::from("unknown usable flags: x"))
+        );
+    }
+
+    #[test]
+    fn test_validate_policy() {
+        let value =
+            validate_policy(json!({"length": 10, "usable": "n"}), &Config::default()).unwrap();
+        assert_eq!(value["policy"]["usable"], json!("n"));
+        assert_eq!(value["policy"]["symbols"], json!(""));
+        assert!((value["entropy"].as_f64().unwrap() - 10.0 * 10f64.log2()).abs() < 1e-9);
+        assert_eq!(value["uniform_entropy"], value["entropy"]);
+
+        let value =
+            validate_policy(json!({"usable": "ns", "mobile": true}), &Config::default()).unwrap();
+        assert!(value["entropy"].as_f64() < value["uniform_entropy"].as_f64());
+
+        assert_eq!(
+            validate_policy(json!({"symbols": "!a"}), &Config::default()),
+            Err(String::from("unknown symbol character: a"))
+        );
+    }
+
+    #[test]
+    fn test_list_categories() {
+        let value = list_categories(Value::Null, &Config::default()).unwrap();
+        let flags: Vec<&str> = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["flag"].as_str().unwrap())
+            .collect();
+        assert_eq!(flags, vec!["l", "u", "n", "s", "h", "k"]);
+        assert_eq!(value[2]["easy_characters"], json!("23456789"));
+        assert_eq!(value[4]["description"], json!("hiragana"));
+        assert_eq!(value[4]["characters"].as_str().unwrap().chars().count(), 71);
+
+        let config = Config::parse(
+            r#"{"categories": [{"name": "greek", "flag": "g", "characters": "αβγορ"}]}"#,
+        )
+        .unwrap();
+        let value = list_categories(Value::Null, &config).unwrap();
+        assert_eq!(value[4]["flag"], json!("g"));
+        assert_eq!(value[4]["description"], json!("greek"));
+        assert_eq!(value[4]["characters"], json!("αβγορ"));
+        // ρ looks the same as p, and o is not an easy character.
+        assert_eq!(value[4]["easy_characters"], json!("αβγο"));
+        assert_eq!(value.as_array().unwrap().len(), 7);
+    }
+}
@@ -0,0 +1,288 @@
+use crate::kana::romaji;
+
+/// Language of the spelled names.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Language {
+    English,
+    Japanese,
+}
+
+pub const LANGUAGES: [&str; 2] = ["en", "ja"];
+
+/// Names of the characters in a language.
+struct Names {
+    /// NATO phonetic alphabet from A to Z.
+    letters: [&'static str; 26],
+    digits: [&'static str; 10],
+    /// Names of the characters of `symbols_all!`, in its order.
+    symbols: [&'static str; 32],
+    space: &'static str,
+    upper: &'static str,
+    lower: &'static str,
+    number: &'static str,
+    hiragana: &'static str,
+    katakana: &'static str,
+    delimiter: &'static str,
+}
+
+const ENGLISH: Names = Names {
+    letters: [
+        "Alfa", "Bravo", "Charlie", "Delta", "Echo", "Foxtrot", "Golf", "Hotel", "India",
+        "Juliett", "Kilo", "Lima", "Mike", "November", "Oscar", "Papa", "Quebec", "Romeo",
+        "Sierra", "Tango", "Uniform", "Victor", "Whiskey", "X-ray", "Yankee", "Zulu",
+    ],
+    digits: [
+        "Zero", "One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine",
+    ],
+    symbols: [
+        "Exclamation mark",
+        "At sign",
+        "Hash",
+        "Dollar sign",
+        "Percent sign",
+        "Caret",
+        "Ampersand",
+        "Asterisk",
+        "Left parenthesis",
+        "Right parenthesis",
+        "Backslash",
+        "Equals sign",
+        "Plus sign",
+        "Underscore",
+        "Hyphen",
+        "Left brace",
+        "Right brace",
+        "Left bracket",
+        "Right bracket",
+        "Colon",
+        "Backtick",
+        "Tilde",
+        "Vertical bar",
+        "Apostrophe",
+        "Quotation mark",
+        "Less-than sign",
+        "Greater-than sign",
+        "Question mark",
+        "Semicolon",
+        "Slash",
+        "Period",
+        "Comma",
+    ],
+    space: "Space",
+    upper: "",
+    lower: "lowercase ",
+    number: "",
+    hiragana: "hiragana ",
+    katakana: "katakana ",
+    delimiter: ", ",
+};
+
+const JAPANESE: Names = Names {
+    letters: [
+        "アルファ",
+        "ブラボー",
+        "チャーリー",
+        "デルタ",
+        "エコー",
+        "フォックストロット",
+        "ゴルフ",
+        "ホテル",
+        "インディア",
+        "ジュリエット",
+        "キロ",
+        "リマ",
+        "マイク",
+        "ノベンバー",
+        "オスカー",
+        "パパ",
+        "ケベック",
+        "ロメオ",
+        "シエラ",
+        "タンゴ",
+        "ユニフォーム",
+        "ビクター",
+        "ウィスキー",
+        "エックスレイ",
+        "ヤンキー",
+        "ズールー",
+    ],
+    digits: [
+        "ゼロ",
+        "いち",
+        "に",
+        "さん",
+        "よん",
+        "ご",
+        "ろく",
+        "なな",
+        "はち",
+        "きゅう",
+    ],
+    symbols: [
+        "感嘆符",
+        "アットマーク",
+        "シャープ",
+        "ドル記号",
+        "パーセント",
+        "キャレット",
+        "アンパサンド",
+        "アスタリスク",
+        "左丸括弧",
+        "右丸括弧",
+        "バックスラッシュ",
+        "イコール",
+        "プラス",
+        "アンダースコア",
+        "ハイフン",
+        "左波括弧",
+        "右波括弧",
+        "左角括弧",
+        "右角括弧",
+        "コロン",
+        "バッククォート",
+        "チルダ",
+        "縦棒",
+        "シングルクォート",
+        "ダブルクォート",
+        "小なり",
+        "大なり",
+        "疑問符",
+        "セミコロン",
+        "スラッシュ",
+        "ピリオド",
+        "カンマ",
+    ],
+    space: "スペース",
+    upper: "大文字の",
+    lower: "小文字の",
+    number: "数字の",
+    hiragana: "ひらがなの",
+    katakana: "カタカナの",
+    delimiter: "、",
+};
+
+impl Language {
+    /// Language of `name`, which is one of `LANGUAGES`.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "en" => Ok(Language::English),
+            "ja" => Ok(Language::Japanese),
+            _ => Err(format!("unknown language: {}", name)),
+        }
+    }
+
+    /// Language of the locale in `$LANG`, English unless it is Japanese.
+    pub fn from_env() -> Self {
+        match std::env::var("LANG") {
+            Ok(lang) if lang.starts_with("ja") => Language::Japanese,
+            _ => Language::English,
+        }
+    }
+
+    fn names(self) -> &'static Names {
+        match self {
+            Language::English => &ENGLISH,
+            Language::Japanese => &JAPANESE,
+        }
+    }
+
+    /// Name of `c`, or `c` with its code point unless it has a name.
+    pub fn name(self, c: char) -> String {
+        let names = self.names();
+        match c {
+            'A'..='Z' => format!(
+                "{}{}",
+                names.upper,
+                names.letters[c as usize - 'A' as usize]
+            ),
+            'a'..='z' => {
+                let letter = names.letters[c as usize - 'a' as usize];
+                format!("{}{}", names.lower, letter.to_lowercase())
+            }
+            '0'..='9' => format!(
+                "{}{}",
+                names.number,
+                names.digits[c as usize - '0' as usize]
+            ),
+            ' ' => String::from(names.space),
+            '\u{3041}'..='\u{3096}' => format!("{}{}", names.hiragana, self.kana(c)),
+            '\u{30a1}'..='\u{30f6}' => format!("{}{}", names.katakana, self.kana(c)),
+            _ => match crate::symbols_all!().chars().position(|s| s == c) {
+                Some(i) => String::from(names.symbols[i]),
+                None => format!("{} (U+{:04X})", c, c as u32),
+            },
+        }
+    }
+
+    /// Kana as themselves in Japanese and in romaji in English.
+    fn kana(self, c: char) -> String {
+        match self {
+            Language::English => romaji(&c.to_string()).to_lowercase(),
+            Language::Japanese => c.to_string(),
+        }
+    }
+
+    /// Names of the characters of `secret`, as `Bravo, lowercase kilo, Seven, Exclamation mark`.
+    pub fn spell(self, secret: &str) -> String {
+        secret
+            .chars()
+            .map(|c| self.name(c))
+            .collect::<Vec<String>>()
+            .join(self.names().delimiter)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_spell() {
+        assert_eq!(
+            Language::English.spell("Bk7!"),
+            "Bravo, lowercase kilo, Seven, Exclamation mark"
+        );
+        assert_eq!(
+            Language::Japanese.spell("Bk7!"),
+            "大文字のブラボー、小文字のキロ、数字のなな、感嘆符"
+        );
+        assert_eq!(
+            Language::English.spell("xXあガ α"),
+            "lowercase x-ray, X-ray, hiragana a, katakana ga, Space, α (U+03B1)"
+        );
+        assert_eq!(
+            Language::Japanese.spell("あガ"),
+            "ひらがなのあ、カタカナのガ"
+        );
+    }
+
+    #[test]
+    fn test_names() {
+        for language in &[Language::English, Language::Japanese] {
+            let names: Vec<String> = crate::symbols_all!()
+                .chars()
+                .chain('0'..='9')
+                .chain('a'..='z')
+                .chain('A'..='Z')
+                .map(|c| language.name(c))
+                .collect();
+            // Every character is told apart by its name.
+            let mut unique = names.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(unique.len(), names.len());
+            assert!(names.iter().all(|n| !n.contains("U+")));
+        }
+    }
+
+    #[test]
+    fn test_from_name() {
+        for name in LANGUAGES.iter() {
+            assert!(Language::from_name(name).is_ok());
+        }
+        assert_eq!(
+            Language::from_name("fr"),
+            Err(String::from("unknown language: fr"))
+        );
+    }
+}
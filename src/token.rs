@@ -0,0 +1,102 @@
+use rand::Rng;
+
+use crate::encoding::Encoding;
+
+pub const MIN_BYTES: usize = 8;
+pub const MAX_BYTES: usize = 1024;
+pub const DEFAULT_BYTES: usize = 32;
+
+/// Token of `bytes` random bytes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    bytes: usize,
+    encoding: Encoding,
+    padding: bool,
+}
+
+impl Token {
+    /// Token padded as the encoding does by default unless `padding` is specified.
+    pub fn new(bytes: usize, encoding: Encoding, padding: Option<bool>) -> Result<Self, String> {
+        Self::validate_bytes(bytes)?;
+        if padding == Some(true) && !encoding.has_padding() {
+            return Err(format!("{} has no padding", encoding.name()));
+        }
+        Ok(Token {
+            bytes,
+            encoding,
+            padding: padding.unwrap_or_else(|| encoding.pads_by_default()),
+        })
+    }
+
+    pub fn validate_bytes(bytes: usize) -> Result<(), String> {
+        if (MIN_BYTES..=MAX_BYTES).contains(&bytes) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Needs {} to {} bytes: {}",
+                MIN_BYTES, MAX_BYTES, bytes
+            ))
+        }
+    }
+
+    /// Entropy in bits, which the encoding does not change.
+    pub fn entropy(&self) -> f64 {
+        (self.bytes * 8) as f64
+    }
+
+    pub fn generate(&self) -> String {
+        self.sample(&mut rand::thread_rng())
+    }
+
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        let mut bytes = vec![0u8; self.bytes];
+        rng.fill_bytes(&mut bytes);
+        self.encoding.encode(&bytes, self.padding)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn test_sample() {
+        // Bytes of 1, 2, 3 and 4 as 64-bit words in little endian.
+        let mut rng = StepRng::new(1, 1);
+        let token = Token::new(8, Encoding::Hex, None).unwrap();
+        assert_eq!(token.sample(&mut rng), "0100000000000000");
+        let token = Token::new(8, Encoding::Base64, None).unwrap();
+        assert_eq!(token.sample(&mut rng), "AgAAAAAAAAA=");
+        let token = Token::new(8, Encoding::Base64Url, None).unwrap();
+        assert_eq!(token.sample(&mut rng), "AwAAAAAAAAA");
+        let token = Token::new(8, Encoding::Base64Url, Some(true)).unwrap();
+        assert_eq!(token.sample(&mut rng), "BAAAAAAAAAA=");
+    }
+
+    #[test]
+    fn test_generate() {
+        let token = Token::new(32, Encoding::Base58, None).unwrap();
+        assert_eq!(token.entropy(), 256.0);
+        let generated = token.generate();
+        assert_ne!(generated, token.generate());
+        assert!(generated.chars().all(|c| c.is_ascii_alphanumeric()));
+        let token = Token::new(32, Encoding::Base32, Some(false)).unwrap();
+        assert_eq!(token.generate().len(), 52);
+        let token = Token::new(32, Encoding::Base32, None).unwrap();
+        assert_eq!(token.generate().len(), 56);
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            Token::new(4, Encoding::Hex, None),
+            Err(String::from("Needs 8 to 1024 bytes: 4"))
+        );
+        assert_eq!(
+            Token::new(32, Encoding::Crockford, Some(true)),
+            Err(String::from("crockford has no padding"))
+        );
+        assert!(Token::new(32, Encoding::Crockford, Some(false)).is_ok());
+    }
+}
@@ -0,0 +1,52 @@
+use wasm_bindgen::prelude::*;
+
+use crate::generator::{policy::Policy, Generator};
+
+/// Password generator exported to JavaScript as `Generator`.
+#[wasm_bindgen(js_name = Generator)]
+pub struct WasmGenerator {
+    generator: Generator,
+}
+
+#[wasm_bindgen(js_class = Generator)]
+impl WasmGenerator {
+    /// Same options and validations as the command line.
+    /// `usable` defaults to all categories and `symbols` to all symbols.
+    /// The other options are taken by `fromPolicy`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        length: usize,
+        usable: Option<String>,
+        is_easy: bool,
+        symbols: Option<String>,
+    ) -> Result<WasmGenerator, JsValue> {
+        let default = Policy::default();
+        let policy = Policy {
+            length,
+            usable: usable.unwrap_or(default.usable),
+            is_easy,
+            symbols: symbols.unwrap_or(default.symbols),
+            ..Policy::default()
+        };
+        let generator = policy.generator().map_err(JsValue::from)?;
+        Ok(WasmGenerator { generator })
+    }
+
+    /// Takes every option as a JSON policy, such as `{"length": 16, "first": "l"}`.
+    #[wasm_bindgen(js_name = fromPolicy)]
+    pub fn from_policy(policy: &str) -> Result<WasmGenerator, JsValue> {
+        let policy: Policy =
+            serde_json::from_str(policy).map_err(|e| JsValue::from(e.to_string()))?;
+        let generator = policy.generator().map_err(JsValue::from)?;
+        Ok(WasmGenerator { generator })
+    }
+
+    pub fn generate(&self) -> String {
+        self.generator.generate()
+    }
+
+    /// Bits of entropy of a generated password.
+    pub fn entropy(&self) -> f64 {
+        self.generator.entropy()
+    }
+}
@@ -0,0 +1,238 @@
+use pbkdf2::pbkdf2_hmac;
+use sha1::Sha1;
+
+use crate::generator::policy::Policy;
+use crate::qr::wifi_payload;
+
+/// WPA-PSK passphrases are 8 to 63 printable ASCII characters.
+pub const MIN_LENGTH: usize = 8;
+pub const MAX_LENGTH: usize = 63;
+pub const DEFAULT_LENGTH: usize = 20;
+pub const MAX_SSID_BYTES: usize = 32;
+
+/// Symbols which routers, printers and IoT devices accept in passphrases.
+pub const DEVICE_SAFE_SYMBOLS: &str = "!#%*+-.=?@_";
+
+/// Iterations of PBKDF2 in IEEE 802.11i.
+const PSK_ITERATIONS: u32 = 4096;
+
+/// Security of a network.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Security {
+    /// WPA2-Personal, whose devices derive the PSK from the passphrase.
+    Wpa2,
+    /// WPA3-Personal, whose devices authenticate by SAE with the passphrase.
+    Wpa3,
+}
+
+impl Security {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "wpa2" => Ok(Security::Wpa2),
+            "wpa3" => Ok(Security::Wpa3),
+            _ => Err(format!("unknown security: {}", name)),
+        }
+    }
+}
+
+/// Wi-Fi network of an SSID and a passphrase.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Network {
+    ssid: String,
+    passphrase: String,
+    security: Security,
+}
+
+impl Network {
+    pub fn new(ssid: &str, passphrase: &str, security: Security) -> Result<Self, String> {
+        Self::validate_ssid(ssid)?;
+        Self::validate_length(passphrase.chars().count())?;
+        let unprintable: String = passphrase
+            .chars()
+            .filter(|c| !(' '..='~').contains(c))
+            .collect();
+        if !unprintable.is_empty() {
+            return Err(format!("not printable ASCII: {}", unprintable));
+        }
+        Ok(Network {
+            ssid: String::from(ssid),
+            passphrase: String::from(passphrase),
+            security,
+        })
+    }
+
+    /// Network of a passphrase of `length` letters, numbers and `DEVICE_SAFE_SYMBOLS`.
+    pub fn generate(ssid: &str, length: usize, security: Security) -> Result<Self, String> {
+        Self::validate_ssid(ssid)?;
+        Self::validate_length(length)?;
+        let policy = Policy {
+            length,
+            symbols: String::from(DEVICE_SAFE_SYMBOLS),
+            ..Policy::default()
+        };
+        Self::new(ssid, &policy.generator()?.generate(), security)
+    }
+
+    pub fn validate_ssid(ssid: &str) -> Result<(), String> {
+        if ssid.is_empty() || ssid.len() > MAX_SSID_BYTES {
+            Err(format!(
+                "Needs 1 to {} bytes of SSID: {}",
+                MAX_SSID_BYTES,
+                ssid.len()
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn validate_length(length: usize) -> Result<(), String> {
+        if (MIN_LENGTH..=MAX_LENGTH).contains(&length) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Needs {} to {} characters: {}",
+                MIN_LENGTH, MAX_LENGTH, length
+            ))
+        }
+    }
+
+    pub fn passphrase(&self) -> &str {
+        &self.passphrase
+    }
+
+    /// 256-bit PSK in hex, derived by PBKDF2-HMAC-SHA1 with the SSID as the salt.
+    pub fn psk(&self) -> String {
+        let mut psk = [0u8; 32];
+        pbkdf2_hmac::<Sha1>(
+            self.passphrase.as_bytes(),
+            self.ssid.as_bytes(),
+            PSK_ITERATIONS,
+            &mut psk,
+        );
+        psk.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Network block of `wpa_supplicant.conf`, as printed by `wpa_passphrase` for WPA2.
+    pub fn supplicant_conf(&self) -> String {
+        let printable = self
+            .ssid
+            .chars()
+            .all(|c| (' '..='~').contains(&c) && c != '"');
+        let ssid = if printable {
+            format!("\"{}\"", self.ssid)
+        } else {
+            self.ssid.bytes().map(|b| format!("{:02x}", b)).collect()
+        };
+        let mut lines = vec![format!("\tssid={}", ssid)];
+        match self.security {
+            Security::Wpa2 => {
+                lines.push(format!("\t#psk=\"{}\"", self.passphrase));
+                lines.push(format!("\tpsk={}", self.psk()));
+            }
+            Security::Wpa3 => {
+                lines.push(String::from("\tkey_mgmt=SAE"));
+                lines.push(format!("\tsae_password=\"{}\"", self.passphrase));
+                lines.push(String::from("\tieee80211w=2"));
+            }
+        }
+        format!("network={{\n{}\n}}", lines.join("\n"))
+    }
+
+    /// Payload of QR codes which phones join, as `WIFI:T:WPA;S:...;P:...;;`.
+    pub fn uri(&self) -> String {
+        let security = match self.security {
+            Security::Wpa2 => "WPA",
+            Security::Wpa3 => "SAE",
+        };
+        wifi_payload(&self.ssid, &self.passphrase, security, false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_psk() {
+        // Test vectors of IEEE 802.11i H.4.
+        let network = Network::new("IEEE", "password", Security::Wpa2).unwrap();
+        assert_eq!(
+            network.psk(),
+            "f42c6fc52df0ebef9ebb4b90b38a5f902e83fe1b135a70e23aed762e9710a12e"
+        );
+        let network = Network::new("ThisIsASSID", "ThisIsAPassword", Security::Wpa2).unwrap();
+        assert_eq!(
+            network.psk(),
+            "0dc0d6eb90555ed6419756b9a15ec3e3209b63df707dd508d14581f8982721af"
+        );
+    }
+
+    #[test]
+    fn test_generate() {
+        for length in &[MIN_LENGTH, DEFAULT_LENGTH, MAX_LENGTH] {
+            let network = Network::generate("home", *length, Security::Wpa2).unwrap();
+            let passphrase = network.passphrase();
+            assert_eq!(passphrase.len(), *length);
+            assert!(passphrase
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || DEVICE_SAFE_SYMBOLS.contains(c)));
+        }
+        assert_eq!(
+            Network::generate("home", 64, Security::Wpa2),
+            Err(String::from("Needs 8 to 63 characters: 64"))
+        );
+        assert_eq!(
+            Network::generate(&"a".repeat(33), 20, Security::Wpa2),
+            Err(String::from("Needs 1 to 32 bytes of SSID: 33"))
+        );
+        let symbols = crate::symbols_all!();
+        assert!(DEVICE_SAFE_SYMBOLS.chars().all(|c| symbols.contains(c)));
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            Network::new("home", "パスワードパスワード", Security::Wpa2),
+            Err(String::from("not printable ASCII: パスワードパスワード"))
+        );
+        assert_eq!(
+            Network::new("home", "short", Security::Wpa2),
+            Err(String::from("Needs 8 to 63 characters: 5"))
+        );
+        assert_eq!(
+            Network::new("", "password", Security::Wpa2),
+            Err(String::from("Needs 1 to 32 bytes of SSID: 0"))
+        );
+    }
+
+    #[test]
+    fn test_supplicant_conf() {
+        let network = Network::new("IEEE", "password", Security::Wpa2).unwrap();
+        assert_eq!(
+            network.supplicant_conf(),
+            "network={\n\
+             \tssid=\"IEEE\"\n\
+             \t#psk=\"password\"\n\
+             \tpsk=f42c6fc52df0ebef9ebb4b90b38a5f902e83fe1b135a70e23aed762e9710a12e\n\
+             }"
+        );
+        let network = Network::new("café \"1\"", "password", Security::Wpa3).unwrap();
+        assert_eq!(
+            network.supplicant_conf(),
+            "network={\n\
+             \tssid=636166c3a920223122\n\
+             \tkey_mgmt=SAE\n\
+             \tsae_password=\"password\"\n\
+             \tieee80211w=2\n\
+             }"
+        );
+    }
+
+    #[test]
+    fn test_uri() {
+        let network = Network::new("my;net", "pass:word", Security::Wpa2).unwrap();
+        assert_eq!(network.uri(), r"WIFI:T:WPA;S:my\;net;P:pass\:word;;");
+        let network = Network::new("my;net", "pass:word", Security::Wpa3).unwrap();
+        assert_eq!(network.uri(), r"WIFI:T:WPA;R:1;S:my\;net;P:pass\:word;;");
+    }
+}
@@ -0,0 +1,255 @@
+use pswdgn::Policy;
+use serde::Deserialize;
+use serde_yaml::Value;
+
+/// Generates passwords from every category which are safe for `context`.
+fn passwords(context: &str) -> Vec<String> {
+    let generator = Policy {
+        length: 32,
+        safe_for: String::from(context),
+        ..Policy::default()
+    }
+    .generator()
+    .unwrap();
+    (0..200).map(|_| generator.generate()).collect()
+}
+
+/// Splits `key=value` pairs separated by `separator`.
+fn pairs(s: &str, separator: char) -> Vec<(&str, &str)> {
+    s.split(separator)
+        .filter(|p| !p.is_empty())
+        .map(|p| p.split_once('=').unwrap())
+        .collect()
+}
+
+/// Parses the attributes of an ODBC connection string, whose values are either plain up to
+/// `;` or enclosed in braces with `}}` for `}`.
+fn odbc_attributes(s: &str) -> Result<Vec<(String, String)>, String> {
+    let mut attributes = Vec::new();
+    let mut chars = s.chars().peekable();
+    while chars.peek().is_some() {
+        let key: String = chars.by_ref().take_while(|c| *c != '=').collect();
+        let mut value = String::new();
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('}') if chars.peek() == Some(&'}') => {
+                        chars.next();
+                        value.push('}');
+                    }
+                    Some('}') => break,
+                    Some(c) => value.push(c),
+                    None => return Err(format!("unclosed brace: {}", s)),
+                }
+            }
+            match chars.next() {
+                Some(';') | None => {}
+                Some(c) => return Err(format!("{} after brace: {}", c, s)),
+            }
+        } else {
+            value = chars.by_ref().take_while(|c| *c != ';').collect();
+            if value.contains('{') || value.contains('}') {
+                return Err(format!("brace in plain value: {}", s));
+            }
+        }
+        attributes.push((key, value));
+    }
+    Ok(attributes)
+}
+
+/// Parses the attribute type and value of every RDN of an LDAP distinguished name
+/// (RFC 4514), in which `,` and `+` separate the RDNs and their attributes.
+fn ldap_rdns(dn: &str) -> Result<Vec<(String, String)>, String> {
+    let mut rdns = Vec::new();
+    let mut chars = dn.chars().peekable();
+    while chars.peek().is_some() {
+        let key: String = chars.by_ref().take_while(|c| *c != '=').collect();
+        let mut value = String::new();
+        // Leading and trailing spaces must be escaped.
+        let mut escaped_len = 0;
+        match chars.peek() {
+            Some('#') => return Err(format!("hex string value: {}", dn)),
+            Some(' ') => return Err(format!("leading space: {}", dn)),
+            _ => {}
+        }
+        loop {
+            match chars.next() {
+                Some(',') | Some('+') | None => break,
+                Some('\\') => match chars.next() {
+                    Some(c) if " \"#+,;<=>\\".contains(c) => {
+                        value.push(c);
+                        escaped_len = value.len();
+                    }
+                    c => return Err(format!("escape of {:?}: {}", c, dn)),
+                },
+                Some(c) if "\"<>;".contains(c) => return Err(format!("unescaped {}: {}", c, dn)),
+                Some(c) => value.push(c),
+            }
+        }
+        if value.len() > escaped_len && value.ends_with(' ') {
+            return Err(format!("trailing space: {}", dn));
+        }
+        rdns.push((key, value));
+    }
+    Ok(rdns)
+}
+
+#[test]
+fn test_url() {
+    for password in passwords("url") {
+        let url = url::Url::parse(&format!("postgres://app:{}@localhost/db", password)).unwrap();
+        assert_eq!(url.password(), Some(password.as_str()));
+        assert_eq!(url.host_str(), Some("localhost"));
+    }
+}
+
+#[test]
+fn test_shell() {
+    for password in passwords("shell") {
+        let words = shell_words::split(&format!("login {} now", password)).unwrap();
+        assert_eq!(words, vec!["login", &password, "now"]);
+    }
+}
+
+#[test]
+fn test_json() {
+    for password in passwords("json") {
+        let parsed: String = serde_json::from_str(&format!("\"{}\"", password)).unwrap();
+        assert_eq!(parsed, password);
+    }
+}
+
+#[derive(Deserialize)]
+struct User {
+    #[serde(rename = "@password")]
+    password: String,
+    #[serde(rename = "$text")]
+    text: String,
+}
+
+#[test]
+fn test_xml() {
+    for password in passwords("xml") {
+        let xml = format!("<user password='{0}'>{0}</user>", password);
+        let user: User = quick_xml::de::from_str(&xml).unwrap();
+        assert_eq!(user.password, password);
+        assert_eq!(user.text, password);
+    }
+}
+
+#[test]
+fn test_yaml() {
+    for password in passwords("yaml") {
+        let parsed: Value = serde_yaml::from_str(&format!("password: {}\n", password)).unwrap();
+        assert_eq!(parsed["password"], Value::String(password));
+    }
+}
+
+#[test]
+fn test_dotenv() {
+    for password in passwords("dotenv") {
+        let env = format!("PASSWORD={}\n", password);
+        let vars: Vec<(String, String)> = dotenvy::from_read_iter(env.as_bytes())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(vars, vec![(String::from("PASSWORD"), password)]);
+    }
+}
+
+#[test]
+fn test_jdbc() {
+    for password in passwords("jdbc") {
+        let jdbc = format!(
+            "jdbc:postgresql://localhost/db?user=app&password={}",
+            password
+        );
+        let url = url::Url::parse(jdbc.trim_start_matches("jdbc:")).unwrap();
+        let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        assert_eq!(query[1], (String::from("password"), password.clone()));
+
+        let jdbc = format!(
+            "jdbc:sqlserver://localhost;user=app;password={};encrypt=true",
+            password
+        );
+        let properties = pairs(jdbc.split_once(';').unwrap().1, ';');
+        assert_eq!(properties[1], ("password", password.as_str()));
+        assert_eq!(properties.len(), 3);
+    }
+}
+
+#[test]
+fn test_odbc() {
+    for password in passwords("odbc") {
+        let odbc = format!("Driver={{PostgreSQL}};Uid=app;Pwd={};", password);
+        let attributes = odbc_attributes(&odbc).unwrap();
+        assert_eq!(attributes[2], (String::from("Pwd"), password));
+        assert_eq!(attributes.len(), 3);
+    }
+}
+
+#[test]
+fn test_odbc_quoting() {
+    let pwd = |odbc: &str| odbc_attributes(odbc).map(|a| a[1].1.clone());
+    assert_eq!(pwd("Uid=app;Pwd={a;b}}c};"), Ok(String::from("a;b}c")));
+    assert_eq!(pwd("Uid=app;Pwd=a;b;").ok(), Some(String::from("a")));
+    assert!(pwd("Uid=app;Pwd={a;").is_err());
+    assert!(pwd("Uid=app;Pwd=a}b;").is_err());
+    assert!(pwd("Uid=app;Pwd={a}b;").is_err());
+    // Passwords safe for ODBC need no braces.
+    let odbc = Policy {
+        length: 8,
+        usable: String::from("s"),
+        safe_for: String::from("odbc"),
+        ..Policy::default()
+    };
+    let symbols = odbc.generator().unwrap();
+    for _ in 0..200 {
+        let password = symbols.generate();
+        assert!(!password.contains(|c| "{};=".contains(c)), "{}", password);
+    }
+}
+
+#[test]
+fn test_ldap_dn() {
+    for password in passwords("ldap-dn") {
+        let dn = format!("cn={},ou=users,dc=example", password);
+        let rdns = ldap_rdns(&dn).unwrap();
+        assert_eq!(rdns[0], (String::from("cn"), password));
+        assert_eq!(rdns.len(), 3);
+    }
+}
+
+#[test]
+fn test_ldap_dn_escaping() {
+    let cn = |dn: &str| ldap_rdns(dn).map(|r| r[0].1.clone());
+    assert_eq!(
+        cn(r#"cn=\#a\,b\+c\"d\\e\<f\>g\;h\ ,dc=example"#),
+        Ok(String::from(r#"#a,b+c"d\e<f>g;h "#))
+    );
+    assert_eq!(cn(r"cn=\ a,dc=example"), Ok(String::from(" a")));
+    assert!(cn("cn=#a,dc=example").is_err());
+    assert!(cn("cn= a,dc=example").is_err());
+    assert!(cn("cn=a ,dc=example").is_err());
+    for c in r#""<>;"#.chars() {
+        assert!(cn(&format!("cn=a{}b,dc=example", c)).is_err(), "{}", c);
+    }
+    assert!(cn(r"cn=a\b,dc=example").is_err());
+    assert_eq!(cn("cn=a,b,dc=example"), Ok(String::from("a")));
+    assert_eq!(cn("cn=a+b,dc=example"), Ok(String::from("a")));
+}
+
+#[test]
+fn test_csv() {
+    for password in passwords("csv") {
+        let csv = format!("app,{},now\n", password);
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(csv.as_bytes());
+        let record = reader.records().next().unwrap().unwrap();
+        assert_eq!(
+            record.iter().collect::<Vec<_>>(),
+            vec!["app", &password, "now"]
+        );
+    }
+}
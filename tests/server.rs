@@ -0,0 +1,293 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+use pswdgn::server::{Config, Listen, Server, DEFAULT_WORKERS};
+use serde_json::Value;
+
+struct Running {
+    server: Arc<Server>,
+    addr: SocketAddr,
+}
+
+impl Running {
+    fn start(token: Option<&str>, max_body: usize) -> Self {
+        let server = Server::bind(Config {
+            listen: Listen::Tcp(String::from("127.0.0.1:0")),
+            token: token.map(String::from),
+            max_body,
+            workers: DEFAULT_WORKERS,
+        })
+        .unwrap();
+        let server = Arc::new(server);
+        let addr = server.local_addr().unwrap();
+        let running = Arc::clone(&server);
+        thread::spawn(move || running.run());
+        Running { server, addr }
+    }
+
+    fn request(&self, method: &str, path: &str, headers: &[&str], body: &str) -> Reply {
+        let mut stream = TcpStream::connect(self.addr).unwrap();
+        let mut request = format!(
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n",
+            method,
+            path,
+            body.len()
+        );
+        for h in headers {
+            request.push_str(h);
+            request.push_str("\r\n");
+        }
+        request.push_str("\r\n");
+        request.push_str(body);
+        stream.write_all(request.as_bytes()).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        Reply::parse(&response)
+    }
+
+    fn post(&self, path: &str, body: &str) -> Reply {
+        self.request("POST", path, &[], body)
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+struct Reply {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Value,
+}
+
+impl Reply {
+    fn parse(response: &str) -> Self {
+        let (head, body) = response.split_at(response.find("\r\n\r\n").unwrap());
+        let mut lines = head.split("\r\n");
+        let status = lines.next().unwrap().split(' ').nth(1).unwrap();
+        let headers = lines
+            .map(|l| {
+                let (field, value) = l.split_at(l.find(':').unwrap());
+                (field.to_ascii_lowercase(), String::from(value[1..].trim()))
+            })
+            .collect();
+        Reply {
+            status: status.parse().unwrap(),
+            headers,
+            body: serde_json::from_str(&body[4..]).unwrap(),
+        }
+    }
+
+    fn header(&self, field: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(f, _)| f == field)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[test]
+fn stalled_request() {
+    let running = Running::start(None, 1024);
+    // A client which never sends its body holds up one worker.
+    let mut stalled = TcpStream::connect(running.addr).unwrap();
+    stalled
+        .write_all(b"POST /generate HTTP/1.1\r\nHost: localhost\r\nContent-Length: 10\r\n\r\n")
+        .unwrap();
+    thread::sleep(std::time::Duration::from_millis(100));
+
+    let reply = running.post("/generate", "");
+    assert_eq!(reply.status, 200);
+}
+
+#[test]
+fn generate() {
+    let running = Running::start(None, 1024);
+    let reply = running.post("/generate", r#"{"length": 20, "usable": "ln"}"#);
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.header("cache-control"), Some("no-store"));
+    assert_eq!(reply.header("content-type"), Some("application/json"));
+    let password = reply.body["password"].as_str().unwrap();
+    assert_eq!(password.len(), 20);
+    assert!(password
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()));
+
+    let reply = running.post("/generate", "");
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.body["password"].as_str().unwrap().len(), 8);
+}
+
+#[test]
+fn batch() {
+    let running = Running::start(None, 1024);
+    let reply = running.post("/batch", r#"{"count": 5, "policy": {"easy": true}}"#);
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.body["passwords"].as_array().unwrap().len(), 5);
+
+    let reply = running.post("/batch", r#"{"count": 1001}"#);
+    assert_eq!(reply.status, 400);
+    assert_eq!(reply.header("cache-control"), Some("no-store"));
+}
+
+#[test]
+fn passphrase() {
+    let running = Running::start(None, 1024);
+    let reply = running.post(
+        "/passphrase",
+        r#"{"words": 5, "separator": " ", "policy": {"usable": "n"}}"#,
+    );
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.header("cache-control"), Some("no-store"));
+    let passphrase = reply.body["passphrase"].as_str().unwrap();
+    assert_eq!(passphrase.split(' ').count(), 5);
+    assert!(passphrase.chars().all(|c| c == ' ' || c.is_ascii_digit()));
+    let entropy = reply.body["entropy"].as_f64().unwrap();
+    assert!((entropy - 40.0 * 10f64.log2()).abs() < 1e-9);
+
+    let reply = running.post("/passphrase", r#"{"words": 1001}"#);
+    assert_eq!(reply.status, 400);
+    assert_eq!(
+        reply.body["error"].as_str(),
+        Some("Needs 1 to 1000 words: 1001")
+    );
+
+    let reply = running.post("/passphrase", r#"{"kana": true}"#);
+    assert_eq!(reply.status, 200);
+    assert_eq!(
+        reply.body["passphrase"]
+            .as_str()
+            .unwrap()
+            .split('-')
+            .count(),
+        4
+    );
+    assert!(reply.body["romaji"].as_str().unwrap().is_ascii());
+
+    let reply = running.post("/passphrase", r#"{"kana": true, "separator": "あ"}"#);
+    assert_eq!(reply.status, 400);
+    assert_eq!(
+        reply.body["error"].as_str(),
+        Some("separator must not be empty nor kana: あ")
+    );
+}
+
+#[test]
+fn check() {
+    let running = Running::start(None, 1024);
+    let reply = running.post(
+        "/check",
+        r#"{"password": "abcd1234", "policy": {"usable": "ln"}}"#,
+    );
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.body["valid"], Value::Bool(true));
+
+    let reply = running.post(
+        "/check",
+        r#"{"password": "abcdefgh", "policy": {"usable": "ln"}}"#,
+    );
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.body["valid"], Value::Bool(false));
+    assert_eq!(
+        reply.body["error"].as_str(),
+        Some("Usable character category is missing")
+    );
+}
+
+#[test]
+fn entropy() {
+    let running = Running::start(None, 1024);
+    let reply = running.post("/entropy", r#"{"length": 10, "usable": "n"}"#);
+    assert_eq!(reply.status, 200);
+    let entropy = reply.body["entropy"].as_f64().unwrap();
+    assert!((entropy - 10.0 * 10f64.log2()).abs() < 1e-9);
+}
+
+#[test]
+fn invalid_policy() {
+    let running = Running::start(None, 1024);
+    let reply = running.post("/generate", r#"{"length": 7}"#);
+    assert_eq!(reply.status, 400);
+    assert_eq!(reply.body["error"].as_str(), Some("Needs 8 or more: 7"));
+
+    let reply = running.post("/generate", r#"{"usable": "x"}"#);
+    assert_eq!(reply.status, 400);
+    assert_eq!(
+        reply.body["error"].as_str(),
+        Some("unknown usable flags: x")
+    );
+
+    let reply = running.post("/generate", "[");
+    assert_eq!(reply.status, 400);
+}
+
+#[test]
+fn routing() {
+    let running = Running::start(None, 1024);
+    assert_eq!(running.post("/unknown", "").status, 404);
+    let reply = running.request("GET", "/generate", &[], "");
+    assert_eq!(reply.status, 405);
+    assert_eq!(reply.header("cache-control"), Some("no-store"));
+}
+
+#[test]
+fn size_limit() {
+    let running = Running::start(None, 32);
+    let body = format!(r#"{{"length": 8, "symbols": "{}"}}"#, "!".repeat(32));
+    assert_eq!(running.post("/generate", &body).status, 413);
+    assert_eq!(running.post("/generate", r#"{"length": 8}"#).status, 200);
+}
+
+#[test]
+fn bearer_token() {
+    let running = Running::start(Some("s3cret"), 1024);
+    let reply = running.post("/generate", "");
+    assert_eq!(reply.status, 401);
+    assert_eq!(reply.header("www-authenticate"), Some("Bearer"));
+
+    let reply = running.request("POST", "/generate", &["Authorization: Bearer wrong"], "");
+    assert_eq!(reply.status, 401);
+
+    let reply = running.request("POST", "/generate", &["Authorization: Bearer s3cret"], "");
+    assert_eq!(reply.status, 200);
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_socket() {
+    use std::os::unix::net::UnixStream;
+
+    let path = std::env::temp_dir().join(format!("pswdgn-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let server = Arc::new(
+        Server::bind(Config {
+            listen: Listen::Unix(path.clone()),
+            token: None,
+            max_body: 1024,
+            workers: DEFAULT_WORKERS,
+        })
+        .unwrap(),
+    );
+    let running = Arc::clone(&server);
+    thread::spawn(move || running.run());
+
+    let mut stream = UnixStream::connect(&path).unwrap();
+    stream
+        .write_all(
+            b"POST /generate HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: 0\r\n\r\n",
+        )
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    server.unblock();
+    let _ = std::fs::remove_file(&path);
+
+    let reply = Reply::parse(&response);
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.body["password"].as_str().unwrap().len(), 8);
+}
@@ -0,0 +1,119 @@
+// Test harness for the WebAssembly build.
+//
+//     npm run build
+//     npm test
+"use strict";
+
+const assert = require("assert");
+const { Generator } = require("./pkg/pswdgn.js");
+
+const LOWER = "abcdefghijklmnopqrstuvwxyz";
+const UPPER = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const NUMBER = "0123456789";
+const SYMBOL = "!@#$%^&*()\\=+_-{}[]:`~|'\"<>?;/.,";
+
+const tests = [];
+function test(name, f) {
+  tests.push([name, f]);
+}
+
+function assertOnly(password, characters) {
+  for (const c of password) {
+    assert.ok(characters.includes(c), `unexpected character ${c} in ${password}`);
+  }
+}
+
+test("default length and categories", () => {
+  const password = new Generator(8).generate();
+  assert.strictEqual(password.length, 8);
+  assertOnly(password, LOWER + UPPER + NUMBER + SYMBOL);
+});
+
+test("passwords differ", () => {
+  const gen = new Generator(15, "lun", false);
+  assert.notStrictEqual(gen.generate(), gen.generate());
+});
+
+test("every usable category appears", () => {
+  const gen = new Generator(8, "ln", false);
+  for (let i = 0; i < 100; i++) {
+    const password = gen.generate();
+    assertOnly(password, LOWER + NUMBER);
+    assert.ok([...password].some((c) => LOWER.includes(c)));
+    assert.ok([...password].some((c) => NUMBER.includes(c)));
+  }
+});
+
+test("easy characters", () => {
+  const password = new Generator(255, "lun", true).generate();
+  for (const c of "lIO01") {
+    assert.ok(!password.includes(c), `hard to identify character ${c}`);
+  }
+});
+
+test("explicit symbols", () => {
+  const password = new Generator(32, "s", false, "!@").generate();
+  assertOnly(password, "!@");
+});
+
+test("excluded and required characters", () => {
+  const gen = Generator.fromPolicy(JSON.stringify({ length: 16, usable: "ln", exclude: "abc", require: "xyz9" }));
+  for (let i = 0; i < 100; i++) {
+    const password = gen.generate();
+    for (const c of "abc") {
+      assert.ok(!password.includes(c), `excluded character ${c} in ${password}`);
+    }
+    for (const c of "xyz9") {
+      assert.ok(password.includes(c), `required character ${c} not in ${password}`);
+    }
+  }
+});
+
+test("exclusion empties a category", () => {
+  assert.throws(
+    () => Generator.fromPolicy(JSON.stringify({ length: 8, usable: "ns", symbols: "!@", exclude: "!@" })),
+    /all characters are excluded from usable flags: s/
+  );
+});
+
+test("symbols safe for contexts", () => {
+  const password = Generator.fromPolicy(JSON.stringify({ length: 64, usable: "s", safe_for: "url,shell" })).generate();
+  assertOnly(password, "_-.");
+});
+
+test("generator from a JSON policy", () => {
+  const gen = Generator.fromPolicy(JSON.stringify({ length: 12, first: "l", last: "n" }));
+  for (let i = 0; i < 100; i++) {
+    const password = gen.generate();
+    assert.strictEqual(password.length, 12);
+    assert.ok(LOWER.includes(password[0]), password);
+    assert.ok(NUMBER.includes(password[11]), password);
+  }
+  assert.throws(() => Generator.fromPolicy('{"lenght": 12}'), /unknown field/);
+});
+
+test("length out of range", () => {
+  assert.throws(() => new Generator(7), /Needs 8 or more: 7/);
+  assert.throws(() => new Generator(256), /Needs 255 or less: 256/);
+});
+
+test("unknown usable flags", () => {
+  assert.throws(() => new Generator(8, "lx"), /unknown usable flags: x/);
+});
+
+test("unknown symbol character", () => {
+  assert.throws(() => new Generator(8, "s", false, "!a"), /unknown symbol character: a/);
+});
+
+let failed = 0;
+for (const [name, f] of tests) {
+  try {
+    f();
+    console.log(`ok     ${name}`);
+  } catch (e) {
+    failed++;
+    console.log(`FAILED ${name}\n${e.stack}`);
+  }
+}
+console.log(`\n${tests.length - failed} passed; ${failed} failed`);
+process.exit(failed === 0 ? 0 : 1);